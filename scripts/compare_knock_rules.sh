@@ -0,0 +1,32 @@
+#!/bin/sh
+# Checks that the risk-aware knock rule still out-scores the classic rule.
+# Plays seeded duplicate hands between the Challenging bot with its endgame
+# search turned off (so only the knock rule differs) and `heuristic-classic`,
+# and fails unless the whole 95% interval of the points margin is above zero.
+#
+# Usage: scripts/compare_knock_rules.sh [HANDS] [SEED]   (defaults: 2000 42)
+
+set -eu
+
+hands="${1:-2000}"
+seed="${2:-42}"
+dir="$(mktemp -d)"
+trap 'rm -rf "$dir"' EXIT
+
+printf 'name = "risk-aware"\nendgame_stock = 0\n' > "$dir/risk-aware.toml"
+
+cargo run --release --quiet --bin deadwood-sim -- \
+    --hands "$hands" --seed "$seed" --profile "$dir" \
+    --strategy risk-aware:challenging --strategy heuristic-classic:challenging \
+    --json > "$dir/report.json"
+
+margin="$(grep '"points_margin":' "$dir/report.json" | tr -d ' ,' | cut -d: -f2)"
+low="$(grep -A1 '"points_margin_ci":' "$dir/report.json" | tail -n 1 | tr -d ' ,')"
+
+echo "risk-aware vs classic over $hands hands (seed $seed): $margin points per hand, 95% CI from $low"
+if awk -v low="$low" 'BEGIN { exit !(low > 0) }'; then
+    echo "OK: the risk-aware knock rule scores more points than the classic rule."
+else
+    echo "FAIL: the risk-aware knock rule no longer beats the classic rule on points." >&2
+    exit 1
+fi
@@ -0,0 +1,43 @@
+#!/bin/sh
+# Minimal deadwood engine used by the engine protocol tests.
+# It always draws from the stock, discards the first card in hand and never knocks.
+#
+# Modes (first argument):
+#   play     answer every request (default)
+#   illegal  answer every request with an unknown card
+#   silent   complete the handshake, then never answer `go`
+
+mode="${1:-play}"
+first=""
+
+while IFS= read -r line; do
+    set -- $line
+    case "$1" in
+        deadwood)
+            echo "id name mock"
+            ;;
+        isready)
+            echo "ready"
+            ;;
+        position)
+            first="$3"
+            ;;
+        go)
+            case "$mode" in
+                silent) ;;
+                illegal) echo "discard ZZ" ;;
+                *)
+                    if [ "$2" = "draw" ]; then
+                        echo "info stock is as good as anything"
+                        echo "draw stock"
+                    else
+                        echo "discard $first"
+                    fi
+                    ;;
+            esac
+            ;;
+        quit)
+            exit 0
+            ;;
+    esac
+done
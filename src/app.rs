@@ -1,13 +1,35 @@
+use std::{
+    collections::BTreeMap,
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+
 use anyhow::{Context, Result};
+use chrono::NaiveDate;
 use crossterm::event::{KeyCode, KeyEvent};
 use ratatui::style::Color;
 
 use crate::{
-    bot::{take_turn, BotDifficulty},
+    archive::{self, HandRecord},
+    bot::{
+        self, take_step, BotDifficulty, Hint, Strategy, StrategyRegistry, TurnTrace,
+        DEFAULT_STRATEGY,
+    },
     cards::{Card, Suit},
-    config::{Config, ConfigLoadOutcome},
-    game::{ActionOutcome, DrawSource, Game, PlayerId, TurnPhase},
-    storage::{self, Paths, RoundSummary, SessionData},
+    config::{Config, ConfigLoadOutcome, ConfigOverrides, ConfigSource, EffectiveConfig},
+    daily::{self, DailyResult, Leaderboard},
+    engine::{EngineStrategy, ENGINE_STRATEGY},
+    game::{
+        ActionOutcome, DrawSource, Game, Move, PlayerId, RoundResult, Rules, Scoreboard, TurnPhase,
+    },
+    keys::Action,
+    notation::{self, NotatedHand},
+    params::BotParams,
+    puzzle::{self, Answer, PuzzleProgress, PuzzleRun, Question},
+    replay::Replay,
+    settings::SettingsScreen,
+    stats::StatsReport,
+    storage::{self, Paths, RoundSummary, SessionData, DEFAULT_PROFILE},
 };
 
 const EXIT_PROMPT_MESSAGE: &str =
@@ -19,6 +41,65 @@ enum ExitPrompt {
     SaveBeforeQuit,
 }
 
+/// Settings supplied on the command line that take precedence over the config file.
+#[derive(Debug, Clone, Default)]
+pub struct LaunchOptions {
+    pub difficulty: Option<BotDifficulty>,
+    /// Profile to play as; the default profile when unset.
+    pub profile: Option<String>,
+    /// Seed for the shuffles, so a session's deals can be repeated.
+    pub seed: Option<u64>,
+    pub rules: Option<Rules>,
+    /// Shared config file to read instead of the one in the data directory.
+    pub config_file: Option<PathBuf>,
+    /// Directory for profiles, sessions and archives instead of the OS default.
+    pub data_dir: Option<PathBuf>,
+    /// Neither load nor save the session or the hand archive.
+    pub no_persist: bool,
+    /// Hand file (text notation) to open in the replay viewer.
+    pub replay: Option<PathBuf>,
+    /// Puzzle file (TOML or JSON) to start in puzzle mode.
+    pub puzzles: Option<PathBuf>,
+}
+
+impl LaunchOptions {
+    /// The flags that change settings, as the top layer of the configuration.
+    pub fn config_overrides(&self) -> ConfigOverrides {
+        let mut overrides = ConfigOverrides::default();
+        if let Some(difficulty) = self.difficulty {
+            overrides.set("--difficulty", "bot_difficulty", difficulty.name());
+        }
+        if let Some(rules) = self.rules {
+            overrides.set("--rules", "rules", rules.name());
+        }
+        if self.no_persist {
+            overrides.set("--no-persist", "persist_stats", false);
+            overrides.set("--no-persist", "archive_hands", false);
+        }
+        overrides
+    }
+}
+
+/// The built-in strategies plus the bot profiles in the bots directory and
+/// the configured external engine, with a warning for each unreadable profile.
+pub fn strategy_registry(config: &Config, paths: &Paths) -> (StrategyRegistry, Vec<String>) {
+    let mut registry = StrategyRegistry::builtin();
+    let (profiles, mut warnings) = BotParams::load_dir(paths.bots_dir());
+    for profile in profiles {
+        if let Err(err) = registry.register_profile(profile) {
+            warnings.push(format!("{err}."));
+        }
+    }
+    if let Some(engine) = config.engine().cloned() {
+        registry.register(
+            ENGINE_STRATEGY,
+            "External engine speaking the deadwood text protocol.",
+            move |difficulty| Ok(Box::new(EngineStrategy::spawn(&engine, difficulty)?)),
+        );
+    }
+    (registry, warnings)
+}
+
 pub struct App {
     should_quit: bool,
     pub game: Game,
@@ -27,29 +108,126 @@ pub struct App {
     error: Option<String>,
     knock_intent: bool,
     bot_difficulty: BotDifficulty,
+    bot: Box<dyn Strategy>,
+    registry: StrategyRegistry,
+    /// Totals for the difficulties that are not currently being played.
+    other_scoreboards: BTreeMap<BotDifficulty, Scoreboard>,
+    difficulty_picker: Option<usize>,
+    /// Bot reasoning for each of its turns this round, oldest first.
+    bot_traces: Vec<TurnTrace>,
+    trace_view: Option<usize>,
+    stats_view: Option<StatsReport>,
+    /// Date shown in the daily deal overlay, while it is open.
+    daily_view: Option<NaiveDate>,
+    daily: Leaderboard,
+    /// Set while the current round is the daily deal for this date.
+    daily_round: Option<NaiveDate>,
+    replay: Option<Replay>,
+    /// Position in `hand_records` of the hand being replayed, if it came from there.
+    replay_index: Option<usize>,
+    /// Every archived hand plus those finished this session, oldest first.
+    hand_records: Vec<HandRecord>,
+    puzzle_run: Option<PuzzleRun>,
+    puzzle_progress: PuzzleProgress,
+    hint: Option<Hint>,
+    hints_this_round: u32,
     config: Config,
+    /// Where each setting came from, for the settings screen.
+    config_sources: EffectiveConfig,
+    settings: Option<SettingsScreen>,
     paths: Paths,
     exit_prompt: Option<ExitPrompt>,
+    /// Set by `--no-persist`: quit without offering to save.
+    no_persist: bool,
+    /// Whether this run has already moved the previous session into the backups.
+    session_rotated: bool,
     round_history: Vec<RoundSummary>,
     recent_draw: Option<Card>,
+    /// Pause before each half of the bot's turn; zero plays it instantly.
+    bot_delay: Duration,
+    /// When the bot's next draw or discard is due.
+    bot_step_due: Option<Instant>,
+    /// Set by a keypress to finish the bot's current turn without pausing.
+    skip_bot_animation: bool,
     show_help: bool,
 }
 
 impl App {
-    pub fn new() -> Result<Self> {
-        let game = Game::new().context("failed to initialise game")?;
-        let paths = Paths::new().context("failed to prepare application directories")?;
+    pub fn new(options: LaunchOptions) -> Result<Self> {
+        let game = match options.seed {
+            Some(seed) => Game::with_seed(seed),
+            None => Game::new(),
+        }
+        .context("failed to initialise game")?;
+        let profile = options.profile.as_deref().unwrap_or(DEFAULT_PROFILE);
+        let mut paths = Paths::new(profile, options.data_dir.as_deref())
+            .context("failed to prepare application directories")?;
+        if let Some(config_file) = &options.config_file {
+            paths = paths.with_config_file(config_file.clone());
+        }
         let ConfigLoadOutcome {
             config,
             created,
-            warnings,
-        } = Config::load_or_create(paths.config_file()).context("failed to load configuration")?;
+            mut warnings,
+            effective,
+        } = Config::load_or_create(&paths, &options.config_overrides())
+            .context("failed to load configuration")?;
+
+        let bot_delay = config.bot_delay();
+        let bot_difficulty = config.bot_difficulty();
+        let (registry, mut profile_warnings) = strategy_registry(&config, &paths);
+        warnings.append(&mut profile_warnings);
+        let bot = match registry.create(config.bot_strategy(), bot_difficulty) {
+            Ok(bot) => bot,
+            Err(err) => {
+                warnings.push(format!("{err}. Using {DEFAULT_STRATEGY}."));
+                registry.create(DEFAULT_STRATEGY, bot_difficulty)?
+            }
+        };
 
         let mut session_data: Option<SessionData> = None;
         let mut session_errors = Vec::new();
+        let mut hand_records = Vec::new();
+        if config.archive_hands() {
+            match archive::load(paths.archive_file()) {
+                Ok(contents) => {
+                    if !contents.skipped.is_empty() {
+                        session_errors.push(format!(
+                            "Skipped {} unreadable hand(s) in {} ({}).",
+                            contents.skipped.len(),
+                            paths.archive_file().display(),
+                            contents.skipped[0]
+                        ));
+                    }
+                    hand_records = contents.records;
+                }
+                Err(err) => session_errors.push(format!("Failed to load hand archive: {err:#}")),
+            }
+        }
+
+        let daily = Leaderboard::load(paths.daily_file()).unwrap_or_else(|err| {
+            session_errors.push(format!(
+                "{err:#}. Starting a new leaderboard; the old file will be kept as {}.",
+                storage::aside_path(paths.daily_file()).display()
+            ));
+            Leaderboard::replacing_unreadable()
+        });
+
+        let puzzle_progress =
+            PuzzleProgress::load(paths.puzzle_progress_file()).unwrap_or_else(|err| {
+                session_errors.push(format!(
+                    "{err:#}. Starting over; the old file will be kept as {}.",
+                    storage::aside_path(paths.puzzle_progress_file()).display()
+                ));
+                PuzzleProgress::replacing_unreadable()
+            });
+
         if config.persist_stats() {
             match storage::load_session(paths.session_file()) {
-                Ok(Some(data)) => session_data = Some(data),
+                Ok(Some(loaded)) => {
+                    session_errors.extend(loaded.warnings);
+                    session_data = Some(loaded.data);
+                }
                 Ok(None) => {}
                 Err(err) => session_errors.push(format!("Failed to load session data: {err}")),
             }
@@ -62,12 +240,36 @@ impl App {
             message: None,
             error: None,
             knock_intent: false,
-            bot_difficulty: BotDifficulty::Challenging,
+            bot_difficulty,
+            bot,
+            registry,
+            other_scoreboards: BTreeMap::new(),
+            difficulty_picker: None,
+            bot_traces: Vec::new(),
+            trace_view: None,
+            stats_view: None,
+            daily_view: None,
+            daily,
+            daily_round: None,
+            replay: None,
+            replay_index: None,
+            hand_records,
+            puzzle_run: None,
+            puzzle_progress,
+            hint: None,
+            hints_this_round: 0,
             config,
+            config_sources: effective,
+            settings: None,
             paths,
             exit_prompt: None,
+            no_persist: options.no_persist,
+            session_rotated: false,
             round_history: Vec::new(),
             recent_draw: None,
+            bot_delay,
+            bot_step_due: None,
+            skip_bot_animation: false,
             show_help: false,
         };
 
@@ -80,7 +282,11 @@ impl App {
         }
 
         if let Some(data) = session_data {
-            app.game.scoreboard = data.scoreboard;
+            app.other_scoreboards = data.scoreboards_by_difficulty();
+            app.game.scoreboard = app
+                .other_scoreboards
+                .remove(&bot_difficulty)
+                .unwrap_or_default();
             let mut history = data.round_history;
             if history.len() > MAX_ROUND_HISTORY {
                 let start = history.len() - MAX_ROUND_HISTORY;
@@ -88,8 +294,9 @@ impl App {
             }
             app.round_history = history;
             info_messages.push(format!(
-                "Loaded session data ({} rounds).",
-                app.game.scoreboard.rounds_played
+                "Loaded session data ({} rounds on {}).",
+                app.game.scoreboard.rounds_played,
+                bot_difficulty.label()
             ));
         }
 
@@ -109,6 +316,39 @@ impl App {
             ));
         }
 
+        app.bot.on_round_start(&app.game, PlayerId::Bot);
+
+        if let Some(path) = &options.replay {
+            let opened = notation::read_file(path).and_then(|hand| {
+                let title = path
+                    .file_name()
+                    .map(|name| name.to_string_lossy().into_owned())
+                    .unwrap_or_default();
+                Replay::new(hand.to_record()?, title)
+            });
+            match opened {
+                Ok(replay) => {
+                    app.replay = Some(replay);
+                    info_messages.push(format!("Replaying {}.", path.display()));
+                }
+                Err(err) => session_errors.push(format!("Failed to open replay: {err:#}")),
+            }
+        }
+
+        if let Some(path) = &options.puzzles {
+            match puzzle::load_file(path).and_then(PuzzleRun::new) {
+                Ok(run) => {
+                    info_messages.push(format!(
+                        "Loaded {} puzzle(s) from {}.",
+                        run.puzzles().len(),
+                        path.display()
+                    ));
+                    app.puzzle_run = Some(run);
+                }
+                Err(err) => session_errors.push(format!("Failed to open puzzles: {err:#}")),
+            }
+        }
+
         if !info_messages.is_empty() {
             app.message = Some(info_messages.join(" "));
         }
@@ -142,7 +382,11 @@ impl App {
     }
 
     pub fn update(&mut self) -> Result<()> {
-        if self.show_help {
+        if self.show_help
+            || self.settings.is_some()
+            || self.replay.is_some()
+            || self.puzzle_run.is_some()
+        {
             return Ok(());
         }
 
@@ -150,11 +394,35 @@ impl App {
             return Ok(());
         }
 
-        while self.game.phase != TurnPhase::RoundOver && self.game.current_player == PlayerId::Bot {
-            match take_turn(&mut self.game, self.bot_difficulty)? {
+        while self.bot_turn_in_progress() {
+            if !self.skip_bot_animation && !self.bot_delay.is_zero() {
+                let now = Instant::now();
+                match self.bot_step_due {
+                    None => {
+                        self.bot_step_due = Some(now + self.bot_delay);
+                        return Ok(());
+                    }
+                    Some(due) if now < due => return Ok(()),
+                    Some(_) => {}
+                }
+            }
+            self.bot_step_due = None;
+
+            let drawing = self.game.phase == TurnPhase::AwaitDraw;
+            let outcome = take_step(&mut self.game, PlayerId::Bot, self.bot.as_mut())?;
+            if drawing && outcome == ActionOutcome::Continue {
+                self.message = Some(self.describe_bot_draw());
+                continue;
+            }
+
+            if let Some(trace) = self.bot.last_trace() {
+                self.bot_traces.push(trace.clone());
+            }
+            self.skip_bot_animation = false;
+            match outcome {
                 ActionOutcome::Continue => {
-                    if self.game.current_player != PlayerId::Bot {
-                        break;
+                    if let Some(card) = self.game.discard.last() {
+                        self.message = Some(format!("Bot discarded the {card}. Your turn."));
                     }
                 }
                 ActionOutcome::RoundEnded => {
@@ -166,50 +434,135 @@ impl App {
         Ok(())
     }
 
+    fn bot_turn_in_progress(&self) -> bool {
+        self.game.phase != TurnPhase::RoundOver && self.game.current_player == PlayerId::Bot
+    }
+
+    fn describe_bot_draw(&self) -> String {
+        match self.game.moves.last() {
+            Some(Move::Draw {
+                source: DrawSource::Discard,
+                card,
+                ..
+            }) => format!("Bot took the {card} from the discard pile."),
+            _ => "Bot drew from the stock.".to_string(),
+        }
+    }
+
     pub fn handle_key(&mut self, key_event: KeyEvent) -> Result<()> {
         if self.show_help {
-            match key_event.code {
-                KeyCode::Esc | KeyCode::Char('?') => {
-                    self.show_help = false;
-                    self.message = Some("Returned to the game.".to_string());
-                }
-                _ => {}
+            if self.closes(Action::Rules, &key_event) {
+                self.show_help = false;
+                self.message = Some("Returned to the game.".to_string());
             }
             return Ok(());
         }
 
+        if self.settings.is_some() {
+            self.handle_settings(key_event);
+            return Ok(());
+        }
+
         if self.process_exit_prompt(key_event)? {
             return Ok(());
         }
 
-        if let KeyCode::Char('?') = key_event.code {
-            self.show_help = true;
-            self.message = Some("Gin Rummy rules open. Press Esc or ? to close.".to_string());
+        // Esc leaves puzzle mode rather than quitting.
+        if self.puzzle_run.is_some()
+            && (key_event.code == KeyCode::Esc
+                || !(self.pressed(Action::Quit, &key_event)
+                    || self.pressed(Action::Rules, &key_event)))
+        {
+            self.handle_puzzle(key_event)?;
             return Ok(());
         }
 
-        match key_event.code {
-            KeyCode::Char('q') | KeyCode::Esc => {
-                self.request_exit()?;
+        // Any key hurries the bot along; only quitting and the rules stay available.
+        if self.bot_turn_in_progress() && self.exit_prompt.is_none() {
+            self.skip_bot_animation = true;
+            if ![Action::Quit, Action::Rules, Action::Settings]
+                .into_iter()
+                .any(|action| self.pressed(action, &key_event))
+            {
                 return Ok(());
             }
-            _ => {}
+        }
+
+        if self.difficulty_picker.is_some() {
+            self.handle_difficulty_picker(key_event)?;
+            return Ok(());
+        }
+
+        if self.trace_view.is_some() {
+            self.handle_trace_view(key_event);
+            return Ok(());
+        }
+
+        // Quitting and the rules stay available over a replay; Esc only closes it.
+        if self.replay.is_some()
+            && (key_event.code == KeyCode::Esc
+                || !(self.pressed(Action::Quit, &key_event)
+                    || self.pressed(Action::Rules, &key_event)))
+        {
+            self.handle_replay(key_event);
+            return Ok(());
+        }
+
+        if self.daily_view.is_some() {
+            self.handle_daily_view(key_event)?;
+            return Ok(());
+        }
+
+        if self.stats_view.is_some() {
+            if self.closes(Action::Stats, &key_event) {
+                self.stats_view = None;
+                self.message = Some("Returned to the round summary.".to_string());
+            }
+            return Ok(());
+        }
+
+        if self.pressed(Action::Rules, &key_event) {
+            self.show_help = true;
+            self.message = Some(format!(
+                "Gin Rummy rules open. Press Esc or {} to close.",
+                self.config.keys().primary(Action::Rules)
+            ));
+            return Ok(());
+        }
+
+        if self.pressed(Action::Settings, &key_event) {
+            self.settings = Some(SettingsScreen::new(&self.config));
+            self.message = Some("Settings open. Changes show immediately.".to_string());
+            return Ok(());
+        }
+
+        if self.pressed(Action::Quit, &key_event) {
+            self.request_exit()?;
+            return Ok(());
         }
 
         self.error = None;
 
         if self.game.phase == TurnPhase::RoundOver {
-            match key_event.code {
-                KeyCode::Enter | KeyCode::Char('n') => {
-                    self.reset_messages();
-                    self.game.start_next_round()?;
-                    self.selection = 0;
-                    self.knock_intent = false;
-                    self.recent_draw = None;
-                    self.message = Some("New round started.".to_string());
-                    self.update()?;
-                }
-                _ => {}
+            let pressed = |action| self.pressed(action, &key_event);
+            if pressed(Action::NextRound) {
+                self.reset_messages();
+                self.game.start_next_round()?;
+                self.begin_round("New round started.")?;
+            } else if pressed(Action::DailyDeal) {
+                self.open_daily_view();
+            } else if pressed(Action::Puzzles) {
+                self.open_puzzles();
+            } else if pressed(Action::BotDifficulty) {
+                self.open_difficulty_picker();
+            } else if pressed(Action::BotReasoning) {
+                self.open_trace_view();
+            } else if pressed(Action::Stats) {
+                self.open_stats_view();
+            } else if pressed(Action::Export) {
+                self.export_last_hand();
+            } else if pressed(Action::Replay) {
+                self.open_archived_replay(self.hand_records.len().checked_sub(1));
             }
             return Ok(());
         }
@@ -218,6 +571,11 @@ impl App {
             return Ok(());
         }
 
+        if self.pressed(Action::Hint, &key_event) {
+            self.show_hint();
+            return Ok(());
+        }
+
         match self.game.phase {
             TurnPhase::AwaitDraw => self.handle_draw_phase(key_event)?,
             TurnPhase::AwaitDiscard => self.handle_discard_phase(key_event)?,
@@ -227,6 +585,67 @@ impl App {
         Ok(())
     }
 
+    fn handle_settings(&mut self, key_event: KeyEvent) {
+        let close = self.closes(Action::Settings, &key_event);
+        let pressed = |action| self.pressed(action, &key_event);
+        let (up, down) = (pressed(Action::MoveUp), pressed(Action::MoveDown));
+        let (left, right) = (pressed(Action::MoveLeft), pressed(Action::MoveRight));
+        let save = pressed(Action::Discard);
+        let Some(screen) = self.settings.as_mut() else {
+            return;
+        };
+        if close {
+            self.config = screen.original().clone();
+            self.settings = None;
+            self.message = Some("Settings unchanged.".to_string());
+        } else if up || down {
+            screen.move_selection(down);
+        } else if left || right {
+            screen.adjust(&mut self.config, right);
+        } else if save {
+            self.save_settings();
+            return;
+        }
+        self.bot_delay = self.config.bot_delay();
+    }
+
+    /// Writes the settings changed on the settings screen to the shared config
+    /// file and closes the screen.
+    fn save_settings(&mut self) {
+        let Some(screen) = self.settings.take() else {
+            return;
+        };
+        if screen.changed().is_empty() {
+            self.message = Some("No settings changed.".to_string());
+            return;
+        }
+        let path = self.paths.config_file();
+        if let Err(err) = screen.save(&self.config, path) {
+            self.error = Some(format!("Failed to save settings: {err:#}"));
+            self.settings = Some(screen);
+            return;
+        }
+        let overridden: Vec<String> = screen
+            .changed()
+            .iter()
+            .filter_map(|key| {
+                let source = self.config_sources.source(key)?;
+                source
+                    .overrides_user_file()
+                    .then(|| format!("{key} ({source})"))
+            })
+            .collect();
+        self.message = Some(if overridden.is_empty() {
+            format!("Saved settings to {}.", path.display())
+        } else {
+            format!(
+                "Saved settings to {}. Next launch still takes {} from a higher-priority source.",
+                path.display(),
+                overridden.join(", ")
+            )
+        });
+    }
+
     fn process_exit_prompt(&mut self, key_event: KeyEvent) -> Result<bool> {
         if self.exit_prompt.is_none() {
             return Ok(false);
@@ -251,7 +670,9 @@ impl App {
     }
 
     fn request_exit(&mut self) -> Result<()> {
-        if self.config.persist_stats() {
+        if self.no_persist {
+            self.should_quit = true;
+        } else if self.config.persist_stats() {
             self.save_and_quit()?;
         } else {
             self.exit_prompt = Some(ExitPrompt::SaveBeforeQuit);
@@ -276,35 +697,459 @@ impl App {
     }
 
     fn save_session_data(&mut self) -> Result<()> {
-        let data = SessionData::new(self.game.scoreboard.clone(), self.round_history.clone());
-        storage::save_session(self.paths.session_file(), &data)
+        let mut scoreboards = self.other_scoreboards.clone();
+        scoreboards.insert(self.bot_difficulty, self.game.scoreboard.clone());
+        let data = SessionData::new(self.bot_difficulty, scoreboards, self.round_history.clone());
+        storage::save_session(self.paths.session_file(), &data, !self.session_rotated)?;
+        self.session_rotated = true;
+        Ok(())
     }
 
-    fn handle_draw_phase(&mut self, key_event: KeyEvent) -> Result<()> {
-        match key_event.code {
-            KeyCode::Char(c) if c.eq_ignore_ascii_case(&'s') => {
-                self.execute_draw(DrawSource::Stock)?
+    fn open_trace_view(&mut self) {
+        if self.bot_traces.is_empty() {
+            self.message = Some("The bot made no decisions this round.".to_string());
+            return;
+        }
+        self.trace_view = Some(self.bot_traces.len() - 1);
+        self.message = Some("Showing the bot's reasoning for its last turn.".to_string());
+    }
+
+    /// Resets per-round state after the game has dealt a new round.
+    fn begin_round(&mut self, message: &str) -> Result<()> {
+        self.bot.on_round_start(&self.game, PlayerId::Bot);
+        self.bot_traces.clear();
+        self.hints_this_round = 0;
+        self.selection = 0;
+        self.knock_intent = false;
+        self.recent_draw = None;
+        self.message = Some(message.to_string());
+        self.update()
+    }
+
+    fn open_daily_view(&mut self) {
+        self.daily_view = Some(daily::today());
+        self.message = Some("Showing the daily deal.".to_string());
+    }
+
+    fn handle_daily_view(&mut self, key_event: KeyEvent) -> Result<()> {
+        let Some(date) = self.daily_view else {
+            return Ok(());
+        };
+        if self.pressed(Action::NextRound, &key_event) {
+            if self.daily.result(date, self.paths.profile()).is_some() {
+                self.message =
+                    Some("You have already played today's deal. Come back tomorrow.".to_string());
+                return Ok(());
+            }
+            self.daily_view = None;
+            self.reset_messages();
+            self.game
+                .start_seeded_round(daily::seed_for(date), daily::DAILY_DEALER)?;
+            self.daily_round = Some(date);
+            // Record the attempt now, so quitting mid-hand cannot replay the deal.
+            let started = DailyResult::started(self.bot_difficulty, archive::unix_now());
+            self.daily.record(date, self.paths.profile(), started);
+            self.save_daily();
+            self.begin_round(&format!("Daily deal for {date} started. Good luck!"))?;
+        } else if self.closes(Action::DailyDeal, &key_event) {
+            self.daily_view = None;
+            self.message = Some("Returned to the round summary.".to_string());
+        }
+        Ok(())
+    }
+
+    /// Adds the daily hand that just finished to the leaderboard.
+    fn record_daily(&mut self, date: NaiveDate, result: &RoundResult) {
+        let points = match result.winner {
+            Some(PlayerId::Bot) => -result.points_awarded,
+            _ => result.points_awarded,
+        };
+        let entry = DailyResult {
+            winner: result.winner,
+            points,
+            difficulty: self.bot_difficulty,
+            timestamp: archive::unix_now(),
+            finished: true,
+        };
+        self.daily.record(date, self.paths.profile(), entry);
+        self.save_daily();
+    }
+
+    /// Writes the leaderboard, unless `--no-persist` keeps this run off the disk.
+    fn save_daily(&mut self) {
+        if self.no_persist {
+            return;
+        }
+        if let Err(err) = self.daily.save(self.paths.daily_file()) {
+            self.error = Some(format!("Failed to save the daily leaderboard: {err:#}"));
+        }
+    }
+
+    /// Writes the hand that just finished to the profile's exports directory.
+    fn export_last_hand(&mut self) {
+        let Some(record) = self.hand_records.last() else {
+            self.message = Some("No finished hand to export yet.".to_string());
+            return;
+        };
+        let hand = NotatedHand::from_record(record, self.paths.profile());
+        let path = self
+            .paths
+            .exports_dir()
+            .join(format!("hand-{}-{}.txt", record.timestamp, record.seed));
+        match notation::write_file(&path, &hand) {
+            Ok(()) => self.message = Some(format!("Exported the hand to {}.", path.display())),
+            Err(err) => self.error = Some(format!("Failed to export the hand: {err:#}")),
+        }
+    }
+
+    /// Opens the replay viewer on the archived hand at `index`.
+    fn open_archived_replay(&mut self, index: Option<usize>) {
+        let Some((index, record)) =
+            index.and_then(|index| Some((index, self.hand_records.get(index)?.clone())))
+        else {
+            self.message = Some("No recorded hand to replay yet.".to_string());
+            return;
+        };
+        let title = format!("hand {} of {}", index + 1, self.hand_records.len());
+        match Replay::new(record, title) {
+            Ok(replay) => {
+                self.message = Some(format!("Replaying {}.", replay.title()));
+                self.replay = Some(replay);
+                self.replay_index = Some(index);
             }
-            KeyCode::Char(c) if c.eq_ignore_ascii_case(&'d') => {
-                self.execute_draw(DrawSource::Discard)?
+            Err(err) => self.error = Some(format!("Failed to replay the hand: {err:#}")),
+        }
+    }
+
+    fn handle_replay(&mut self, key_event: KeyEvent) {
+        if self.closes(Action::Replay, &key_event) {
+            self.replay = None;
+            self.replay_index = None;
+            self.message = Some("Closed the replay.".to_string());
+            return;
+        }
+        if self.pressed(Action::ReplayPreviousHand, &key_event) {
+            if let Some(index) = self.replay_index.filter(|&index| index > 0) {
+                self.open_archived_replay(Some(index - 1));
             }
-            _ => {}
+            return;
+        }
+        if self.pressed(Action::ReplayNextHand, &key_event) {
+            if let Some(index) = self.replay_index {
+                if index + 1 < self.hand_records.len() {
+                    self.open_archived_replay(Some(index + 1));
+                }
+            }
+            return;
+        }
+        let keys = self.config.keys();
+        let Some(replay) = self.replay.as_mut() else {
+            return;
+        };
+        if keys.pressed(Action::ReplayBack, &key_event) {
+            replay.back();
+        } else if keys.pressed(Action::ReplayForward, &key_event) {
+            replay.forward();
+        } else if keys.pressed(Action::ReplayFirst, &key_event) {
+            replay.first();
+        } else if keys.pressed(Action::ReplayLast, &key_event) {
+            replay.last();
+        } else if keys.pressed(Action::ReplayShowBot, &key_event) {
+            replay.toggle_face_up();
+        }
+    }
+
+    /// Starts puzzle mode with every puzzle in the puzzles directory.
+    fn open_puzzles(&mut self) {
+        let (puzzles, warnings) = puzzle::load_dir(self.paths.puzzles_dir());
+        if !warnings.is_empty() {
+            self.error = Some(warnings.join(" "));
+        }
+        if puzzles.is_empty() {
+            self.message = Some(format!(
+                "No puzzles found. Add TOML or JSON puzzle files to {}.",
+                self.paths.puzzles_dir().display()
+            ));
+            return;
+        }
+        match PuzzleRun::new(puzzles) {
+            Ok(run) => {
+                self.message = Some(format!("Loaded {} puzzle(s).", run.puzzles().len()));
+                self.puzzle_run = Some(run);
+                self.selection = 0;
+                self.knock_intent = false;
+            }
+            Err(err) => self.error = Some(format!("Failed to open puzzles: {err:#}")),
+        }
+    }
+
+    fn handle_puzzle(&mut self, key_event: KeyEvent) -> Result<()> {
+        let leave = self.closes(Action::Puzzles, &key_event);
+        let draw_stock = self.pressed(Action::DrawStock, &key_event);
+        let draw_discard = self.pressed(Action::DrawDiscard, &key_event);
+        let discard = self.pressed(Action::Discard, &key_event);
+        let next = self.pressed(Action::NextRound, &key_event);
+        let retry = self.pressed(Action::Replay, &key_event);
+        let Some(run) = self.puzzle_run.as_mut() else {
+            return Ok(());
+        };
+        if leave {
+            self.puzzle_run = None;
+            self.selection = 0;
+            self.knock_intent = false;
+            self.message = Some("Left puzzle mode.".to_string());
+            return Ok(());
+        }
+
+        if run.verdict().is_some() {
+            if next {
+                if run.advance()? {
+                    self.selection = 0;
+                    self.knock_intent = false;
+                    self.message = Some(format!(
+                        "Puzzle {} of {}.",
+                        run.number(),
+                        run.puzzles().len()
+                    ));
+                } else {
+                    self.message = Some(format!(
+                        "That was the last puzzle. {}",
+                        self.describe_puzzle_progress()
+                    ));
+                }
+            } else if retry {
+                run.retry();
+                self.knock_intent = false;
+                self.message = Some("Try the puzzle again.".to_string());
+            }
+            return Ok(());
+        }
+
+        let answer = match run.puzzle().question() {
+            Question::Draw if draw_stock => Answer::Draw(DrawSource::Stock),
+            Question::Draw if draw_discard => Answer::Draw(DrawSource::Discard),
+            Question::Discard if discard => {
+                let hand = &run.game().human.hand;
+                Answer::Discard {
+                    card: hand[self.selection.min(hand.len() - 1)],
+                    knock: self.knock_intent,
+                }
+            }
+            Question::Discard => {
+                self.handle_discard_phase(key_event)?;
+                return Ok(());
+            }
+            Question::Draw => return Ok(()),
+        };
+        self.answer_puzzle(answer);
+        Ok(())
+    }
+
+    /// Grades an answer to the current puzzle and records it in the profile's progress.
+    fn answer_puzzle(&mut self, answer: Answer) {
+        let Some(run) = self.puzzle_run.as_mut() else {
+            return;
+        };
+        let name = run.puzzle().name.clone();
+        let Some(verdict) = run.answer(answer) else {
+            return;
+        };
+        let correct = verdict.correct;
+        let explanation = verdict.explanation.clone();
+        self.puzzle_progress.record(&name, correct);
+        if !self.no_persist {
+            if let Err(err) = self.puzzle_progress.save(self.paths.puzzle_progress_file()) {
+                self.error = Some(format!("Failed to save puzzle progress: {err:#}"));
+            }
+        }
+        let opening = if correct { "Correct!" } else { "Not quite." };
+        self.message = Some(format!(
+            "{opening} {explanation} {}",
+            self.describe_puzzle_progress()
+        ));
+    }
+
+    fn describe_puzzle_progress(&self) -> String {
+        let Some(run) = &self.puzzle_run else {
+            return String::new();
+        };
+        let summary = self.puzzle_progress.summary(run.puzzles());
+        let rate = summary
+            .solve_rate()
+            .map_or("no attempts".to_string(), |rate| {
+                format!("{:.0}% of attempts correct", rate * 100.0)
+            });
+        format!(
+            "Solved {} of {} puzzles, {} on the first try ({rate}).",
+            summary.solved, summary.puzzles, summary.first_try
+        )
+    }
+
+    fn open_stats_view(&mut self) {
+        self.stats_view = Some(StatsReport::from_records(&self.hand_records));
+        self.message = Some("Showing statistics for every recorded hand.".to_string());
+    }
+
+    fn handle_trace_view(&mut self, key_event: KeyEvent) {
+        let Some(index) = self.trace_view else {
+            return;
+        };
+        if self.pressed(Action::MoveLeft, &key_event) {
+            self.trace_view = Some(index.saturating_sub(1));
+        } else if self.pressed(Action::MoveRight, &key_event) {
+            let last = self.bot_traces.len().saturating_sub(1);
+            self.trace_view = Some((index + 1).min(last));
+        } else if self.closes(Action::BotReasoning, &key_event) {
+            self.trace_view = None;
+            self.message = Some("Returned to the round summary.".to_string());
+        }
+    }
+
+    fn open_difficulty_picker(&mut self) {
+        let current = BotDifficulty::ALL
+            .iter()
+            .position(|&difficulty| difficulty == self.bot_difficulty)
+            .unwrap_or(0);
+        self.difficulty_picker = Some(current);
+        self.message = Some("Choose the bot difficulty for the next round.".to_string());
+    }
+
+    fn handle_difficulty_picker(&mut self, key_event: KeyEvent) -> Result<()> {
+        let Some(index) = self.difficulty_picker else {
+            return Ok(());
+        };
+        let count = BotDifficulty::ALL.len();
+        let pressed = |action| self.pressed(action, &key_event);
+        if pressed(Action::MoveUp) || pressed(Action::MoveLeft) {
+            self.difficulty_picker = Some((index + count - 1) % count);
+        } else if pressed(Action::MoveDown) || pressed(Action::MoveRight) {
+            self.difficulty_picker = Some((index + 1) % count);
+        } else if pressed(Action::Discard) {
+            self.difficulty_picker = None;
+            self.set_difficulty(BotDifficulty::ALL[index])?;
+        } else if self.closes(Action::BotDifficulty, &key_event) {
+            self.difficulty_picker = None;
+            self.message = Some("Difficulty unchanged.".to_string());
+        }
+        Ok(())
+    }
+
+    /// Switches the bot to `difficulty`, swapping in that difficulty's scoreboard so
+    /// results from different levels are never combined.
+    fn set_difficulty(&mut self, difficulty: BotDifficulty) -> Result<()> {
+        if difficulty == self.bot_difficulty {
+            self.message = Some(format!("Bot difficulty stays {}.", difficulty.label()));
+            return Ok(());
+        }
+
+        // An engine that cannot be restarted should not end the game.
+        let strategy_name = self.bot.name().to_string();
+        self.bot = match self.registry.create(&strategy_name, difficulty) {
+            Ok(bot) => bot,
+            Err(err) => {
+                self.error = Some(format!("{err:#}. Using {DEFAULT_STRATEGY}."));
+                self.registry.create(DEFAULT_STRATEGY, difficulty)?
+            }
+        };
+
+        let previous = std::mem::take(&mut self.game.scoreboard);
+        self.other_scoreboards.insert(self.bot_difficulty, previous);
+        self.game.scoreboard = self
+            .other_scoreboards
+            .remove(&difficulty)
+            .unwrap_or_default();
+        self.bot_difficulty = difficulty;
+
+        let sb = &self.game.scoreboard;
+        self.message = Some(format!(
+            "Bot difficulty set to {}. Score on {}: You {} - Bot {}.",
+            difficulty.label(),
+            difficulty.label(),
+            sb.human,
+            sb.bot
+        ));
+        Ok(())
+    }
+
+    fn handle_draw_phase(&mut self, key_event: KeyEvent) -> Result<()> {
+        if self.pressed(Action::DrawStock, &key_event) {
+            self.execute_draw(DrawSource::Stock)?;
+        } else if self.pressed(Action::DrawDiscard, &key_event) {
+            self.execute_draw(DrawSource::Discard)?;
         }
         Ok(())
     }
 
     fn handle_discard_phase(&mut self, key_event: KeyEvent) -> Result<()> {
-        match key_event.code {
-            KeyCode::Left | KeyCode::Char('h') | KeyCode::Char('H') => self.move_selection_left(),
-            KeyCode::Right | KeyCode::Char('l') | KeyCode::Char('L') => self.move_selection_right(),
-            KeyCode::Char(c) if c.eq_ignore_ascii_case(&'k') => self.toggle_knock(),
-            KeyCode::Enter | KeyCode::Char(' ') => self.execute_discard()?,
-            _ => {}
+        if self.pressed(Action::MoveLeft, &key_event) {
+            self.move_selection_left();
+        } else if self.pressed(Action::MoveRight, &key_event) {
+            self.move_selection_right();
+        } else if self.pressed(Action::ToggleKnock, &key_event) {
+            self.toggle_knock();
+        } else if self.pressed(Action::Discard, &key_event) {
+            self.execute_discard()?;
         }
         Ok(())
     }
 
+    /// Whether `key_event` is one of the keys bound to `action`.
+    fn pressed(&self, action: Action, key_event: &KeyEvent) -> bool {
+        self.config.keys().pressed(action, key_event)
+    }
+
+    /// Overlays close with Esc or the key that opened them.
+    fn closes(&self, action: Action, key_event: &KeyEvent) -> bool {
+        key_event.code == KeyCode::Esc || self.pressed(action, key_event)
+    }
+
+    fn show_hint(&mut self) {
+        let Some(hint) = bot::suggest(&self.game, PlayerId::Human) else {
+            return;
+        };
+        self.hints_this_round += 1;
+        let advice = match &hint {
+            Hint::Draw(draw) => match (draw.top_discard, draw.deadwood_with_discard) {
+                (Some(card), Some(with_discard)) if with_discard <= draw.current_deadwood => {
+                    format!(
+                        "Take the {card} from the discard pile (deadwood {} -> {with_discard} before discarding).",
+                        draw.current_deadwood
+                    )
+                }
+                (Some(card), Some(with_discard)) => format!(
+                    "Draw from the stock: the {card} doesn't help (deadwood {} -> {with_discard}).",
+                    draw.current_deadwood
+                ),
+                _ => "Draw from the stock.".to_string(),
+            },
+            Hint::Discard {
+                index,
+                candidate,
+                knock,
+            } => {
+                self.selection = *index;
+                let knock_advice = if knock.knock {
+                    "Knocking is favourable."
+                } else if knock.deadwood_after_discard <= 10 {
+                    "You can knock, but it's risky; consider playing on."
+                } else {
+                    "Too much deadwood to knock."
+                };
+                format!(
+                    "Discard the {} (leaves {} deadwood). {knock_advice}",
+                    candidate.card, candidate.deadwood
+                )
+            }
+        };
+        self.hint = Some(hint);
+        self.message = Some(format!(
+            "Hint: {advice} ({} used this round)",
+            self.hints_this_round
+        ));
+    }
+
     fn execute_draw(&mut self, source: DrawSource) -> Result<()> {
+        self.hint = None;
         let previous_hand = self.game.human.hand.clone();
         match self.game.draw(PlayerId::Human, source) {
             Ok(ActionOutcome::Continue) => {
@@ -333,6 +1178,7 @@ impl App {
             return Ok(());
         }
 
+        self.hint = None;
         let index = self.selection.min(self.game.human.hand.len() - 1);
         match self.game.discard(PlayerId::Human, index, self.knock_intent) {
             Ok(ActionOutcome::Continue) => {
@@ -351,21 +1197,23 @@ impl App {
     }
 
     fn move_selection_left(&mut self) {
-        if self.game.human.hand.is_empty() {
+        let count = self.board().human.hand.len();
+        if count == 0 {
             return;
         }
         if self.selection == 0 {
-            self.selection = self.game.human.hand.len() - 1;
+            self.selection = count - 1;
         } else {
             self.selection -= 1;
         }
     }
 
     fn move_selection_right(&mut self) {
-        if self.game.human.hand.is_empty() {
+        let count = self.board().human.hand.len();
+        if count == 0 {
             return;
         }
-        self.selection = (self.selection + 1) % self.game.human.hand.len();
+        self.selection = (self.selection + 1) % count;
     }
 
     fn toggle_knock(&mut self) {
@@ -374,8 +1222,10 @@ impl App {
 
     fn on_round_end(&mut self) {
         if let Some(result) = self.game.pending_round.clone() {
+            let played_level = self.bot.effective_level();
+            self.bot.on_round_end(&self.game, PlayerId::Bot, &result);
             let sb = &self.game.scoreboard;
-            let summary = format!(
+            let mut summary = format!(
                 "Round {}: {} | Score: You {} - Bot {} | Hands: You {} Bot {} Draws {}",
                 sb.rounds_played,
                 result,
@@ -385,18 +1235,53 @@ impl App {
                 sb.bot_hands_won,
                 sb.draws
             );
+            if self.hints_this_round > 0 {
+                summary.push_str(&format!(" | Hints used: {}", self.hints_this_round));
+            }
+            if let (Some(played), Some(next)) = (played_level, self.bot.effective_level()) {
+                summary.push_str(&format!(
+                    " | Bot level: {:.0}% (next round {:.0}%)",
+                    played * 100.0,
+                    next * 100.0
+                ));
+            }
+            self.archive_round(&result);
+            if let Some(date) = self.daily_round.take() {
+                self.record_daily(date, &result);
+                summary.push_str(&format!(" | Daily deal for {date} recorded"));
+            }
             self.message = Some(summary.clone());
             self.record_round(summary);
         }
         self.selection = 0;
         self.knock_intent = false;
         self.recent_draw = None;
+        self.hint = None;
+    }
+
+    fn archive_round(&mut self, result: &RoundResult) {
+        let record = HandRecord::from_round(
+            &self.game,
+            result,
+            Some(self.bot_difficulty),
+            self.bot.name(),
+            self.hints_this_round,
+            self.config.rules(),
+        );
+        if self.config.archive_hands() {
+            if let Err(err) = archive::append(self.paths.archive_file(), &record) {
+                self.error = Some(format!("Failed to archive the hand: {err:#}"));
+            }
+        }
+        self.hand_records.push(record);
     }
 
     fn record_round(&mut self, summary: String) {
         let entry = RoundSummary {
             round_number: self.game.scoreboard.rounds_played,
             description: summary,
+            difficulty: Some(self.bot_difficulty),
+            hints_used: self.hints_this_round,
         };
         self.round_history.push(entry);
         if self.round_history.len() > MAX_ROUND_HISTORY {
@@ -408,6 +1293,53 @@ impl App {
         self.knock_intent
     }
 
+    pub fn profile(&self) -> &str {
+        self.paths.profile()
+    }
+
+    pub fn bot_name(&self) -> &str {
+        self.bot.name()
+    }
+
+    pub fn bot_difficulty(&self) -> BotDifficulty {
+        self.bot_difficulty
+    }
+
+    /// The adaptive bot's current strength (0.0–1.0), if it adapts.
+    pub fn bot_level(&self) -> Option<f64> {
+        self.bot.effective_level()
+    }
+
+    pub fn difficulty_picker(&self) -> Option<usize> {
+        self.difficulty_picker
+    }
+
+    /// The bot turn being inspected, with its 1-based number and the turn count.
+    pub fn trace_view(&self) -> Option<(&TurnTrace, usize, usize)> {
+        let index = self.trace_view?;
+        let trace = self.bot_traces.get(index)?;
+        Some((trace, index + 1, self.bot_traces.len()))
+    }
+
+    /// Statistics for every recorded hand, while the stats screen is open.
+    pub fn stats_view(&self) -> Option<&StatsReport> {
+        self.stats_view.as_ref()
+    }
+
+    /// The settings screen, while it is open.
+    pub fn settings(&self) -> Option<&SettingsScreen> {
+        self.settings.as_ref()
+    }
+
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    /// Where the value of a config key was read from at startup.
+    pub fn config_source(&self, key: &str) -> Option<&ConfigSource> {
+        self.config_sources.source(key)
+    }
+
     pub fn suit_color(&self, suit: Suit) -> Color {
         self.config.suit_color(suit)
     }
@@ -420,11 +1352,195 @@ impl App {
         self.exit_prompt.is_some()
     }
 
+    /// Hand index of the discard suggested by the latest hint.
+    pub fn hinted_discard(&self) -> Option<usize> {
+        if let Some(run) = &self.puzzle_run {
+            return run.verdict().and_then(|verdict| verdict.solution_index);
+        }
+        match self.hint {
+            Some(Hint::Discard { index, .. }) => Some(index),
+            _ => None,
+        }
+    }
+
     pub fn recent_draw(&self) -> Option<Card> {
-        self.recent_draw
+        match &self.replay {
+            Some(replay) => replay.drawn_card(),
+            None if self.puzzle_run.is_some() => None,
+            None => self.recent_draw,
+        }
+    }
+
+    /// The daily deal overlay's date and the leaderboard, while it is open.
+    pub fn daily_view(&self) -> Option<(NaiveDate, &Leaderboard)> {
+        self.daily_view.map(|date| (date, &self.daily))
+    }
+
+    /// The replay being viewed, if any.
+    pub fn replay(&self) -> Option<&Replay> {
+        self.replay.as_ref()
+    }
+
+    /// The puzzle being played and this profile's results, in puzzle mode.
+    pub fn puzzle_run(&self) -> Option<(&PuzzleRun, &PuzzleProgress)> {
+        self.puzzle_run
+            .as_ref()
+            .map(|run| (run, &self.puzzle_progress))
+    }
+
+    /// The position on screen: the replayed position while a replay is open,
+    /// the puzzle's position in puzzle mode, otherwise the live game.
+    pub fn board(&self) -> &Game {
+        match (&self.replay, &self.puzzle_run) {
+            (Some(replay), _) => replay.game(),
+            (None, Some(run)) => run.game(),
+            (None, None) => &self.game,
+        }
     }
 
     pub fn show_help(&self) -> bool {
         self.show_help
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use anyhow::bail;
+
+    use super::*;
+    use crate::{bot::HeuristicStrategy, game::build_deck};
+
+    fn test_app(dir: &tempfile::TempDir) -> App {
+        App::new(LaunchOptions {
+            seed: Some(7),
+            data_dir: Some(dir.path().to_path_buf()),
+            no_persist: true,
+            ..LaunchOptions::default()
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn each_difficulty_keeps_its_own_scoreboard() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut app = test_app(&dir);
+        assert_eq!(app.bot_difficulty, BotDifficulty::Challenging);
+        app.game.scoreboard.human = 42;
+        app.game.scoreboard.rounds_played = 3;
+
+        app.set_difficulty(BotDifficulty::Easy).unwrap();
+        assert_eq!(app.bot_difficulty, BotDifficulty::Easy);
+        assert_eq!(app.game.scoreboard.human, 0);
+        assert_eq!(app.other_scoreboards[&BotDifficulty::Challenging].human, 42);
+        app.game.scoreboard.bot = 17;
+
+        app.set_difficulty(BotDifficulty::Challenging).unwrap();
+        assert_eq!(app.game.scoreboard.human, 42);
+        assert_eq!(app.game.scoreboard.rounds_played, 3);
+        assert_eq!(app.other_scoreboards[&BotDifficulty::Easy].bot, 17);
+        assert!(!app
+            .other_scoreboards
+            .contains_key(&BotDifficulty::Challenging));
+    }
+
+    #[test]
+    fn a_strategy_that_cannot_restart_falls_back_to_the_default() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut app = test_app(&dir);
+        app.registry
+            .register("engine", "Always fails.", |_difficulty| {
+                bail!("engine handshake timed out")
+            });
+        app.bot = Box::new(HeuristicStrategy::with_params(BotParams {
+            name: "engine".to_string(),
+            ..BotParams::default()
+        }));
+
+        app.set_difficulty(BotDifficulty::Easy).unwrap();
+        assert_eq!(app.bot_difficulty, BotDifficulty::Easy);
+        assert_eq!(app.bot.name(), DEFAULT_STRATEGY);
+        let error = app.error_message().unwrap();
+        assert!(error.contains("engine handshake timed out"), "{error}");
+        assert!(error.ends_with("Using heuristic."), "{error}");
+    }
+
+    fn press(app: &mut App, code: KeyCode) {
+        app.handle_key(KeyEvent::from(code)).unwrap();
+    }
+
+    #[test]
+    fn a_hint_points_at_the_bots_discard_and_is_counted_in_the_summary() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut app = test_app(&dir);
+        let hand: Vec<Card> = "AS 2S 3S 4H 4D 4C 9C 10C JC QC KD"
+            .split_whitespace()
+            .map(|label| label.parse().unwrap())
+            .collect();
+        let mut rest: Vec<Card> = build_deck()
+            .into_iter()
+            .filter(|card| !hand.contains(card))
+            .collect();
+        app.game.bot.hand = rest.drain(..10).collect();
+        app.game.discard = rest.drain(..1).collect();
+        app.game.stock = rest;
+        app.game.human.hand = hand;
+        app.game.moves.clear();
+        app.game.current_player = PlayerId::Human;
+        app.game.phase = TurnPhase::AwaitDiscard;
+
+        press(&mut app, KeyCode::Char('t'));
+        let expected = HeuristicStrategy::new(BotDifficulty::Challenging)
+            .choose_discard(&app.game, PlayerId::Human);
+        assert_eq!(app.hinted_discard(), Some(expected));
+        assert_eq!(app.game.human.hand[expected].to_string(), "KD");
+        assert_eq!(app.selection, expected);
+        assert_eq!(app.hints_this_round, 1);
+
+        press(&mut app, KeyCode::Char('k'));
+        press(&mut app, KeyCode::Enter);
+        assert_eq!(app.game.phase, TurnPhase::RoundOver);
+        let summary = app.round_history.last().unwrap();
+        assert_eq!(summary.hints_used, 1);
+        assert!(summary.description.contains("Hints used: 1"));
+        assert_eq!(app.hand_records.last().unwrap().hints_used, 1);
+    }
+
+    #[test]
+    fn overlays_follow_the_rebound_move_and_discard_keys() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("config.toml"),
+            "[keys]\nmove_left = \"a\"\nmove_right = \"e\"\nmove_down = \"j\"\ndiscard = \"tab\"\n",
+        )
+        .unwrap();
+        let mut app = test_app(&dir);
+        assert_eq!(app.error_message(), None);
+        app.game.phase = TurnPhase::RoundOver;
+        app.bot_traces = vec![TurnTrace::default(); 3];
+
+        press(&mut app, KeyCode::Char('w'));
+        assert_eq!(app.trace_view, Some(2));
+        press(&mut app, KeyCode::Char('h'));
+        assert_eq!(app.trace_view, Some(2));
+        press(&mut app, KeyCode::Char('a'));
+        press(&mut app, KeyCode::Char('a'));
+        assert_eq!(app.trace_view, Some(0));
+        press(&mut app, KeyCode::Char('e'));
+        assert_eq!(app.trace_view, Some(1));
+        press(&mut app, KeyCode::Esc);
+        assert_eq!(app.trace_view, None);
+
+        press(&mut app, KeyCode::Char('b'));
+        let current = app.difficulty_picker.unwrap();
+        press(&mut app, KeyCode::Down);
+        assert_eq!(app.difficulty_picker, Some(current));
+        press(&mut app, KeyCode::Char('j'));
+        let next = (current + 1) % BotDifficulty::ALL.len();
+        assert_eq!(app.difficulty_picker, Some(next));
+        press(&mut app, KeyCode::Enter);
+        assert_eq!(app.difficulty_picker, Some(next));
+        press(&mut app, KeyCode::Tab);
+        assert_eq!(app.difficulty_picker, None);
+        assert_eq!(app.bot_difficulty, BotDifficulty::ALL[next]);
+    }
+}
@@ -0,0 +1,472 @@
+//! Append-only archive of every completed hand, one JSON record per line.
+//!
+//! Each record carries the schema `version` it was written with. Fields added
+//! in later versions must have serde defaults, and older versions that need
+//! more than that are upgraded in [`migrate`] before parsing, so archives
+//! written by earlier releases stay readable.
+
+use std::{
+    fs::{self, OpenOptions},
+    io::Write,
+    path::Path,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{
+    bot::BotDifficulty,
+    cards::Card,
+    game::{Deal, DrawSource, Game, Move, PlayerId, RoundEndReason, RoundResult, Rules},
+    meld::{self, Meld, MeldKind},
+};
+
+/// Schema version written by this build.
+pub const ARCHIVE_VERSION: u32 = 2;
+
+/// Everything needed to review or replay one finished hand.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HandRecord {
+    #[serde(default = "default_version")]
+    pub version: u32,
+    /// Seconds since the Unix epoch when the hand finished.
+    pub timestamp: u64,
+    /// Seed the deck was shuffled with.
+    pub seed: u64,
+    #[serde(default)]
+    pub difficulty: Option<BotDifficulty>,
+    /// Strategy the bot played with.
+    #[serde(default)]
+    pub bot: String,
+    #[serde(default)]
+    pub hints_used: u32,
+    /// Name of the rules variant the hand was played under.
+    pub rules: String,
+    pub deal: DealRecord,
+    pub moves: Vec<MoveRecord>,
+    pub outcome: OutcomeRecord,
+    /// Melds and deadwood of each hand as it was shown down.
+    pub human: HandAnalysis,
+    pub bot_hand: HandAnalysis,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DealRecord {
+    pub dealer: PlayerId,
+    pub human: Vec<Card>,
+    pub bot: Vec<Card>,
+    pub upcard: Option<Card>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "lowercase")]
+pub enum MoveRecord {
+    Draw {
+        player: PlayerId,
+        source: DrawSource,
+        card: Card,
+    },
+    Discard {
+        player: PlayerId,
+        card: Card,
+        #[serde(default)]
+        knock: bool,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OutcomeKind {
+    Knock,
+    BigGin,
+    StockDepleted,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OutcomeRecord {
+    pub kind: OutcomeKind,
+    pub winner: Option<PlayerId>,
+    pub points: i32,
+    #[serde(default)]
+    pub knocker: Option<PlayerId>,
+    #[serde(default)]
+    pub knocker_deadwood: u32,
+    #[serde(default)]
+    pub opponent_deadwood: u32,
+    #[serde(default)]
+    pub gin: bool,
+    #[serde(default)]
+    pub undercut: bool,
+    #[serde(default)]
+    pub laid_off: Vec<Card>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HandAnalysis {
+    pub cards: Vec<Card>,
+    pub melds: Vec<MeldRecord>,
+    pub deadwood: Vec<Card>,
+    pub deadwood_value: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MeldRecord {
+    /// `set` or `run`.
+    pub kind: String,
+    pub cards: Vec<Card>,
+}
+
+/// Records read back from an archive, plus a note for each line that could not be.
+#[derive(Debug, Default)]
+pub struct ArchiveContents {
+    pub records: Vec<HandRecord>,
+    pub skipped: Vec<String>,
+}
+
+/// Seconds since the Unix epoch, as stored in record timestamps.
+pub fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or_default()
+}
+
+impl HandRecord {
+    /// Builds the record for the round `game` just finished.
+    pub fn from_round(
+        game: &Game,
+        result: &RoundResult,
+        difficulty: Option<BotDifficulty>,
+        bot: &str,
+        hints_used: u32,
+        rules: Rules,
+    ) -> Self {
+        Self {
+            version: ARCHIVE_VERSION,
+            timestamp: unix_now(),
+            seed: game.round_seed,
+            difficulty,
+            bot: bot.to_string(),
+            hints_used,
+            rules: rules.name().to_string(),
+            deal: DealRecord {
+                dealer: game.deal.dealer,
+                human: game.deal.human.clone(),
+                bot: game.deal.bot.clone(),
+                upcard: game.deal.upcard,
+            },
+            moves: game.moves.iter().copied().map(MoveRecord::from).collect(),
+            outcome: OutcomeRecord::from(result),
+            human: HandAnalysis::of(&result.human_hand),
+            bot_hand: HandAnalysis::of(&result.bot_hand),
+        }
+    }
+}
+
+impl From<Move> for MoveRecord {
+    fn from(mv: Move) -> Self {
+        match mv {
+            Move::Draw {
+                player,
+                source,
+                card,
+            } => MoveRecord::Draw {
+                player,
+                source,
+                card,
+            },
+            Move::Discard {
+                player,
+                card,
+                knock,
+            } => MoveRecord::Discard {
+                player,
+                card,
+                knock,
+            },
+        }
+    }
+}
+
+impl From<MoveRecord> for Move {
+    fn from(record: MoveRecord) -> Self {
+        match record {
+            MoveRecord::Draw {
+                player,
+                source,
+                card,
+            } => Move::Draw {
+                player,
+                source,
+                card,
+            },
+            MoveRecord::Discard {
+                player,
+                card,
+                knock,
+            } => Move::Discard {
+                player,
+                card,
+                knock,
+            },
+        }
+    }
+}
+
+impl From<DealRecord> for Deal {
+    fn from(record: DealRecord) -> Self {
+        Deal {
+            dealer: record.dealer,
+            human: record.human,
+            bot: record.bot,
+            upcard: record.upcard,
+        }
+    }
+}
+
+impl From<&RoundResult> for OutcomeRecord {
+    fn from(result: &RoundResult) -> Self {
+        let mut outcome = OutcomeRecord {
+            kind: OutcomeKind::StockDepleted,
+            winner: result.winner,
+            points: result.points_awarded,
+            knocker: None,
+            knocker_deadwood: 0,
+            opponent_deadwood: 0,
+            gin: false,
+            undercut: false,
+            laid_off: Vec::new(),
+        };
+        match &result.reason {
+            RoundEndReason::Knock {
+                knocker,
+                knocker_deadwood,
+                opponent_deadwood,
+                laid_off,
+                gin,
+                undercut,
+            } => {
+                outcome.kind = OutcomeKind::Knock;
+                outcome.knocker = Some(*knocker);
+                outcome.knocker_deadwood = *knocker_deadwood;
+                outcome.opponent_deadwood = *opponent_deadwood;
+                outcome.laid_off = laid_off.clone();
+                outcome.gin = *gin;
+                outcome.undercut = *undercut;
+            }
+            RoundEndReason::BigGin {
+                player,
+                opponent_deadwood,
+                ..
+            } => {
+                outcome.kind = OutcomeKind::BigGin;
+                outcome.knocker = Some(*player);
+                outcome.opponent_deadwood = *opponent_deadwood;
+                outcome.gin = true;
+            }
+            RoundEndReason::StockDepleted => {}
+        }
+        outcome
+    }
+}
+
+impl HandAnalysis {
+    pub fn of(cards: &[Card]) -> Self {
+        let analysis = meld::analyze_hand(cards);
+        let mut cards = cards.to_vec();
+        cards.sort();
+        Self {
+            cards,
+            melds: analysis.melds.iter().map(MeldRecord::of).collect(),
+            deadwood: analysis.deadwood,
+            deadwood_value: analysis.deadwood_value,
+        }
+    }
+}
+
+impl MeldRecord {
+    pub fn of(meld: &Meld) -> Self {
+        Self {
+            kind: match meld.kind {
+                MeldKind::Set => "set",
+                MeldKind::Run => "run",
+            }
+            .to_string(),
+            cards: meld.cards.clone(),
+        }
+    }
+}
+
+/// Appends `record` as a single line, creating the archive if needed.
+pub fn append(path: &Path, record: &HandRecord) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).with_context(|| {
+            format!("failed to ensure archive directory at {}", parent.display())
+        })?;
+    }
+    let mut line = serde_json::to_string(record).context("failed to serialise hand record")?;
+    line.push('\n');
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .with_context(|| format!("failed to open hand archive at {}", path.display()))?;
+    file.write_all(line.as_bytes())
+        .with_context(|| format!("failed to append to hand archive at {}", path.display()))
+}
+
+/// Reads every record in the archive. Lines that cannot be parsed (a partial
+/// write, or a record from a newer release) are skipped and reported rather
+/// than failing the whole load. A missing archive is empty.
+pub fn load(path: &Path) -> Result<ArchiveContents> {
+    let mut contents = ArchiveContents::default();
+    if !path.exists() {
+        return Ok(contents);
+    }
+    let data = fs::read_to_string(path)
+        .with_context(|| format!("failed to read hand archive at {}", path.display()))?;
+    for (index, line) in data.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        match parse_line(line) {
+            Ok(record) => contents.records.push(record),
+            Err(err) => contents
+                .skipped
+                .push(format!("line {}: {err:#}", index + 1)),
+        }
+    }
+    Ok(contents)
+}
+
+fn parse_line(line: &str) -> Result<HandRecord> {
+    let value: Value = serde_json::from_str(line).context("not valid JSON")?;
+    let version = value
+        .get("version")
+        .and_then(Value::as_u64)
+        .map_or(default_version(), |version| version as u32);
+    let value = migrate(value, version)?;
+    serde_json::from_value(value).context("unrecognised hand record")
+}
+
+/// Upgrades a record written with schema `version` to the current layout,
+/// one version at a time.
+fn migrate(mut value: Value, mut version: u32) -> Result<Value> {
+    if version > ARCHIVE_VERSION {
+        anyhow::bail!(
+            "written by a newer version of Deadwood (archive version {version}, this build reads {ARCHIVE_VERSION})"
+        );
+    }
+    while version < ARCHIVE_VERSION {
+        value = match version {
+            1 => migrate_v1_to_v2(value)?,
+            other => anyhow::bail!("unknown archive version {other}"),
+        };
+        version += 1;
+    }
+    Ok(value)
+}
+
+/// Version 1 records did not say which rules the hand was played under;
+/// `standard` was the only variant then.
+fn migrate_v1_to_v2(mut value: Value) -> Result<Value> {
+    let record = value
+        .as_object_mut()
+        .ok_or_else(|| anyhow::anyhow!("hand record is not a JSON object"))?;
+    record
+        .entry("rules")
+        .or_insert_with(|| Value::from(Rules::Standard.name()));
+    record.insert("version".to_string(), Value::from(2));
+    Ok(value)
+}
+
+fn default_version() -> u32 {
+    1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        bot::{take_turn, HeuristicStrategy},
+        game::TurnPhase,
+    };
+
+    fn finished_hand(seed: u64) -> HandRecord {
+        let mut game = Game::with_seed(seed).unwrap();
+        let mut bot = HeuristicStrategy::new(BotDifficulty::Challenging);
+        let mut human = HeuristicStrategy::new(BotDifficulty::Easy);
+        while game.phase != TurnPhase::RoundOver {
+            let seat = game.current_player;
+            let strategy = match seat {
+                PlayerId::Bot => &mut bot,
+                PlayerId::Human => &mut human,
+            };
+            take_turn(&mut game, seat, strategy).unwrap();
+        }
+        let result = game.pending_round.clone().unwrap();
+        HandRecord::from_round(&game, &result, None, "heuristic", 0, Rules::Standard)
+    }
+
+    #[test]
+    fn load_skips_unreadable_lines() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("hands.jsonl");
+        let first = finished_hand(1);
+        let second = finished_hand(2);
+        append(&path, &first).unwrap();
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        writeln!(file, "{{\"version\": 1, \"seed\": 3").unwrap();
+        writeln!(file, "{{\"version\": 99, \"seed\": 4}}").unwrap();
+        writeln!(file).unwrap();
+        append(&path, &second).unwrap();
+
+        let contents = load(&path).unwrap();
+        assert_eq!(contents.records, vec![first, second]);
+        assert_eq!(contents.skipped.len(), 2);
+        assert!(contents.skipped[0].starts_with("line 2: not valid JSON"));
+        assert!(contents.skipped[1].starts_with("line 3: written by a newer version"));
+    }
+
+    #[test]
+    fn missing_archive_is_empty() {
+        let dir = tempfile::tempdir().unwrap();
+        let contents = load(&dir.path().join("hands.jsonl")).unwrap();
+        assert!(contents.records.is_empty() && contents.skipped.is_empty());
+    }
+
+    #[test]
+    fn migrate_accepts_current_and_unversioned_records() {
+        let record = finished_hand(5);
+        let mut value = serde_json::to_value(&record).unwrap();
+        assert_eq!(migrate(value.clone(), ARCHIVE_VERSION).unwrap(), value);
+
+        value.as_object_mut().unwrap().remove("version");
+        let line = serde_json::to_string(&value).unwrap();
+        assert_eq!(parse_line(&line).unwrap(), record);
+    }
+
+    #[test]
+    fn version_one_records_were_played_under_standard_rules() {
+        let record = finished_hand(6);
+        let mut value = serde_json::to_value(&record).unwrap();
+        let object = value.as_object_mut().unwrap();
+        object.insert("version".to_string(), Value::from(1));
+        object.remove("rules");
+        let line = serde_json::to_string(&value).unwrap();
+        let migrated = parse_line(&line).unwrap();
+        assert_eq!(migrated.version, ARCHIVE_VERSION);
+        assert_eq!(migrated.rules, "standard");
+        assert_eq!(migrated, record);
+    }
+
+    #[test]
+    fn migrate_rejects_unknown_versions() {
+        let newer = migrate(Value::Null, ARCHIVE_VERSION + 1).unwrap_err();
+        assert!(newer.to_string().contains("newer version of Deadwood"));
+        let unknown = migrate(Value::Null, 0).unwrap_err();
+        assert_eq!(unknown.to_string(), "unknown archive version 0");
+    }
+}
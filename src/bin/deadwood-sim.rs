@@ -0,0 +1,5 @@
+use anyhow::Result;
+
+fn main() -> Result<()> {
+    deadwood::cli::run_sim(std::env::args().skip(1))
+}
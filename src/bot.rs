@@ -1,114 +1,1781 @@
-use anyhow::Result;
-use rand::seq::SliceRandom;
+use std::collections::{HashSet, VecDeque};
+
+use anyhow::{anyhow, bail, Context, Result};
+use itertools::Itertools;
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
 
 use crate::{
-    game::{ActionOutcome, DrawSource, Game, PlayerId, TurnPhase},
+    cards::Card,
+    engine::ENGINE_STRATEGY,
+    game::{
+        build_deck, score_knock, ActionOutcome, DrawSource, Game, PlayerId, RoundResult, TurnPhase,
+    },
     meld::analyze_hand,
+    params::{BotParams, KnockRule},
 };
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub const DEFAULT_STRATEGY: &str = "heuristic";
+/// The heuristic bot with the original fixed knock rule, kept as a baseline.
+pub const CLASSIC_STRATEGY: &str = "heuristic-classic";
+/// Strategy names a bot profile cannot replace.
+const RESERVED_STRATEGIES: [&str; 3] = [DEFAULT_STRATEGY, CLASSIC_STRATEGY, ENGINE_STRATEGY];
+
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default, Serialize, Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
 pub enum BotDifficulty {
     Easy,
+    #[default]
     Challenging,
+    /// Adjusts its strength between rounds to keep the match close.
+    Adaptive,
 }
 
 impl BotDifficulty {
-    fn knock_threshold(self) -> u32 {
+    pub const ALL: [BotDifficulty; 3] = [
+        BotDifficulty::Easy,
+        BotDifficulty::Challenging,
+        BotDifficulty::Adaptive,
+    ];
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        let normalized = name.trim().to_ascii_lowercase();
+        Self::ALL
+            .into_iter()
+            .find(|difficulty| difficulty.name() == normalized)
+    }
+
+    /// Lowercase identifier used in config files and on the command line.
+    pub fn name(self) -> &'static str {
         match self {
-            BotDifficulty::Easy => 6,
-            BotDifficulty::Challenging => 8,
+            BotDifficulty::Easy => "easy",
+            BotDifficulty::Challenging => "challenging",
+            BotDifficulty::Adaptive => "adaptive",
         }
     }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            BotDifficulty::Easy => "Easy",
+            BotDifficulty::Challenging => "Challenging",
+            BotDifficulty::Adaptive => "Adaptive",
+        }
+    }
+}
+
+/// Decision-making for a bot seat. `take_turn` drives the game and asks the
+/// strategy for each choice, so new bots only need to implement this trait and
+/// be added to a `StrategyRegistry`.
+pub trait Strategy {
+    fn name(&self) -> &str;
+
+    fn on_round_start(&mut self, _game: &Game, _seat: PlayerId) {}
+
+    fn choose_draw(&mut self, game: &Game, seat: PlayerId) -> DrawSource;
+
+    /// Returns the index into the seat's hand of the card to discard.
+    fn choose_discard(&mut self, game: &Game, seat: PlayerId) -> usize;
+
+    /// Called after `choose_discard` with the chosen index, before the discard is applied.
+    fn decide_knock(&mut self, game: &Game, seat: PlayerId, discard_index: usize) -> bool;
+
+    fn on_round_end(&mut self, _game: &Game, _seat: PlayerId, _result: &RoundResult) {}
+
+    /// Reasoning behind the most recent turn, if the strategy records one.
+    fn last_trace(&self) -> Option<&TurnTrace> {
+        None
+    }
+
+    /// Current strength from 0.0 (weakest) to 1.0 (strongest), for bots that adapt.
+    fn effective_level(&self) -> Option<f64> {
+        None
+    }
+}
+
+pub type StrategyFactory = Box<dyn Fn(BotDifficulty) -> Result<Box<dyn Strategy>>>;
+
+struct RegistryEntry {
+    name: String,
+    description: String,
+    factory: StrategyFactory,
+}
+
+/// Named strategies that can be selected from the config file.
+pub struct StrategyRegistry {
+    entries: Vec<RegistryEntry>,
+}
+
+impl StrategyRegistry {
+    pub fn empty() -> Self {
+        Self {
+            entries: Vec::new(),
+        }
+    }
+
+    pub fn builtin() -> Self {
+        let mut registry = Self::empty();
+        registry.register(
+            DEFAULT_STRATEGY,
+            "Deadwood-minimising heuristics with difficulty-based knocking.",
+            |difficulty| match difficulty {
+                BotDifficulty::Adaptive => Ok(Box::new(AdaptiveStrategy::new())),
+                _ => Ok(Box::new(HeuristicStrategy::new(difficulty))),
+            },
+        );
+        registry.register(
+            CLASSIC_STRATEGY,
+            "The heuristic bot with the original fixed knock rule.",
+            |difficulty| {
+                Ok(Box::new(HeuristicStrategy::with_params(
+                    BotParams::classic(difficulty),
+                )))
+            },
+        );
+        registry
+    }
+
+    /// Registers a heuristic bot personality under the profile's name. The
+    /// profile fixes every parameter, so the selected difficulty is ignored.
+    /// Profiles may not take the name of a built-in strategy.
+    pub fn register_profile(&mut self, params: BotParams) -> Result<()> {
+        let name = params.name.clone();
+        if RESERVED_STRATEGIES
+            .iter()
+            .any(|reserved| reserved.eq_ignore_ascii_case(name.trim()))
+        {
+            bail!("bot profile '{name}' uses the name of a built-in strategy. Skipping it");
+        }
+        let description = if params.description.is_empty() {
+            "Heuristic bot loaded from a profile.".to_string()
+        } else {
+            params.description.clone()
+        };
+        self.register(name, description, move |_difficulty| {
+            Ok(Box::new(HeuristicStrategy::with_params(params.clone())))
+        });
+        Ok(())
+    }
+
+    /// Adds a strategy, replacing any existing entry with the same name.
+    pub fn register<F>(
+        &mut self,
+        name: impl Into<String>,
+        description: impl Into<String>,
+        factory: F,
+    ) where
+        F: Fn(BotDifficulty) -> Result<Box<dyn Strategy>> + 'static,
+    {
+        let name = name.into();
+        self.entries.retain(|entry| entry.name != name);
+        self.entries.push(RegistryEntry {
+            name,
+            description: description.into(),
+            factory: Box::new(factory),
+        });
+    }
+
+    pub fn create(&self, name: &str, difficulty: BotDifficulty) -> Result<Box<dyn Strategy>> {
+        let normalized = name.trim().to_ascii_lowercase();
+        let entry = self
+            .entries
+            .iter()
+            .find(|entry| entry.name == normalized)
+            .ok_or_else(|| {
+                anyhow!(
+                    "unknown bot strategy '{}' (available: {})",
+                    name,
+                    self.names().collect::<Vec<_>>().join(", ")
+                )
+            })?;
+        (entry.factory)(difficulty)
+            .with_context(|| format!("failed to start bot strategy '{}'", entry.name))
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &str> + '_ {
+        self.entries.iter().map(|entry| entry.name.as_str())
+    }
+
+    pub fn describe(&self, name: &str) -> Option<&str> {
+        self.entries
+            .iter()
+            .find(|entry| entry.name == name)
+            .map(|entry| entry.description.as_str())
+    }
 }
 
-pub fn take_turn(game: &mut Game, difficulty: BotDifficulty) -> Result<ActionOutcome> {
+/// Plays a whole turn for `seat`: the draw and then the discard.
+pub fn take_turn(
+    game: &mut Game,
+    seat: PlayerId,
+    strategy: &mut dyn Strategy,
+) -> Result<ActionOutcome> {
     loop {
-        match game.phase {
-            TurnPhase::AwaitDraw if game.current_player == PlayerId::Bot => {
-                let source = choose_draw_source(game, difficulty);
-                let outcome = game.draw(PlayerId::Bot, source)?;
-                match outcome {
-                    ActionOutcome::Continue => continue,
-                    ActionOutcome::RoundEnded => return Ok(ActionOutcome::RoundEnded),
+        let drawing = game.phase == TurnPhase::AwaitDraw && game.current_player == seat;
+        let outcome = take_step(game, seat, strategy)?;
+        if drawing && outcome == ActionOutcome::Continue {
+            continue;
+        }
+        return Ok(outcome);
+    }
+}
+
+/// Plays a single action for `seat`, either its draw or its discard (with any
+/// knock), so callers can show the two halves of a turn separately.
+pub fn take_step(
+    game: &mut Game,
+    seat: PlayerId,
+    strategy: &mut dyn Strategy,
+) -> Result<ActionOutcome> {
+    match game.phase {
+        TurnPhase::AwaitDraw if game.current_player == seat => {
+            let source = strategy.choose_draw(game, seat);
+            game.draw(seat, source)
+        }
+        TurnPhase::AwaitDiscard if game.current_player == seat => {
+            let index = strategy.choose_discard(game, seat);
+            let knock = strategy.decide_knock(game, seat, index);
+            game.discard(seat, index, knock)
+        }
+        _ => Ok(ActionOutcome::Continue),
+    }
+}
+
+/// Why the bot drew from where it did.
+#[derive(Debug, Clone)]
+pub struct DrawTrace {
+    pub top_discard: Option<Card>,
+    pub current_deadwood: u32,
+    /// Deadwood of the hand with the top discard added, before discarding.
+    pub deadwood_with_discard: Option<u32>,
+    pub choice: DrawSource,
+}
+
+/// One card the bot considered throwing. Higher scores are preferred.
+#[derive(Debug, Clone)]
+pub struct DiscardCandidate {
+    pub card: Card,
+    pub deadwood: u32,
+    pub score: f32,
+}
+
+#[derive(Debug, Clone)]
+pub struct DiscardTrace {
+    pub candidates: Vec<DiscardCandidate>,
+    pub chosen: Card,
+    /// Set when the discard was picked at random rather than by score.
+    pub randomised: bool,
+}
+
+/// A single condition evaluated while deciding whether to knock.
+#[derive(Debug, Clone)]
+pub struct KnockCheck {
+    pub label: String,
+    pub detail: String,
+    pub passed: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct KnockTrace {
+    pub deadwood_after_discard: u32,
+    pub checks: Vec<KnockCheck>,
+    pub knock: bool,
+}
+
+/// Outcome of knocking now versus waiting, averaged over sampled opponent hands.
+#[derive(Debug, Clone)]
+pub struct KnockRisk {
+    pub samples: usize,
+    /// Share of samples in which the knock is undercut.
+    pub undercut_rate: f64,
+    /// Expected points for knocking now; negative when undercuts dominate.
+    pub knock_points: f64,
+    /// Expected points after letting the opponent and then the bot play one more turn.
+    pub wait_points: f64,
+}
+
+/// Everything a strategy considered during one turn.
+#[derive(Debug, Clone, Default)]
+pub struct TurnTrace {
+    pub draw: Option<DrawTrace>,
+    pub discard: Option<DiscardTrace>,
+    pub knock: Option<KnockTrace>,
+    /// Free-form remarks, such as an engine's `info` output or fallback notices.
+    pub notes: Vec<String>,
+}
+
+/// The original deadwood-driven bot: take the discard when it doesn't hurt,
+/// throw the card that leaves the least deadwood, and knock according to its
+/// `KnockRule`.
+pub struct HeuristicStrategy {
+    params: BotParams,
+    trace: TurnTrace,
+    rng: StdRng,
+    /// The endgame search's knock decision for the discard it just chose.
+    endgame_knock: Option<(bool, KnockCheck)>,
+}
+
+impl HeuristicStrategy {
+    pub fn new(difficulty: BotDifficulty) -> Self {
+        Self::with_params(BotParams::for_difficulty(difficulty))
+    }
+
+    pub fn with_params(params: BotParams) -> Self {
+        Self {
+            params,
+            trace: TurnTrace::default(),
+            rng: StdRng::from_entropy(),
+            endgame_knock: None,
+        }
+    }
+
+    /// Swaps in new parameters, keeping the strategy's name.
+    pub fn set_params(&mut self, params: BotParams) {
+        let name = std::mem::take(&mut self.params.name);
+        self.params = BotParams { name, ..params };
+    }
+}
+
+impl Strategy for HeuristicStrategy {
+    fn name(&self) -> &str {
+        &self.params.name
+    }
+
+    fn on_round_start(&mut self, game: &Game, seat: PlayerId) {
+        // Tie any randomness to the deal so seeded games replay identically.
+        self.rng = StdRng::seed_from_u64(seat_seed(game.round_seed, seat));
+    }
+
+    fn choose_draw(&mut self, game: &Game, seat: PlayerId) -> DrawSource {
+        let draw = evaluate_draw(
+            &game.player(seat).hand,
+            game.discard.last().copied(),
+            &self.params,
+        );
+        let mut choice = draw.choice;
+        self.trace = TurnTrace {
+            draw: Some(draw),
+            ..TurnTrace::default()
+        };
+        if let Some(plan) = plan_endgame(game, seat, &self.params, &mut self.rng) {
+            if let EndgameMove::Draw(source) = plan.choice() {
+                choice = source;
+                if let Some(draw) = self.trace.draw.as_mut() {
+                    draw.choice = source;
                 }
             }
-            TurnPhase::AwaitDiscard if game.current_player == PlayerId::Bot => {
-                let (index, knock) = choose_discard(game, difficulty);
-                let outcome = game.discard(PlayerId::Bot, index, knock)?;
-                return Ok(outcome);
+            self.trace.notes.push(plan.summary());
+        }
+        choice
+    }
+
+    fn choose_discard(&mut self, game: &Game, seat: PlayerId) -> usize {
+        let hand = &game.player(seat).hand;
+        let candidates = evaluate_discards(hand, &self.params);
+        let mut chosen = best_candidate(&candidates);
+        let mut randomised = false;
+        self.endgame_knock = None;
+
+        if let Some(plan) = plan_endgame(game, seat, &self.params, &mut self.rng) {
+            if let EndgameMove::Discard { index, knock } = plan.choice() {
+                chosen = index;
+                self.endgame_knock = Some((knock, endgame_knock_check(&plan, index)));
+            }
+            self.trace.notes.push(plan.summary());
+        }
+
+        if self.endgame_knock.is_none()
+            && self.params.random_discard_chance > 0.0
+            && self.rng.gen::<f64>() < self.params.random_discard_chance
+        {
+            let random_index = (0..hand.len())
+                .collect::<Vec<_>>()
+                .choose(&mut self.rng)
+                .copied();
+            if let Some(idx) = random_index {
+                chosen = idx;
+                randomised = true;
             }
-            _ => return Ok(ActionOutcome::Continue),
         }
+
+        self.trace.discard = Some(DiscardTrace {
+            chosen: hand[chosen],
+            candidates,
+            randomised,
+        });
+        chosen
+    }
+
+    fn decide_knock(&mut self, game: &Game, seat: PlayerId, discard_index: usize) -> bool {
+        let randomised = self
+            .trace
+            .discard
+            .as_ref()
+            .is_some_and(|discard| discard.randomised);
+        if randomised || discard_index >= game.player(seat).hand.len() {
+            self.trace.knock = None;
+            return false;
+        }
+
+        if let Some((knock, check)) = self.endgame_knock.take() {
+            let mut remaining = game.player(seat).hand.clone();
+            remaining.remove(discard_index);
+            let deadwood = analyze_hand(&remaining).deadwood_value;
+            self.trace.knock = Some(KnockTrace {
+                deadwood_after_discard: deadwood,
+                checks: vec![
+                    KnockCheck {
+                        label: "Legal knock".to_string(),
+                        detail: format!("deadwood {deadwood} <= 10"),
+                        passed: deadwood <= 10,
+                    },
+                    check,
+                ],
+                knock,
+            });
+            return knock;
+        }
+
+        let knock = match self.params.knock_rule {
+            KnockRule::Classic => evaluate_knock(game, seat, discard_index, &self.params),
+            KnockRule::RiskAware => {
+                evaluate_knock_risk(game, seat, discard_index, &self.params, &mut self.rng)
+            }
+        };
+        let decision = knock.knock;
+        self.trace.knock = Some(knock);
+        decision
+    }
+
+    fn last_trace(&self) -> Option<&TurnTrace> {
+        Some(&self.trace)
     }
 }
 
-fn choose_draw_source(game: &Game, _difficulty: BotDifficulty) -> DrawSource {
-    if game.discard.is_empty() {
-        return DrawSource::Stock;
+/// Rounds of results the adaptive bot looks back over.
+const ADAPTIVE_WINDOW: usize = 6;
+/// Largest change in level after a single round.
+const ADAPTIVE_RATE: f64 = 0.15;
+
+/// The heuristic bot with its strength on a sliding scale. After each round it
+/// looks at who won the recent hands and steps its level down when it has been
+/// winning and up when the human has, which changes its randomness, endgame
+/// search and knocking via `BotParams::for_level`.
+pub struct AdaptiveStrategy {
+    inner: HeuristicStrategy,
+    level: f64,
+    /// Recent results from the bot's side: 1 for a win, -1 for a loss, 0 for a draw.
+    recent: VecDeque<i32>,
+    seeded: bool,
+}
+
+impl AdaptiveStrategy {
+    pub fn new() -> Self {
+        let level = 0.5;
+        let mut inner = HeuristicStrategy::with_params(BotParams::for_level(level));
+        inner.params.name = DEFAULT_STRATEGY.to_string();
+        Self {
+            inner,
+            level,
+            recent: VecDeque::new(),
+            seeded: false,
+        }
     }
 
-    let mut hypothetical = game.bot.hand.clone();
-    let top_discard = *game.discard.last().unwrap();
-    let current_score = analyze_hand(&hypothetical).deadwood_value;
-    hypothetical.push(top_discard);
-    let score_with_discard = analyze_hand(&hypothetical).deadwood_value;
+    fn set_level(&mut self, level: f64) {
+        self.level = level.clamp(0.0, 1.0);
+        self.inner.set_params(BotParams::for_level(self.level));
+    }
+}
 
-    if score_with_discard <= current_score {
-        DrawSource::Discard
-    } else {
-        DrawSource::Stock
+impl Default for AdaptiveStrategy {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
-fn choose_discard(game: &Game, difficulty: BotDifficulty) -> (usize, bool) {
-    let mut best_index = 0;
-    let mut best_deadwood = u32::MAX;
-    let mut best_card_value = 0;
+impl Strategy for AdaptiveStrategy {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
 
-    for (idx, _card) in game.bot.hand.iter().enumerate() {
-        let mut hypothetical = game.bot.hand.clone();
-        let removed = hypothetical.remove(idx);
-        let analysis = analyze_hand(&hypothetical);
-        let deadwood_with_discard = analysis.deadwood_value;
+    fn on_round_start(&mut self, game: &Game, seat: PlayerId) {
+        // Pick up where earlier sessions left off using the stored hand counts.
+        if !self.seeded {
+            self.seeded = true;
+            let board = &game.scoreboard;
+            let (mine, theirs) = match seat {
+                PlayerId::Bot => (board.bot_hands_won, board.human_hands_won),
+                PlayerId::Human => (board.human_hands_won, board.bot_hands_won),
+            };
+            let decided = (mine + theirs) as f64;
+            if decided > 0.0 {
+                self.set_level(0.5 - (mine as f64 - theirs as f64) / (decided + 4.0));
+            }
+        }
+        self.inner.on_round_start(game, seat);
+    }
 
-        if deadwood_with_discard < best_deadwood
-            || (deadwood_with_discard == best_deadwood && removed.rank.value() > best_card_value)
-        {
-            best_deadwood = deadwood_with_discard;
-            best_index = idx;
-            best_card_value = removed.rank.value();
+    fn choose_draw(&mut self, game: &Game, seat: PlayerId) -> DrawSource {
+        self.inner.choose_draw(game, seat)
+    }
+
+    fn choose_discard(&mut self, game: &Game, seat: PlayerId) -> usize {
+        self.inner.choose_discard(game, seat)
+    }
+
+    fn decide_knock(&mut self, game: &Game, seat: PlayerId, discard_index: usize) -> bool {
+        self.inner.decide_knock(game, seat, discard_index)
+    }
+
+    fn on_round_end(&mut self, game: &Game, seat: PlayerId, result: &RoundResult) {
+        self.seeded = true;
+        let outcome = match result.winner {
+            Some(winner) if winner == seat => 1,
+            Some(_) => -1,
+            None => 0,
+        };
+        self.recent.push_back(outcome);
+        while self.recent.len() > ADAPTIVE_WINDOW {
+            self.recent.pop_front();
         }
+        let balance = self.recent.iter().sum::<i32>() as f64 / self.recent.len() as f64;
+        self.set_level(self.level - ADAPTIVE_RATE * balance);
+        self.inner.on_round_end(game, seat, result);
+    }
+
+    fn last_trace(&self) -> Option<&TurnTrace> {
+        self.inner.last_trace()
     }
 
-    let mut knock = false;
+    fn effective_level(&self) -> Option<f64> {
+        Some(self.level)
+    }
+}
+
+/// Per-seat seed derived from a round seed, so both seats don't share a stream.
+pub fn seat_seed(round_seed: u64, seat: PlayerId) -> u64 {
+    match seat {
+        PlayerId::Human => round_seed ^ 0x5DEE_CE66_D1CE_4E5B,
+        PlayerId::Bot => round_seed ^ 0xB0B5_1A7E_0DD5_EED5,
+    }
+}
+
+/// Advice for a player, built from the same evaluations the bot uses.
+#[derive(Debug, Clone)]
+pub enum Hint {
+    Draw(DrawTrace),
+    Discard {
+        index: usize,
+        candidate: DiscardCandidate,
+        knock: KnockTrace,
+    },
+}
+
+/// Suggests the next action for `seat`, or `None` when it isn't that seat's turn.
+pub fn suggest(game: &Game, seat: PlayerId) -> Option<Hint> {
+    if game.current_player != seat {
+        return None;
+    }
+    let params = BotParams::for_difficulty(BotDifficulty::Challenging);
+    match game.phase {
+        TurnPhase::AwaitDraw => Some(Hint::Draw(evaluate_draw(
+            &game.player(seat).hand,
+            game.discard.last().copied(),
+            &params,
+        ))),
+        TurnPhase::AwaitDiscard => {
+            let candidates = evaluate_discards(&game.player(seat).hand, &params);
+            if candidates.is_empty() {
+                return None;
+            }
+            let index = best_candidate(&candidates);
+            // Seeded from the position so asking twice gives the same advice.
+            let mut rng = StdRng::seed_from_u64(
+                seat_seed(game.round_seed, seat).wrapping_add(game.moves.len() as u64),
+            );
+            let knock = evaluate_knock_risk(game, seat, index, &params, &mut rng);
+            Some(Hint::Discard {
+                index,
+                candidate: candidates[index].clone(),
+                knock,
+            })
+        }
+        TurnPhase::RoundOver => None,
+    }
+}
+
+/// Compares the hand's deadwood with and without the top discard; the discard is
+/// worth taking unless it makes the hand worse by more than the allowed margin.
+pub fn evaluate_draw(hand: &[Card], top_discard: Option<Card>, params: &BotParams) -> DrawTrace {
+    let current_deadwood = analyze_hand(hand).deadwood_value;
+    let Some(top_discard) = top_discard else {
+        return DrawTrace {
+            top_discard: None,
+            current_deadwood,
+            deadwood_with_discard: None,
+            choice: DrawSource::Stock,
+        };
+    };
+
+    let mut hypothetical = hand.to_vec();
+    hypothetical.push(top_discard);
+    let deadwood_with_discard = analyze_hand(&hypothetical).deadwood_value;
+    let choice =
+        if deadwood_with_discard as i32 <= current_deadwood as i32 + params.take_discard_margin {
+            DrawSource::Discard
+        } else {
+            DrawSource::Stock
+        };
+
+    DrawTrace {
+        top_discard: Some(top_discard),
+        current_deadwood,
+        deadwood_with_discard: Some(deadwood_with_discard),
+        choice,
+    }
+}
+
+/// Scores every possible discard by the deadwood left behind, preferring to shed
+/// high-value cards as weighted by the profile. Candidates are returned in hand order.
+pub fn evaluate_discards(hand: &[Card], params: &BotParams) -> Vec<DiscardCandidate> {
+    hand.iter()
+        .enumerate()
+        .map(|(idx, &card)| {
+            let mut hypothetical = hand.to_vec();
+            hypothetical.remove(idx);
+            let deadwood = analyze_hand(&hypothetical).deadwood_value;
+            let score =
+                card.rank.value() as f32 * params.discard_value_weight as f32 - deadwood as f32;
+            DiscardCandidate {
+                card,
+                deadwood,
+                score,
+            }
+        })
+        .collect()
+}
+
+/// Index of the highest-scoring candidate, keeping the earliest on ties.
+pub fn best_candidate(candidates: &[DiscardCandidate]) -> usize {
+    let mut best = 0;
+    for (idx, candidate) in candidates.iter().enumerate() {
+        if candidate.score > candidates[best].score {
+            best = idx;
+        }
+    }
+    best
+}
+
+/// Applies the knock rule to the hand left after discarding `discard_index`.
+/// Marginal knocks are held back until the hand is strong or the round runs late.
+pub fn evaluate_knock(
+    game: &Game,
+    seat: PlayerId,
+    discard_index: usize,
+    params: &BotParams,
+) -> KnockTrace {
+    let threshold = params.knock_threshold;
+    let late_discards = params.late_round_discards;
+    let late_stock = params.late_round_stock;
+    let strong = params.strong_hand_deadwood;
+    let mut remaining = game.player(seat).hand.clone();
+    remaining.remove(discard_index);
+    let deadwood = analyze_hand(&remaining).deadwood_value;
+
     let cards_played = game.discard.len().saturating_sub(1);
     let remaining_stock = game.stock.len();
-    let late_round = cards_played >= 8 || remaining_stock <= 15;
-    let strong_hand = best_deadwood <= 3;
-
-    if best_deadwood <= difficulty.knock_threshold() && (late_round || strong_hand) {
-        // Hold off on marginal knocks until deeper in the round to keep them rare.
-        let hypothetical = {
-            let mut hand = game.bot.hand.clone();
-            hand.remove(best_index);
-            hand
+    let late_round = cards_played >= late_discards || remaining_stock <= late_stock;
+    let strong_hand = deadwood <= strong;
+
+    let checks = vec![
+        KnockCheck {
+            label: "Legal knock".to_string(),
+            detail: format!("deadwood {deadwood} <= 10"),
+            passed: deadwood <= 10,
+        },
+        KnockCheck {
+            label: "Knock threshold".to_string(),
+            detail: format!("deadwood {deadwood} <= {threshold}"),
+            passed: deadwood <= threshold,
+        },
+        KnockCheck {
+            label: "Late round".to_string(),
+            detail: format!(
+                "{cards_played} discards (>= {late_discards}) or {remaining_stock} stock (<= {late_stock})"
+            ),
+            passed: late_round,
+        },
+        KnockCheck {
+            label: "Strong hand".to_string(),
+            detail: format!("deadwood {deadwood} <= {strong}"),
+            passed: strong_hand,
+        },
+    ];
+    let knock = deadwood <= 10 && deadwood <= threshold && (late_round || strong_hand);
+
+    KnockTrace {
+        deadwood_after_discard: deadwood,
+        checks,
+        knock,
+    }
+}
+
+/// Decides whether to knock after discarding `discard_index` by weighing the
+/// points expected from knocking now, including undercuts, against playing on
+/// for another turn. The opponent's hand is sampled from the unseen cards,
+/// keeping the cards it is known to have picked up from the discard pile.
+pub fn evaluate_knock_risk(
+    game: &Game,
+    seat: PlayerId,
+    discard_index: usize,
+    params: &BotParams,
+    rng: &mut StdRng,
+) -> KnockTrace {
+    let threshold = params.knock_threshold;
+    let mut remaining = game.player(seat).hand.clone();
+    let discarded = remaining.remove(discard_index);
+    let deadwood = analyze_hand(&remaining).deadwood_value;
+
+    let mut checks = vec![
+        KnockCheck {
+            label: "Legal knock".to_string(),
+            detail: format!("deadwood {deadwood} <= 10"),
+            passed: deadwood <= 10,
+        },
+        KnockCheck {
+            label: "Knock threshold".to_string(),
+            detail: format!("deadwood {deadwood} <= {threshold}"),
+            passed: deadwood <= threshold,
+        },
+    ];
+    if deadwood > 10 || deadwood > threshold {
+        return KnockTrace {
+            deadwood_after_discard: deadwood,
+            checks,
+            knock: false,
+        };
+    }
+    if deadwood == 0 {
+        checks.push(KnockCheck {
+            label: "Gin".to_string(),
+            detail: "gin cannot be undercut".to_string(),
+            passed: true,
+        });
+        return KnockTrace {
+            deadwood_after_discard: deadwood,
+            checks,
+            knock: true,
+        };
+    }
+
+    let risk = estimate_knock_risk(game, seat, &remaining, discarded, params, rng);
+    let profitable = risk.knock_points > params.min_knock_points;
+    let beats_waiting = risk.knock_points >= risk.wait_points;
+    checks.push(KnockCheck {
+        label: "Undercut risk".to_string(),
+        detail: format!(
+            "{:.0}% of {} sampled hands undercut, {:+.1} expected points",
+            risk.undercut_rate * 100.0,
+            risk.samples,
+            risk.knock_points
+        ),
+        passed: profitable,
+    });
+    checks.push(KnockCheck {
+        label: "Knock beats waiting".to_string(),
+        detail: format!(
+            "{:+.1} now vs {:+.1} after another turn",
+            risk.knock_points, risk.wait_points
+        ),
+        passed: beats_waiting,
+    });
+
+    KnockTrace {
+        deadwood_after_discard: deadwood,
+        checks,
+        knock: profitable && beats_waiting,
+    }
+}
+
+/// Samples opponent hands consistent with what `seat` has seen and scores a
+/// knock with `remaining` against each. Each sample starts from the opponent's
+/// known pickups plus random unseen cards, then replays one stock draw per
+/// opponent turn so that longer hands are modelled as better developed.
+pub fn estimate_knock_risk(
+    game: &Game,
+    seat: PlayerId,
+    remaining: &[Card],
+    discarded: Card,
+    params: &BotParams,
+    rng: &mut StdRng,
+) -> KnockRisk {
+    let opponent = seat.other();
+    let known = game.known_cards(opponent);
+    let unseen = unseen_cards(game, seat, &[discarded]);
+
+    let hidden = game.player(opponent).hand.len().saturating_sub(known.len());
+    let replayed = game.turns_taken(opponent).min(params.replayed_draws);
+    let samples = params.risk_samples.max(1);
+
+    let mut undercuts = 0;
+    let mut knock_total = 0;
+    let mut wait_total = 0;
+    for _ in 0..samples {
+        let mut pool = unseen.clone();
+        pool.shuffle(rng);
+        let hand = sample_opponent_hand(&known, &mut pool, hidden, replayed, params);
+
+        if let Some(score) = score_knock(remaining, &hand) {
+            if score.undercut {
+                undercuts += 1;
+            }
+            knock_total += score.knocker_points();
+        }
+        wait_total += wait_points(game.stock.len(), remaining, hand, &mut pool, params);
+    }
+
+    let count = samples as f64;
+    KnockRisk {
+        samples,
+        undercut_rate: undercuts as f64 / count,
+        knock_points: knock_total as f64 / count,
+        wait_points: wait_total as f64 / count,
+    }
+}
+
+/// Cards `seat` cannot see: neither in its hand, the discard pile, `also_seen`,
+/// nor known to be held by the opponent.
+fn unseen_cards(game: &Game, seat: PlayerId, also_seen: &[Card]) -> Vec<Card> {
+    let seen: HashSet<Card> = game
+        .player(seat)
+        .hand
+        .iter()
+        .chain(&game.discard)
+        .chain(&game.known_cards(seat.other()))
+        .chain(also_seen)
+        .copied()
+        .collect();
+    build_deck()
+        .into_iter()
+        .filter(|card| !seen.contains(card))
+        .collect()
+}
+
+/// Builds an opponent hand from its `known` cards plus `hidden` cards from the
+/// shuffled `pool`, then replays `replayed` stock draws so the hand looks as
+/// developed as the opponent's turn count suggests. Cards used or shed are
+/// taken out of `pool`.
+fn sample_opponent_hand(
+    known: &[Card],
+    pool: &mut Vec<Card>,
+    hidden: usize,
+    replayed: usize,
+    params: &BotParams,
+) -> Vec<Card> {
+    let mut hand = known.to_vec();
+    hand.extend(pool.drain(..hidden.min(pool.len())));
+    for _ in 0..replayed {
+        let Some(card) = pool.pop() else { break };
+        hand.push(card);
+        shed_worst(&mut hand, known, params);
+    }
+    hand
+}
+
+/// Points for the bot if it passes on knocking: the opponent draws and knocks
+/// whenever that pays, otherwise the bot draws once more and knocks if it can.
+/// Stock draws with two or fewer cards left end the hand as a draw.
+fn wait_points(
+    stock: usize,
+    mine: &[Card],
+    mut theirs: Vec<Card>,
+    pool: &mut Vec<Card>,
+    params: &BotParams,
+) -> i32 {
+    if stock <= 2 {
+        return 0;
+    }
+    let Some(card) = pool.pop() else { return 0 };
+    theirs.push(card);
+    shed_worst(&mut theirs, &[], params);
+    if let Some(score) = score_knock(&theirs, mine) {
+        if score.knocker_points() > 0 {
+            return -score.knocker_points();
+        }
+    }
+
+    if stock - 1 <= 2 {
+        return 0;
+    }
+    let Some(card) = pool.pop() else { return 0 };
+    let mut mine = mine.to_vec();
+    mine.push(card);
+    shed_worst(&mut mine, &[], params);
+    score_knock(&mine, &theirs).map_or(0, |score| score.knocker_points().max(0))
+}
+
+/// Discards the card that leaves the least deadwood, never throwing one of `keep`.
+fn shed_worst(hand: &mut Vec<Card>, keep: &[Card], params: &BotParams) {
+    let candidates = evaluate_discards(hand, params);
+    let worst = candidates
+        .iter()
+        .enumerate()
+        .filter(|(_, candidate)| !keep.contains(&candidate.card))
+        .max_by(|(_, a), (_, b)| a.score.total_cmp(&b.score))
+        .map(|(idx, _)| idx);
+    if let Some(idx) = worst {
+        hand.remove(idx);
+    }
+}
+
+/// Most opponent hands listed out before the search falls back to sampling.
+const ENDGAME_MAX_ENUMERATED: usize = 100_000;
+/// Deepest the endgame search goes, in discards, however much budget is left.
+const ENDGAME_MAX_HORIZON: u32 = 16;
+
+/// A decision the endgame search can recommend.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EndgameMove {
+    Draw(DrawSource),
+    Discard { index: usize, knock: bool },
+}
+
+/// One root move weighed by the endgame search.
+#[derive(Debug, Clone)]
+pub struct EndgameOption {
+    pub action: EndgameMove,
+    pub label: String,
+    pub expected_points: f64,
+}
+
+/// Result of an endgame search for the decision in front of the bot.
+#[derive(Debug, Clone)]
+pub struct EndgamePlan {
+    /// Opponent hands searched.
+    pub hands: usize,
+    /// Whether `hands` covers every opponent hand consistent with what is known.
+    pub exhaustive: bool,
+    /// Discards searched past the current decision, or `None` if every line
+    /// was followed to the end of the hand.
+    pub depth: Option<u32>,
+    /// Positions visited across all passes.
+    pub positions: usize,
+    pub options: Vec<EndgameOption>,
+    pub best: usize,
+}
+
+impl EndgamePlan {
+    pub fn choice(&self) -> EndgameMove {
+        self.options[self.best].action
+    }
+
+    pub fn summary(&self) -> String {
+        let best = &self.options[self.best];
+        let depth = match self.depth {
+            Some(depth) => format!("{depth} discards deep"),
+            None => "to the end of the hand".to_string(),
         };
-        let analysis = analyze_hand(&hypothetical);
-        if analysis.deadwood_value <= 10 {
-            knock = true;
+        format!(
+            "Endgame search over {} {} opponent hands, {depth} ({} positions): {} ({:+.1} expected points)",
+            self.hands,
+            if self.exhaustive {
+                "possible"
+            } else {
+                "sampled"
+            },
+            self.positions,
+            best.label,
+            best.expected_points
+        )
+    }
+}
+
+/// Expectimax over the rest of a hand. The bot maximises over its best
+/// discards and knocks, the opponent follows the plain heuristic and knocks
+/// whenever it wouldn't be undercut, and every card that could come off the
+/// stock is tried in turn. All moves go through `Game`, so the stock rule
+/// (drawing with two or fewer cards left ends the hand) and big gin apply.
+/// Lines still going after `horizon` discards score as a draw.
+struct EndgameSearch {
+    seat: PlayerId,
+    breadth: usize,
+    opponent_params: BotParams,
+    positions: usize,
+    /// Positions at which the current pass gives up.
+    limit: usize,
+    /// Whether the current pass hit `limit`, making its values meaningless.
+    aborted: bool,
+    /// Whether a line was cut off by the horizon before the hand ended.
+    truncated: bool,
+}
+
+/// Searches the rest of the hand for `seat` once the stock is at or below the
+/// profile's `endgame_stock`, returning the plan for its current decision.
+///
+/// The search deepens one discard at a time within `endgame_budget`
+/// positions. The first pass spends up to an eighth of the budget on as many
+/// opponent hands as it reaches: every hand consistent with what the bot has
+/// seen, in random order, or sampled hands when there are too many to list.
+/// Each deeper pass searches the same hands again and replaces the previous
+/// result only if it finishes within the budget; a pass that looks too big
+/// from the growth of the earlier ones is not started. The search stops once
+/// no line is cut off before the hand ends. The unseen cards outside the
+/// opponent's hand make up the stock, and every order they could be drawn
+/// in is tried.
+pub fn plan_endgame(
+    game: &Game,
+    seat: PlayerId,
+    params: &BotParams,
+    rng: &mut StdRng,
+) -> Option<EndgamePlan> {
+    if params.endgame_stock == 0
+        || game.stock.len() > params.endgame_stock
+        || game.current_player != seat
+        || game.phase == TurnPhase::RoundOver
+    {
+        return None;
+    }
+
+    let mut search = EndgameSearch {
+        seat,
+        breadth: params.endgame_breadth.max(1),
+        opponent_params: BotParams::default(),
+        positions: 0,
+        limit: 0,
+        aborted: false,
+        truncated: false,
+    };
+    let actions = search.moves(game);
+    if actions.is_empty() {
+        return None;
+    }
+    let budget = params.endgame_budget.max(1);
+    let listed = worlds_total(game, seat);
+
+    let mut worlds = Vec::new();
+    let mut totals = Vec::new();
+    let mut depth = 0;
+    let mut complete = false;
+    let mut pass_costs: Vec<usize> = Vec::new();
+    for horizon in 1..=ENDGAME_MAX_HORIZON {
+        // Skip a pass that would clearly not finish, assuming it grows like the last one.
+        if let [.., before, last] = pass_costs[..] {
+            let estimate = last.saturating_mul(last) / before.max(1);
+            if search.positions.saturating_add(estimate) > budget {
+                break;
+            }
+        }
+        let start = search.positions;
+        search.truncated = false;
+        search.aborted = false;
+        let mut pass = vec![0.0; actions.len()];
+        if horizon == 1 {
+            search.limit = budget / 8;
+            for world in EndgameWorlds::new(game, seat, params, rng)? {
+                let values = search.world_values(&world, &actions, horizon);
+                if search.aborted {
+                    break;
+                }
+                pass.iter_mut()
+                    .zip(values)
+                    .for_each(|(total, value)| *total += value);
+                worlds.push(world);
+            }
+        } else {
+            search.limit = budget;
+            for world in &worlds {
+                let values = search.world_values(world, &actions, horizon);
+                if search.aborted {
+                    break;
+                }
+                pass.iter_mut()
+                    .zip(values)
+                    .for_each(|(total, value)| *total += value);
+            }
+            if search.aborted {
+                break;
+            }
+        }
+        pass_costs.push(search.positions - start);
+        totals = pass;
+        depth = horizon;
+        if !search.truncated {
+            complete = true;
+            break;
+        }
+    }
+    if worlds.is_empty() {
+        return None;
+    }
+
+    let options: Vec<EndgameOption> = actions
+        .iter()
+        .zip(totals)
+        .map(|(&action, total)| EndgameOption {
+            action,
+            label: describe_endgame_move(game, seat, action),
+            expected_points: total / worlds.len() as f64,
+        })
+        .collect();
+
+    let mut best = 0;
+    for (idx, option) in options.iter().enumerate() {
+        if option.expected_points > options[best].expected_points {
+            best = idx;
         }
     }
+    Some(EndgamePlan {
+        hands: worlds.len(),
+        exhaustive: listed == Some(worlds.len()),
+        depth: if complete { None } else { Some(depth) },
+        positions: search.positions,
+        options,
+        best,
+    })
+}
+
+/// Number of opponent hands consistent with what `seat` has seen, if small
+/// enough to list.
+fn worlds_total(game: &Game, seat: PlayerId) -> Option<usize> {
+    let opponent = seat.other();
+    let unseen = unseen_cards(game, seat, &[]).len();
+    let hidden = game
+        .player(opponent)
+        .hand
+        .len()
+        .saturating_sub(game.known_cards(opponent).len());
+    combinations(unseen, hidden).filter(|&count| count <= ENDGAME_MAX_ENUMERATED)
+}
+
+/// Copies of `game` with the opponent's hand and the stock filled in from the
+/// unseen cards: every consistent opponent hand in random order when they can
+/// be listed, otherwise an endless run of sampled hands.
+struct EndgameWorlds<'a> {
+    game: &'a Game,
+    opponent: PlayerId,
+    unseen: Vec<Card>,
+    hands: EndgameHands<'a>,
+}
 
-    if difficulty == BotDifficulty::Easy && rand::random::<f32>() < 0.2 {
-        let mut rng = rand::thread_rng();
-        let random_index = (0..game.bot.hand.len())
-            .collect::<Vec<_>>()
-            .choose(&mut rng)
-            .copied();
-        if let Some(idx) = random_index {
-            return (idx, false);
+enum EndgameHands<'a> {
+    Listed(std::vec::IntoIter<Vec<Card>>),
+    Sampled {
+        known: Vec<Card>,
+        hidden: usize,
+        replayed: usize,
+        params: &'a BotParams,
+        rng: &'a mut StdRng,
+    },
+}
+
+impl<'a> EndgameWorlds<'a> {
+    fn new(
+        game: &'a Game,
+        seat: PlayerId,
+        params: &'a BotParams,
+        rng: &'a mut StdRng,
+    ) -> Option<Self> {
+        let opponent = seat.other();
+        let known = game.known_cards(opponent);
+        let unseen = unseen_cards(game, seat, &[]);
+        let hidden = game.player(opponent).hand.len().checked_sub(known.len())?;
+        if hidden > unseen.len() {
+            return None;
         }
+
+        let hands = if worlds_total(game, seat).is_some() {
+            let mut hands: Vec<Vec<Card>> = unseen
+                .iter()
+                .copied()
+                .combinations(hidden)
+                .map(|hidden_cards| known.iter().copied().chain(hidden_cards).collect())
+                .collect();
+            hands.shuffle(rng);
+            EndgameHands::Listed(hands.into_iter())
+        } else {
+            EndgameHands::Sampled {
+                known,
+                hidden,
+                replayed: game.turns_taken(opponent).min(params.replayed_draws),
+                params,
+                rng,
+            }
+        };
+        Some(Self {
+            game,
+            opponent,
+            unseen,
+            hands,
+        })
+    }
+}
+
+impl Iterator for EndgameWorlds<'_> {
+    type Item = Game;
+
+    fn next(&mut self) -> Option<Game> {
+        let mut hand = match &mut self.hands {
+            EndgameHands::Listed(hands) => hands.next()?,
+            EndgameHands::Sampled {
+                known,
+                hidden,
+                replayed,
+                params,
+                rng,
+            } => {
+                let mut pool = self.unseen.clone();
+                pool.shuffle(rng);
+                sample_opponent_hand(known, &mut pool, *hidden, *replayed, params)
+            }
+        };
+        hand.sort();
+        let mut world = self.game.clone();
+        world.stock = self
+            .unseen
+            .iter()
+            .copied()
+            .filter(|card| !hand.contains(card))
+            .collect();
+        world.player_mut(self.opponent).hand = hand;
+        Some(world)
+    }
+}
+
+/// `n` choose `k`, or `None` if it overflows.
+fn combinations(n: usize, k: usize) -> Option<usize> {
+    if k > n {
+        return Some(0);
+    }
+    let k = k.min(n - k);
+    let mut count: usize = 1;
+    for i in 0..k {
+        count = count.checked_mul(n - i)? / (i + 1);
+    }
+    Some(count)
+}
+
+impl EndgameSearch {
+    /// The moves worth searching for the player to act in `game`.
+    fn moves(&self, game: &Game) -> Vec<EndgameMove> {
+        let seat = game.current_player;
+        match game.phase {
+            TurnPhase::AwaitDraw => {
+                let mut moves = vec![EndgameMove::Draw(DrawSource::Stock)];
+                if !game.discard.is_empty() {
+                    moves.push(EndgameMove::Draw(DrawSource::Discard));
+                }
+                moves
+            }
+            TurnPhase::AwaitDiscard => {
+                let hand = &game.player(seat).hand;
+                let mut candidates: Vec<(usize, DiscardCandidate)> =
+                    evaluate_discards(hand, &self.opponent_params)
+                        .into_iter()
+                        .enumerate()
+                        .collect();
+                candidates.sort_by(|(_, a), (_, b)| b.score.total_cmp(&a.score));
+                let mut moves = Vec::new();
+                for (index, candidate) in candidates.into_iter().take(self.breadth) {
+                    moves.push(EndgameMove::Discard {
+                        index,
+                        knock: false,
+                    });
+                    if candidate.deadwood <= 10 {
+                        moves.push(EndgameMove::Discard { index, knock: true });
+                    }
+                }
+                moves
+            }
+            TurnPhase::RoundOver => Vec::new(),
+        }
+    }
+
+    /// Expected points for the searching seat after each of `actions` in `world`.
+    fn world_values(&mut self, world: &Game, actions: &[EndgameMove], horizon: u32) -> Vec<f64> {
+        actions
+            .iter()
+            .map(|&action| self.move_value(world, action, horizon))
+            .collect()
     }
 
-    (best_index, knock)
+    /// Expected points for the searching seat after `action` in `game`.
+    fn move_value(&mut self, game: &Game, action: EndgameMove, horizon: u32) -> f64 {
+        match action {
+            EndgameMove::Draw(DrawSource::Stock) if game.stock.len() > 2 => {
+                // Chance node: each unseen stock card is equally likely to be on top.
+                let mut total = 0.0;
+                for idx in 0..game.stock.len() {
+                    let mut next = game.clone();
+                    let last = next.stock.len() - 1;
+                    next.stock.swap(idx, last);
+                    total += self.apply(next, action, horizon);
+                }
+                total / game.stock.len() as f64
+            }
+            _ => self.apply(game.clone(), action, horizon),
+        }
+    }
+
+    fn apply(&mut self, mut game: Game, action: EndgameMove, horizon: u32) -> f64 {
+        if self.positions >= self.limit {
+            self.aborted = true;
+            return 0.0;
+        }
+        self.positions += 1;
+        let seat = game.current_player;
+        let (applied, horizon) = match action {
+            EndgameMove::Draw(source) => (game.draw(seat, source), horizon),
+            EndgameMove::Discard { index, knock } => {
+                (game.discard(seat, index, knock), horizon.saturating_sub(1))
+            }
+        };
+        if applied.is_err() {
+            return f64::NEG_INFINITY;
+        }
+        self.value(&game, horizon)
+    }
+
+    fn value(&mut self, game: &Game, horizon: u32) -> f64 {
+        if game.phase == TurnPhase::RoundOver {
+            return game
+                .pending_round
+                .as_ref()
+                .map_or(0.0, |result| match result.winner {
+                    Some(winner) if winner == self.seat => result.points_awarded as f64,
+                    Some(_) => -(result.points_awarded as f64),
+                    None => 0.0,
+                });
+        }
+
+        if horizon == 0 {
+            self.truncated = true;
+            return 0.0;
+        }
+
+        if game.current_player == self.seat {
+            let mut best = f64::NEG_INFINITY;
+            for action in self.moves(game) {
+                best = best.max(self.move_value(game, action, horizon));
+            }
+            best
+        } else {
+            let action = self.opponent_move(game);
+            self.move_value(game, action, horizon)
+        }
+    }
+
+    /// The opponent model: heuristic draws and discards, knocking whenever the
+    /// knock would not be undercut.
+    fn opponent_move(&self, game: &Game) -> EndgameMove {
+        let seat = game.current_player;
+        let hand = &game.player(seat).hand;
+        match game.phase {
+            TurnPhase::AwaitDraw => EndgameMove::Draw(
+                evaluate_draw(hand, game.discard.last().copied(), &self.opponent_params).choice,
+            ),
+            _ => {
+                let index = best_candidate(&evaluate_discards(hand, &self.opponent_params));
+                let mut remaining = hand.clone();
+                remaining.remove(index);
+                let knock = score_knock(&remaining, &game.player(seat.other()).hand)
+                    .is_some_and(|score| !score.undercut);
+                EndgameMove::Discard { index, knock }
+            }
+        }
+    }
+}
+
+/// Summarises the search's verdict on knocking with the discard at `index`.
+fn endgame_knock_check(plan: &EndgamePlan, index: usize) -> KnockCheck {
+    let value = |knock: bool| {
+        plan.options
+            .iter()
+            .find(|option| option.action == EndgameMove::Discard { index, knock })
+            .map(|option| option.expected_points)
+    };
+    let detail = match (value(true), value(false)) {
+        (Some(knock), Some(play_on)) => {
+            format!("{knock:+.1} knocking vs {play_on:+.1} playing on")
+        }
+        (None, Some(play_on)) => format!("{play_on:+.1} playing on"),
+        _ => "no evaluation".to_string(),
+    };
+    KnockCheck {
+        label: "Endgame search".to_string(),
+        detail,
+        passed: matches!(plan.choice(), EndgameMove::Discard { knock: true, .. }),
+    }
+}
+
+fn describe_endgame_move(game: &Game, seat: PlayerId, action: EndgameMove) -> String {
+    match action {
+        EndgameMove::Draw(DrawSource::Stock) => "draw from the stock".to_string(),
+        EndgameMove::Draw(DrawSource::Discard) => match game.discard.last() {
+            Some(card) => format!("take the {card}"),
+            None => "take the discard".to_string(),
+        },
+        EndgameMove::Discard { index, knock } => {
+            let card = game.player(seat).hand[index];
+            if knock {
+                format!("discard the {card} and knock")
+            } else {
+                format!("discard the {card}")
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::Move;
+
+    fn cards(labels: &str) -> Vec<Card> {
+        labels
+            .split_whitespace()
+            .map(|label| label.parse().unwrap())
+            .collect()
+    }
+
+    /// The bot to move with the given hands and stock; every other card is
+    /// in the discard pile under `top`.
+    fn endgame(bot: &str, human: &str, stock: &str, top: &str, phase: TurnPhase) -> Game {
+        let mut game = Game::with_seed(0).unwrap();
+        game.bot.hand = cards(bot);
+        game.human.hand = cards(human);
+        game.stock = cards(stock);
+        let top: Card = top.parse().unwrap();
+        let dealt: Vec<Card> = game
+            .bot
+            .hand
+            .iter()
+            .chain(&game.human.hand)
+            .chain(&game.stock)
+            .copied()
+            .chain([top])
+            .collect();
+        game.discard = build_deck()
+            .into_iter()
+            .filter(|card| !dealt.contains(card))
+            .chain([top])
+            .collect();
+        game.moves.clear();
+        game.current_player = PlayerId::Bot;
+        game.phase = phase;
+        game
+    }
+
+    fn plan(game: &Game) -> EndgamePlan {
+        let params = BotParams::default();
+        let mut rng = StdRng::seed_from_u64(7);
+        plan_endgame(game, PlayerId::Bot, &params, &mut rng).unwrap()
+    }
+
+    #[test]
+    fn endgame_takes_the_discard_for_gin() {
+        // The KC completes big gin; a stock draw with two cards left ends the hand.
+        let game = endgame(
+            "AS 2S 3S 4H 4D 4C 9C 10C JC QC",
+            "5S 7S 9S JS KS 6H 8H 10H QH 6D",
+            "8D 10D",
+            "KC",
+            TurnPhase::AwaitDraw,
+        );
+        let plan = plan(&game);
+        assert_eq!(plan.choice(), EndgameMove::Draw(DrawSource::Discard));
+        assert!(plan.exhaustive);
+        assert_eq!(plan.hands, 66);
+    }
+
+    #[test]
+    fn endgame_knocks_against_any_possible_hand() {
+        // None of the twelve unseen cards meld, so every opponent hand loses to a knock.
+        let game = endgame(
+            "AS 2S 3S 4H 4D 4C 9C 10C JC 2H KD",
+            "5S 7S 9S JS KS 6H 8H 10H QH 6D",
+            "8D 10D",
+            "QC",
+            TurnPhase::AwaitDiscard,
+        );
+        let plan = plan(&game);
+        let king = game
+            .bot
+            .hand
+            .iter()
+            .position(|card| card.to_string() == "KD");
+        assert_eq!(
+            plan.choice(),
+            EndgameMove::Discard {
+                index: king.unwrap(),
+                knock: true
+            }
+        );
+        assert!(plan.exhaustive);
+    }
+
+    #[test]
+    fn endgame_does_not_knock_into_an_undercut() {
+        // The opponent picked up all ten of its cards from the discard pile, so
+        // its hand (deadwood 2) is known and would undercut a knock on 9.
+        let mut game = endgame(
+            "AS 2S 3S 4H 4D 4C 9C 10C JC 9H KD",
+            "5D 6D 7D 5H 6H 7H 8C 8S 8H 2C",
+            "QS KS",
+            "QC",
+            TurnPhase::AwaitDiscard,
+        );
+        for &card in &game.human.hand.clone() {
+            game.moves.push(Move::Draw {
+                player: PlayerId::Human,
+                source: DrawSource::Discard,
+                card,
+            });
+        }
+        let plan = plan(&game);
+        assert_eq!(plan.hands, 1);
+        assert!(plan.exhaustive);
+        assert!(matches!(
+            plan.choice(),
+            EndgameMove::Discard { knock: false, .. }
+        ));
+        let knock = plan
+            .options
+            .iter()
+            .filter(|option| matches!(option.action, EndgameMove::Discard { knock: true, .. }))
+            .map(|option| option.expected_points)
+            .fold(f64::NEG_INFINITY, f64::max);
+        assert!(knock < 0.0);
+    }
+
+    #[test]
+    fn sampled_opponent_hands_keep_the_known_cards() {
+        let known = cards("5D 6D 7D");
+        let unseen = cards("2C 3C 4C 5C 6C 7C 8C 9C KS QS JS 10S");
+        let params = BotParams::default();
+
+        let mut pool = unseen.clone();
+        let dealt = sample_opponent_hand(&known, &mut pool, 7, 0, &params);
+        assert_eq!(dealt.len(), 10);
+        assert_eq!(dealt[..3], known[..]);
+        assert_eq!(pool, unseen[7..]);
+
+        // Each replayed draw keeps the better card, so the hand only improves.
+        let mut pool = unseen.clone();
+        let replayed = sample_opponent_hand(&known, &mut pool, 7, 3, &params);
+        assert_eq!(replayed.len(), 10);
+        assert!(known.iter().all(|card| replayed.contains(card)));
+        assert_eq!(pool, unseen[7..9]);
+        assert!(analyze_hand(&replayed).deadwood_value <= analyze_hand(&dealt).deadwood_value);
+    }
+
+    /// The bot about to discard the KD and knock on 9, against an opponent
+    /// whose whole hand was picked up from the discard pile.
+    fn knock_against(human: &str) -> (Game, usize, Vec<Card>) {
+        let mut game = endgame(
+            "AS 2S 3S 4H 4D 4C 9C 10C JC 9H KD",
+            human,
+            "QS KS",
+            "QC",
+            TurnPhase::AwaitDiscard,
+        );
+        for &card in &game.human.hand.clone() {
+            game.moves.push(Move::Draw {
+                player: PlayerId::Human,
+                source: DrawSource::Discard,
+                card,
+            });
+        }
+        let king = game
+            .bot
+            .hand
+            .iter()
+            .position(|card| card.to_string() == "KD")
+            .unwrap();
+        let mut remaining = game.bot.hand.clone();
+        remaining.remove(king);
+        (game, king, remaining)
+    }
+
+    #[test]
+    fn a_certain_undercut_is_priced_in_and_the_bot_plays_on() {
+        let (game, king, remaining) = knock_against("5D 6D 7D 5H 6H 7H 8C 8S 8H 2C");
+        let score = score_knock(&remaining, &game.human.hand).unwrap();
+        assert!(score.undercut);
+        let params = BotParams::default();
+        let mut rng = StdRng::seed_from_u64(1);
+        let discarded = game.bot.hand[king];
+
+        let risk = estimate_knock_risk(
+            &game,
+            PlayerId::Bot,
+            &remaining,
+            discarded,
+            &params,
+            &mut rng,
+        );
+        assert_eq!(risk.samples, params.risk_samples);
+        assert_eq!(risk.undercut_rate, 1.0);
+        assert_eq!(risk.knock_points, score.knocker_points() as f64);
+        // Drawing from a two-card stock ends the hand, so waiting scores nothing.
+        assert_eq!(risk.wait_points, 0.0);
+
+        let trace = evaluate_knock_risk(&game, PlayerId::Bot, king, &params, &mut rng);
+        assert_eq!(trace.deadwood_after_discard, 9);
+        assert!(!trace.knock);
+    }
+
+    #[test]
+    fn a_safe_knock_is_taken_only_when_it_clears_the_minimum() {
+        let (game, king, remaining) = knock_against("QS KS QD KH 10S 10D 8D 6D 7S 5C");
+        let score = score_knock(&remaining, &game.human.hand).unwrap();
+        assert!(!score.undercut);
+        let mut params = BotParams::default();
+        let mut rng = StdRng::seed_from_u64(1);
+        let discarded = game.bot.hand[king];
+
+        let risk = estimate_knock_risk(
+            &game,
+            PlayerId::Bot,
+            &remaining,
+            discarded,
+            &params,
+            &mut rng,
+        );
+        assert_eq!(risk.undercut_rate, 0.0);
+        assert_eq!(risk.knock_points, score.knocker_points() as f64);
+        assert!(risk.knock_points > risk.wait_points);
+        assert!(evaluate_knock_risk(&game, PlayerId::Bot, king, &params, &mut rng).knock);
+
+        params.min_knock_points = risk.knock_points;
+        let trace = evaluate_knock_risk(&game, PlayerId::Bot, king, &params, &mut rng);
+        assert!(!trace.knock);
+        assert!(trace
+            .checks
+            .iter()
+            .any(|check| check.label == "Undercut risk" && !check.passed));
+    }
+
+    #[test]
+    fn heuristic_turn_records_its_draw_discard_and_knock() {
+        let mut game = Game::with_seed(3).unwrap();
+        game.current_player = PlayerId::Bot;
+        game.phase = TurnPhase::AwaitDraw;
+        let hand = game.bot.hand.clone();
+        let top = game.discard.last().copied();
+        let mut strategy = HeuristicStrategy::new(BotDifficulty::Challenging);
+        strategy.on_round_start(&game, PlayerId::Bot);
+        take_turn(&mut game, PlayerId::Bot, &mut strategy).unwrap();
+
+        let trace = strategy.last_trace().unwrap();
+        let draw = trace.draw.as_ref().unwrap();
+        let discard = trace.discard.as_ref().unwrap();
+        let knock = trace.knock.as_ref().unwrap();
+        assert_eq!(draw.top_discard, top);
+        assert_eq!(draw.current_deadwood, analyze_hand(&hand).deadwood_value);
+        assert_eq!(discard.candidates.len(), 11);
+        assert!(!discard.randomised);
+        assert!(discard
+            .candidates
+            .iter()
+            .any(|candidate| candidate.card == discard.chosen));
+        assert!(!knock.checks.is_empty());
+        assert!(matches!(
+            game.moves[..],
+            [
+                Move::Draw { source, .. },
+                Move::Discard { card, knock: knocked, .. },
+            ] if source == draw.choice && card == discard.chosen && knocked == knock.knock
+        ));
+    }
+
+    fn won_by(winner: Option<PlayerId>) -> RoundResult {
+        RoundResult {
+            winner,
+            points_awarded: 10,
+            reason: crate::game::RoundEndReason::StockDepleted,
+            human_hand: Vec::new(),
+            bot_hand: Vec::new(),
+        }
+    }
+
+    fn adaptive_levels(winner: PlayerId) -> (AdaptiveStrategy, Vec<f64>) {
+        let game = Game::with_seed(1).unwrap();
+        let mut bot = AdaptiveStrategy::new();
+        bot.on_round_start(&game, PlayerId::Bot);
+        let mut levels = vec![bot.effective_level().unwrap()];
+        for _ in 0..ADAPTIVE_WINDOW {
+            bot.on_round_end(&game, PlayerId::Bot, &won_by(Some(winner)));
+            levels.push(bot.effective_level().unwrap());
+        }
+        (bot, levels)
+    }
+
+    #[test]
+    fn adaptive_bot_eases_off_while_it_keeps_winning() {
+        let (bot, levels) = adaptive_levels(PlayerId::Bot);
+        assert_eq!(levels[0], 0.5);
+        assert!(
+            levels.windows(2).all(|pair| pair[1] <= pair[0]),
+            "{levels:?}"
+        );
+        assert!(levels[1] < levels[0]);
+        assert_eq!(bot.effective_level(), Some(0.0));
+        assert_eq!(
+            bot.inner.params,
+            BotParams {
+                name: DEFAULT_STRATEGY.to_string(),
+                ..BotParams::for_difficulty(BotDifficulty::Easy)
+            }
+        );
+    }
+
+    #[test]
+    fn adaptive_bot_tightens_up_while_the_human_keeps_winning() {
+        let (bot, levels) = adaptive_levels(PlayerId::Human);
+        assert!(
+            levels.windows(2).all(|pair| pair[1] >= pair[0]),
+            "{levels:?}"
+        );
+        assert!(levels[1] > levels[0]);
+        assert_eq!(bot.effective_level(), Some(1.0));
+        assert_eq!(bot.inner.params, BotParams::default());
+        assert_eq!(bot.name(), DEFAULT_STRATEGY);
+    }
+
+    #[test]
+    fn adaptive_bot_starts_from_the_stored_hand_counts() {
+        let mut game = Game::with_seed(1).unwrap();
+        game.scoreboard.human_hands_won = 6;
+        let mut bot = AdaptiveStrategy::new();
+        bot.on_round_start(&game, PlayerId::Bot);
+        assert_eq!(bot.effective_level(), Some(1.0));
+        // Stored counts only seed the first round.
+        game.scoreboard.bot_hands_won = 20;
+        bot.on_round_start(&game, PlayerId::Bot);
+        assert_eq!(bot.effective_level(), Some(1.0));
+    }
+
+    fn profile(name: &str) -> BotParams {
+        BotParams {
+            name: name.to_string(),
+            ..BotParams::for_difficulty(BotDifficulty::Easy)
+        }
+    }
+
+    #[test]
+    fn profiles_cannot_replace_builtin_strategies() {
+        let mut registry = StrategyRegistry::builtin();
+        let err = registry.register_profile(profile("Heuristic")).unwrap_err();
+        assert!(err.to_string().contains("built-in strategy"));
+        assert!(registry.register_profile(profile(ENGINE_STRATEGY)).is_err());
+        assert_eq!(
+            registry.describe(DEFAULT_STRATEGY),
+            Some("Deadwood-minimising heuristics with difficulty-based knocking.")
+        );
+
+        registry.register_profile(profile("gin-hunter")).unwrap();
+        assert!(registry.names().any(|name| name == "gin-hunter"));
+    }
 }
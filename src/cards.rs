@@ -1,4 +1,9 @@
-use std::fmt::{Display, Formatter};
+use std::{
+    fmt::{Display, Formatter},
+    str::FromStr,
+};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 pub const HAND_SIZE: usize = 10;
 
@@ -21,6 +26,13 @@ impl Suit {
             Suit::Spades => 'S',
         }
     }
+
+    /// The suit for a letter (`C`, `D`, `H`, `S`), in either case.
+    pub fn from_symbol(symbol: char) -> Option<Self> {
+        Suit::ALL
+            .into_iter()
+            .find(|suit| suit.symbol().eq_ignore_ascii_case(&symbol))
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Ord, PartialOrd)]
@@ -89,6 +101,17 @@ impl Rank {
             Rank::King => "K",
         }
     }
+
+    /// The rank for a short name (`A`, `2`…`10`, `J`, `Q`, `K`), in either case.
+    /// `T` is accepted for ten as well.
+    pub fn from_short_name(name: &str) -> Option<Self> {
+        if name.eq_ignore_ascii_case("T") {
+            return Some(Rank::Ten);
+        }
+        Rank::ALL
+            .into_iter()
+            .find(|rank| rank.short_name().eq_ignore_ascii_case(name))
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Ord, PartialOrd)]
@@ -107,6 +130,11 @@ impl Card {
         format!("{}{}", self.rank.short_name(), self.suit.symbol())
     }
 
+    /// Looks up a card by its label (`7H`, `10S`), ignoring case.
+    pub fn from_label(label: &str) -> Option<Self> {
+        label.parse().ok()
+    }
+
     pub fn face_down() -> &'static str {
         "##"
     }
@@ -117,3 +145,126 @@ impl Display for Card {
         write!(f, "{}{}", self.rank.short_name(), self.suit.symbol())
     }
 }
+
+/// Why a card label could not be read.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseCardError {
+    label: String,
+    reason: String,
+}
+
+impl ParseCardError {
+    fn new(label: &str, reason: impl Into<String>) -> Self {
+        Self {
+            label: label.to_string(),
+            reason: reason.into(),
+        }
+    }
+}
+
+impl Display for ParseCardError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid card '{}': {}", self.label, self.reason)
+    }
+}
+
+impl std::error::Error for ParseCardError {}
+
+/// Parses a rank followed by a suit letter: `7H`, `10s`, `Td`, `qc`.
+impl FromStr for Card {
+    type Err = ParseCardError;
+
+    fn from_str(label: &str) -> Result<Self, Self::Err> {
+        let trimmed = label.trim();
+        let Some(symbol) = trimmed.chars().last() else {
+            return Err(ParseCardError::new(
+                label,
+                "expected a rank and a suit, e.g. 7H",
+            ));
+        };
+        let rank = &trimmed[..trimmed.len() - symbol.len_utf8()];
+        let suit = Suit::from_symbol(symbol).ok_or_else(|| {
+            if symbol.is_ascii_digit() || rank.is_empty() {
+                ParseCardError::new(label, "missing suit (C, D, H or S)")
+            } else {
+                ParseCardError::new(label, format!("unknown suit '{symbol}' (C, D, H or S)"))
+            }
+        })?;
+        if rank.is_empty() {
+            return Err(ParseCardError::new(
+                label,
+                "missing rank (A, 2-10, J, Q or K)",
+            ));
+        }
+        let rank = Rank::from_short_name(rank).ok_or_else(|| {
+            ParseCardError::new(label, format!("unknown rank '{rank}' (A, 2-10, J, Q or K)"))
+        })?;
+        Ok(Card::new(rank, suit))
+    }
+}
+
+/// Cards are stored by label so saved files stay readable.
+impl Serialize for Card {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Card {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let label = String::deserialize(deserializer)?;
+        label.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error(label: &str) -> String {
+        label.parse::<Card>().unwrap_err().to_string()
+    }
+
+    #[test]
+    fn labels_parse_in_any_case_and_with_t_for_ten() {
+        for (label, expected) in [
+            ("7H", "7H"),
+            ("10s", "10S"),
+            ("Td", "10D"),
+            ("qc", "QC"),
+            (" AS ", "AS"),
+        ] {
+            let card: Card = label.parse().unwrap();
+            assert_eq!(card.to_string(), expected, "{label}");
+        }
+    }
+
+    #[test]
+    fn bad_labels_say_what_is_wrong() {
+        assert_eq!(
+            error("10"),
+            "invalid card '10': missing suit (C, D, H or S)"
+        );
+        assert_eq!(error("K"), "invalid card 'K': missing suit (C, D, H or S)");
+        assert_eq!(
+            error("H"),
+            "invalid card 'H': missing rank (A, 2-10, J, Q or K)"
+        );
+        assert_eq!(
+            error(""),
+            "invalid card '': expected a rank and a suit, e.g. 7H"
+        );
+        assert_eq!(
+            error("7X"),
+            "invalid card '7X': unknown suit 'X' (C, D, H or S)"
+        );
+        assert_eq!(
+            error("1H"),
+            "invalid card '1H': unknown rank '1' (A, 2-10, J, Q or K)"
+        );
+        assert_eq!(
+            error("11h"),
+            "invalid card '11h': unknown rank '11' (A, 2-10, J, Q or K)"
+        );
+    }
+}
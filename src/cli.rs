@@ -0,0 +1,904 @@
+//! Command-line parsing for the `deadwood` binary and the subcommands that run
+//! without the terminal UI.
+
+use std::{
+    cmp::Reverse,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{anyhow, bail, Result};
+use itertools::Itertools;
+use serde::Serialize;
+
+use crate::{
+    app::{strategy_registry, LaunchOptions},
+    archive::{self, MeldRecord},
+    bot::{BotDifficulty, StrategyRegistry, DEFAULT_STRATEGY},
+    cards::{Card, HAND_SIZE},
+    config::{Config, ConfigSource, CONFIG_ENV_PREFIX},
+    game::Rules,
+    meld::{analyze_hand, best_arrangements},
+    notation::{self, NotatedHand},
+    params::BotParams,
+    sim::{self, Contender, SimOptions, TuneOptions},
+    stats::StatsReport,
+    storage::{validate_profile_name, Paths, DEFAULT_PROFILE},
+};
+
+pub const USAGE: &str = "Usage: deadwood [OPTIONS] [COMMAND]
+
+Commands:
+  play                 Play in the terminal (the default)
+  stats                Print statistics from the profile's hand archive
+  export [N]           Print archived hand N (1 = oldest; default: the latest) in
+                       the text notation, or write it to --out PATH
+  analyze CARD...      Show the melds and deadwood of a hand, e.g. 7H 8H 9H KS KD KC,
+                       every arrangement tied for the lowest deadwood, and for
+                       11 cards the best discards (--json for JSON output)
+  sim [ARGS...]        Run bot-vs-bot matches (see `deadwood sim --help`)
+  config [check]       Print the effective settings and where each one came from,
+                       or check them for problems (exits non-zero if any)
+
+Options:
+  -p, --profile NAME       Profile to use instead of choosing one at startup
+  -d, --difficulty LEVEL   Bot difficulty: easy, challenging or adaptive
+      --seed N             Seed the shuffles so the same deals come up again
+      --rules VARIANT      Rules variant (standard)
+      --config PATH        Read shared settings from this config file
+      --data-dir PATH      Keep profiles, sessions and archives in this directory
+      --no-persist         Don't load or save the session or the hand archive
+      --replay FILE        Open a hand file in the replay viewer
+      --puzzles FILE       Start in puzzle mode with a puzzle file
+  -h, --help               Show this help
+  -V, --version            Show the version";
+
+/// What the binary was asked to do.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    Play,
+    Stats,
+    Export {
+        /// 1-based position in the archive; the latest hand when unset.
+        hand: Option<usize>,
+        out: Option<PathBuf>,
+    },
+    Analyze {
+        cards: Vec<String>,
+        json: bool,
+    },
+    /// Arguments for the simulator, passed through unparsed.
+    Sim {
+        args: Vec<String>,
+    },
+    /// Print the merged configuration with the source of each value, or
+    /// with `check`, report every problem in it.
+    Config {
+        check: bool,
+    },
+    Help,
+    Version,
+}
+
+#[derive(Debug, Clone)]
+pub struct Cli {
+    pub options: LaunchOptions,
+    pub command: Command,
+}
+
+/// Parses the arguments after the program name. Options may come before or
+/// after the command, except with `sim`, which takes everything after it.
+pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Cli> {
+    let mut args = args.into_iter();
+    let mut options = LaunchOptions::default();
+    let mut command: Option<Command> = None;
+    let mut positional = Vec::new();
+    let mut out = None;
+    let mut json = false;
+
+    while let Some(arg) = args.next() {
+        if command.is_none() && !arg.starts_with('-') {
+            command = Some(match arg.as_str() {
+                "play" => Command::Play,
+                "stats" => Command::Stats,
+                "export" => Command::Export {
+                    hand: None,
+                    out: None,
+                },
+                "analyze" | "analyse" => Command::Analyze {
+                    cards: Vec::new(),
+                    json: false,
+                },
+                "sim" => {
+                    let mut forwarded: Vec<String> = args.by_ref().collect();
+                    if let Some(seed) = options.seed {
+                        if !forwarded.iter().any(|arg| arg.starts_with("--seed")) {
+                            forwarded.splice(0..0, ["--seed".to_string(), seed.to_string()]);
+                        }
+                    }
+                    Command::Sim { args: forwarded }
+                }
+                "config" => Command::Config { check: false },
+                "help" => Command::Help,
+                _ => bail!("unknown command '{arg}'\n\n{USAGE}"),
+            });
+            continue;
+        }
+        if !arg.starts_with('-') {
+            positional.push(arg);
+            continue;
+        }
+
+        let (flag, inline_value) = match arg.split_once('=') {
+            Some((flag, value)) => (flag.to_string(), Some(value.to_string())),
+            None => (arg.clone(), None),
+        };
+        let mut value = |expected: &str| {
+            inline_value
+                .clone()
+                .or_else(|| args.next())
+                .ok_or_else(|| anyhow!("{flag} expects {expected}"))
+        };
+        match flag.as_str() {
+            "--difficulty" | "-d" => {
+                let value = value("a value (easy, challenging or adaptive)")?;
+                options.difficulty = Some(BotDifficulty::from_name(&value).ok_or_else(|| {
+                    anyhow!("unknown difficulty '{value}' (easy, challenging or adaptive)")
+                })?);
+            }
+            "--profile" | "-p" => {
+                options.profile = Some(validate_profile_name(&value("a profile name")?)?)
+            }
+            "--seed" => {
+                options.seed = Some(
+                    value("a number")?
+                        .parse()
+                        .map_err(|_| anyhow!("--seed expects an unsigned number"))?,
+                );
+            }
+            "--rules" => {
+                let value = value("a rules variant")?;
+                options.rules = Some(Rules::from_name(&value).ok_or_else(|| {
+                    anyhow!(
+                        "unknown rules variant '{value}' (supported: {})",
+                        Rules::names()
+                    )
+                })?);
+            }
+            "--config" => options.config_file = Some(PathBuf::from(value("a file path")?)),
+            "--data-dir" => options.data_dir = Some(PathBuf::from(value("a directory")?)),
+            "--no-persist" => options.no_persist = true,
+            "--replay" => options.replay = Some(PathBuf::from(value("a hand file")?)),
+            "--puzzles" => options.puzzles = Some(PathBuf::from(value("a puzzle file")?)),
+            "--out" | "-o" => out = Some(PathBuf::from(value("a file path")?)),
+            "--json" => json = true,
+            "--help" | "-h" => command = Some(Command::Help),
+            "--version" | "-V" => command = Some(Command::Version),
+            _ => bail!("unrecognised argument '{arg}'\n\n{USAGE}"),
+        }
+    }
+
+    let command = command.unwrap_or(Command::Play);
+    if out.is_some() && !matches!(command, Command::Export { .. }) {
+        bail!("--out only applies to the export command\n\n{USAGE}");
+    }
+    if json && !matches!(command, Command::Analyze { .. }) {
+        bail!("--json only applies to the analyze command\n\n{USAGE}");
+    }
+    let command = match command {
+        Command::Export { .. } => {
+            if positional.len() > 1 {
+                bail!("export takes at most one hand number");
+            }
+            let hand = positional
+                .pop()
+                .map(|value| match value.parse::<usize>() {
+                    Ok(number) if number > 0 => Ok(number),
+                    _ => Err(anyhow!(
+                        "export expects a hand number from 1, not '{value}'"
+                    )),
+                })
+                .transpose()?;
+            Command::Export { hand, out }
+        }
+        Command::Analyze { .. } => {
+            if positional.is_empty() {
+                bail!("analyze expects the cards of a hand, e.g. deadwood analyze 7H 8H 9H KS");
+            }
+            Command::Analyze {
+                cards: positional,
+                json,
+            }
+        }
+        Command::Config { .. } => match positional.as_slice() {
+            [] => Command::Config { check: false },
+            [action] if action == "show" => Command::Config { check: false },
+            [action] if action == "check" => Command::Config { check: true },
+            [action, ..] => bail!("unknown config action '{action}' (show or check)"),
+        },
+        other => {
+            if let Some(extra) = positional.first() {
+                bail!("unexpected argument '{extra}'\n\n{USAGE}");
+            }
+            other
+        }
+    };
+    Ok(Cli { options, command })
+}
+
+/// The profile a headless command reads: `--profile`, else the one used last.
+/// These commands only read, so nothing is created and the last profile is
+/// not updated.
+fn headless_paths(options: &LaunchOptions) -> Result<Paths> {
+    let profile = match &options.profile {
+        Some(profile) => profile.clone(),
+        None => Paths::last_profile(options.data_dir.as_deref())?
+            .unwrap_or_else(|| DEFAULT_PROFILE.to_string()),
+    };
+    let paths = Paths::existing(&profile, options.data_dir.as_deref())?;
+    Ok(match &options.config_file {
+        Some(config_file) => paths.with_config_file(config_file.clone()),
+        None => paths,
+    })
+}
+
+fn load_archive(paths: &Paths) -> Result<Vec<archive::HandRecord>> {
+    let contents = archive::load(paths.archive_file())?;
+    if !contents.skipped.is_empty() {
+        eprintln!(
+            "warning: skipped {} unreadable hand(s) in {}",
+            contents.skipped.len(),
+            paths.archive_file().display()
+        );
+    }
+    Ok(contents.records)
+}
+
+/// `deadwood stats`: the statistics screen as plain text.
+pub fn run_stats(options: &LaunchOptions) -> Result<()> {
+    let paths = headless_paths(options)?;
+    let records = load_archive(&paths)?;
+    let report = StatsReport::from_records(&records);
+    if report.overall.hands == 0 {
+        println!(
+            "No hands recorded for profile '{}' yet ({}).",
+            paths.profile(),
+            paths.archive_file().display()
+        );
+        return Ok(());
+    }
+    println!("Statistics for profile '{}'", paths.profile());
+    for line in report.table() {
+        println!("{}", line.trim_end());
+    }
+    Ok(())
+}
+
+/// `deadwood export`: an archived hand in the text notation.
+pub fn run_export(options: &LaunchOptions, hand: Option<usize>, out: Option<&Path>) -> Result<()> {
+    let paths = headless_paths(options)?;
+    let records = load_archive(&paths)?;
+    let index = match hand {
+        Some(number) => number - 1,
+        None => records
+            .len()
+            .checked_sub(1)
+            .ok_or_else(|| anyhow!("profile '{}' has no archived hands", paths.profile()))?,
+    };
+    let record = records.get(index).ok_or_else(|| {
+        anyhow!(
+            "profile '{}' has {} archived hand(s); there is no hand {}",
+            paths.profile(),
+            records.len(),
+            index + 1
+        )
+    })?;
+    let notated = NotatedHand::from_record(record, paths.profile());
+    match out {
+        Some(path) => {
+            notation::write_file(path, &notated)?;
+            eprintln!("Wrote hand {} to {}", index + 1, path.display());
+        }
+        None => print!("{}", notation::write(&notated)),
+    }
+    Ok(())
+}
+
+/// `deadwood config`: the merged settings as TOML, each commented with its source.
+pub fn run_config(options: &LaunchOptions) -> Result<()> {
+    let paths = headless_paths(options)?;
+    let outcome = Config::load(&paths, &options.config_overrides())?;
+    for warning in &outcome.warnings {
+        eprintln!("warning: {warning}");
+    }
+    let effective = &outcome.effective;
+    println!(
+        "# Settings for profile '{}', lowest precedence first:",
+        paths.profile()
+    );
+    println!("#   defaults");
+    for (source, exists) in effective.files() {
+        println!("#   {source}{}", if *exists { "" } else { " (not found)" });
+    }
+    println!("#   {CONFIG_ENV_PREFIX}* environment variables, then command-line flags");
+    let lines: Vec<(String, &ConfigSource)> = effective
+        .entries()
+        .into_iter()
+        .map(|(key, value, source)| (format!("{key} = {value}"), source))
+        .collect();
+    let width = lines.iter().map(|(line, _)| line.len()).max().unwrap_or(0);
+    for (line, source) in lines {
+        println!("{line:<width$}  # {source}");
+    }
+    Ok(())
+}
+
+/// `deadwood config check`: lists every problem in the merged settings and
+/// fails if there are any.
+pub fn run_config_check(options: &LaunchOptions) -> Result<()> {
+    let paths = headless_paths(options)?;
+    let outcome = Config::load(&paths, &options.config_overrides())?;
+    let mut problems = outcome.warnings;
+    let (registry, mut profile_warnings) = strategy_registry(&outcome.config, &paths);
+    problems.append(&mut profile_warnings);
+    let strategy = outcome.config.bot_strategy();
+    let normalized = strategy.trim().to_ascii_lowercase();
+    if !registry.names().any(|name| name == normalized) {
+        problems.push(format!(
+            "Unknown bot_strategy '{strategy}' (available: {}).",
+            registry.names().collect::<Vec<_>>().join(", ")
+        ));
+    }
+
+    let files: Vec<_> = outcome
+        .effective
+        .files()
+        .iter()
+        .filter(|(_, exists)| *exists)
+        .map(|(source, _)| source.to_string())
+        .collect();
+    if problems.is_empty() {
+        if files.is_empty() {
+            println!("Configuration OK: no config files found; using defaults.");
+        } else {
+            println!("Configuration OK. Read {}.", files.join(", "));
+        }
+        return Ok(());
+    }
+    for problem in &problems {
+        println!("- {problem}");
+    }
+    bail!(
+        "{} problem{} in the configuration",
+        problems.len(),
+        if problems.len() == 1 { "" } else { "s" }
+    )
+}
+
+/// Output of `deadwood analyze --json`.
+#[derive(Debug, Serialize)]
+struct AnalyzeReport {
+    cards: Vec<Card>,
+    deadwood_value: u32,
+    /// Every arrangement with the lowest deadwood, the one play uses first.
+    arrangements: Vec<Arrangement>,
+    /// For 11-card hands, every discard and the deadwood it leaves, best first.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    discards: Vec<DiscardOption>,
+}
+
+#[derive(Debug, Serialize)]
+struct Arrangement {
+    melds: Vec<MeldRecord>,
+    deadwood: Vec<Card>,
+}
+
+#[derive(Debug, Serialize)]
+struct DiscardOption {
+    card: Card,
+    deadwood_value: u32,
+    best: bool,
+}
+
+/// `deadwood analyze`: melds and deadwood for a hand given on the command line.
+pub fn run_analyze(cards: &[String], json: bool) -> Result<()> {
+    let hand = cards
+        .iter()
+        .map(|label| label.parse::<Card>())
+        .collect::<Result<Vec<_>, _>>()?;
+    if let Some(card) = hand.iter().duplicates().next() {
+        bail!("{card} appears more than once");
+    }
+    if hand.len() > HAND_SIZE + 1 {
+        bail!(
+            "a hand has at most {} cards, got {}",
+            HAND_SIZE + 1,
+            hand.len()
+        );
+    }
+
+    let arrangements = best_arrangements(&hand);
+    let deadwood_value = arrangements[0].deadwood_value;
+    let mut discards = Vec::new();
+    if hand.len() == HAND_SIZE + 1 {
+        discards = hand
+            .iter()
+            .map(|&card| {
+                let rest: Vec<Card> = hand.iter().copied().filter(|&c| c != card).collect();
+                (card, analyze_hand(&rest).deadwood_value)
+            })
+            .collect::<Vec<_>>();
+        discards.sort_by_key(|&(card, value)| (value, Reverse(card)));
+    }
+    let lowest = discards.first().map(|&(_, value)| value);
+
+    if json {
+        let mut sorted = hand;
+        sorted.sort();
+        let report = AnalyzeReport {
+            cards: sorted,
+            deadwood_value,
+            arrangements: arrangements
+                .iter()
+                .map(|analysis| Arrangement {
+                    melds: analysis.melds.iter().map(MeldRecord::of).collect(),
+                    deadwood: analysis.deadwood.clone(),
+                })
+                .collect(),
+            discards: discards
+                .iter()
+                .map(|&(card, value)| DiscardOption {
+                    card,
+                    deadwood_value: value,
+                    best: Some(value) == lowest,
+                })
+                .collect(),
+        };
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        return Ok(());
+    }
+
+    let tied = arrangements.len() > 1;
+    if tied {
+        println!(
+            "{} arrangements tie at {deadwood_value} deadwood:",
+            arrangements.len()
+        );
+    }
+    for (index, analysis) in arrangements.iter().enumerate() {
+        let indent = if tied {
+            println!("{}.", index + 1);
+            "  "
+        } else {
+            ""
+        };
+        for meld in &analysis.melds {
+            println!("{indent}{:?}: {}", meld.kind, card_list(&meld.cards));
+        }
+        println!(
+            "{indent}Deadwood: {} ({})",
+            analysis.deadwood_value,
+            if analysis.deadwood.is_empty() {
+                "none".to_string()
+            } else {
+                card_list(&analysis.deadwood)
+            }
+        );
+    }
+    if !discards.is_empty() {
+        println!("Discards, best first (deadwood left):");
+        for (card, value) in discards {
+            let marker = if Some(value) == lowest { "*" } else { " " };
+            println!("{marker} {card:<3} {value}");
+        }
+    }
+    Ok(())
+}
+
+fn card_list(cards: &[Card]) -> String {
+    cards.iter().map(Card::to_string).join(" ")
+}
+
+pub const SIM_USAGE: &str =
+    "Usage: deadwood sim [--hands N] [--seed N] [--profile PATH]... [--strategy NAME[:DIFFICULTY]]... [--json]
+       deadwood sim --tune [--iterations N] [--hands N] [--seed N] [--start PATH]
+                    [--strategy OPPONENT] [--name NAME] [--out PATH]
+
+Plays headless bot-vs-bot hands and reports results for every pairing of the
+given strategies (default: heuristic:challenging vs heuristic:easy).
+--profile registers a bot profile (a TOML file, or a directory of them) under
+its name so it can be used with --strategy. `deadwood-sim` takes the same
+arguments.
+
+With --tune, hill-climbs a bot profile (default: the Challenging heuristic, or
+--start) against the first --strategy (default: heuristic:challenging) and
+writes the best profile found to --out (default: tuned.toml).";
+
+struct SimArgs {
+    options: SimOptions,
+    contenders: Vec<Contender>,
+    profiles: Vec<PathBuf>,
+    json: bool,
+    tune: Option<TuneArgs>,
+}
+
+struct TuneArgs {
+    iterations: u32,
+    start: Option<PathBuf>,
+    name: Option<String>,
+    out: PathBuf,
+}
+
+/// `deadwood sim` and the `deadwood-sim` binary.
+pub fn run_sim(args: impl IntoIterator<Item = String>) -> Result<()> {
+    let Some(args) = parse_sim_args(args.into_iter())? else {
+        println!("{SIM_USAGE}");
+        return Ok(());
+    };
+
+    let mut registry = StrategyRegistry::builtin();
+    for path in &args.profiles {
+        for profile in load_profiles(path)? {
+            if let Err(err) = registry.register_profile(profile) {
+                eprintln!("warning: {err}.");
+            }
+        }
+    }
+
+    if let Some(tune) = &args.tune {
+        return run_tuner(&registry, &args, tune);
+    }
+
+    let contenders = if args.contenders.is_empty() {
+        vec![
+            Contender::new(DEFAULT_STRATEGY, BotDifficulty::Challenging),
+            Contender::new(DEFAULT_STRATEGY, BotDifficulty::Easy),
+        ]
+    } else {
+        args.contenders.clone()
+    };
+    let reports = sim::run(&registry, &contenders, &args.options)?;
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&reports)?);
+    } else {
+        println!(
+            "Seed {} | {} hands per pairing",
+            args.options.seed, args.options.hands
+        );
+        print!("{}", sim::format_table(&reports));
+    }
+    Ok(())
+}
+
+fn run_tuner(registry: &StrategyRegistry, args: &SimArgs, tune: &TuneArgs) -> Result<()> {
+    let mut start = match &tune.start {
+        Some(path) => BotParams::load(path)?,
+        None => BotParams::for_difficulty(BotDifficulty::Challenging),
+    };
+    start.name = tune.name.clone().unwrap_or_else(|| "tuned".to_string());
+    let opponent = args
+        .contenders
+        .first()
+        .cloned()
+        .unwrap_or_else(|| Contender::new(DEFAULT_STRATEGY, BotDifficulty::Challenging));
+    let options = TuneOptions {
+        iterations: tune.iterations,
+        hands: args.options.hands,
+        seed: args.options.seed,
+    };
+
+    println!(
+        "Tuning against {} | seed {} | {} hands per candidate",
+        opponent.label(),
+        options.seed,
+        options.hands
+    );
+    let (best, margin) = sim::tune(registry, start, &opponent, &options, |step| {
+        println!(
+            "[{:>4}] {:+.2} pts/hand, {:.1}% wins{}",
+            step.iteration,
+            step.points_margin,
+            step.win_rate * 100.0,
+            if step.accepted { "  (kept)" } else { "" }
+        );
+    })?;
+
+    best.save(&tune.out)?;
+    println!(
+        "Best profile scored {margin:+.2} pts/hand; written to {}",
+        tune.out.display()
+    );
+    Ok(())
+}
+
+fn load_profiles(path: &Path) -> Result<Vec<BotParams>> {
+    if path.is_dir() {
+        let (profiles, warnings) = BotParams::load_dir(path);
+        for warning in warnings {
+            eprintln!("warning: {warning}");
+        }
+        Ok(profiles)
+    } else {
+        Ok(vec![BotParams::load(path)?])
+    }
+}
+
+fn parse_sim_args(mut args: impl Iterator<Item = String>) -> Result<Option<SimArgs>> {
+    let mut options = SimOptions {
+        hands: 1000,
+        seed: rand::random(),
+    };
+    let mut contenders = Vec::new();
+    let mut profiles = Vec::new();
+    let mut json = false;
+    let mut tune = false;
+    let mut tune_args = TuneArgs {
+        iterations: 50,
+        start: None,
+        name: None,
+        out: PathBuf::from("tuned.toml"),
+    };
+
+    while let Some(arg) = args.next() {
+        let (flag, inline_value) = match arg.split_once('=') {
+            Some((flag, value)) => (flag.to_string(), Some(value.to_string())),
+            None => (arg.clone(), None),
+        };
+        let mut value = || {
+            inline_value
+                .clone()
+                .or_else(|| args.next())
+                .ok_or_else(|| anyhow!("{flag} expects a value"))
+        };
+        match flag.as_str() {
+            "--hands" | "-n" => {
+                options.hands = value()?
+                    .parse()
+                    .map_err(|_| anyhow!("--hands expects a positive number"))?;
+            }
+            "--seed" => {
+                options.seed = value()?
+                    .parse()
+                    .map_err(|_| anyhow!("--seed expects an unsigned number"))?;
+            }
+            "--strategy" | "-s" => contenders.push(Contender::parse(&value()?)?),
+            "--profile" | "-p" => profiles.push(PathBuf::from(value()?)),
+            "--json" => json = true,
+            "--tune" => tune = true,
+            "--iterations" => {
+                tune_args.iterations = value()?
+                    .parse()
+                    .map_err(|_| anyhow!("--iterations expects a positive number"))?;
+            }
+            "--start" => tune_args.start = Some(PathBuf::from(value()?)),
+            "--name" => tune_args.name = Some(value()?),
+            "--out" | "-o" => tune_args.out = PathBuf::from(value()?),
+            "--help" | "-h" => return Ok(None),
+            _ => bail!("unrecognised argument '{arg}'\n\n{SIM_USAGE}"),
+        }
+    }
+
+    Ok(Some(SimArgs {
+        options,
+        contenders,
+        profiles,
+        json,
+        tune: tune.then_some(tune_args),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_args(line: &str) -> Result<Cli> {
+        parse(line.split_whitespace().map(str::to_string))
+    }
+
+    fn command(line: &str) -> Command {
+        parse_args(line).unwrap().command
+    }
+
+    fn error(line: &str) -> String {
+        parse_args(line).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn no_command_plays() {
+        assert_eq!(command(""), Command::Play);
+        assert_eq!(command("--seed 4"), Command::Play);
+        assert_eq!(command("-h"), Command::Help);
+        assert_eq!(command("stats --version"), Command::Version);
+    }
+
+    #[test]
+    fn options_may_come_before_or_after_the_command() {
+        for line in [
+            "--profile Ana -d easy --seed=7 stats",
+            "stats --profile ana --difficulty easy --seed 7",
+            "-p ana stats -d=easy --seed 7",
+        ] {
+            let cli = parse_args(line).unwrap();
+            assert_eq!(cli.command, Command::Stats, "{line}");
+            assert_eq!(cli.options.profile.as_deref(), Some("ana"), "{line}");
+            assert_eq!(cli.options.difficulty, Some(BotDifficulty::Easy), "{line}");
+            assert_eq!(cli.options.seed, Some(7), "{line}");
+        }
+        let expected = Command::Export {
+            hand: Some(3),
+            out: Some(PathBuf::from("hand.txt")),
+        };
+        assert_eq!(command("export 3 --out hand.txt"), expected);
+        assert_eq!(command("--out hand.txt export 3"), expected);
+        assert_eq!(command("-o=hand.txt export 3"), expected);
+        assert_eq!(
+            command("--json analyze 7H 8H 9H"),
+            Command::Analyze {
+                cards: vec!["7H".into(), "8H".into(), "9H".into()],
+                json: true,
+            }
+        );
+    }
+
+    #[test]
+    fn command_options_need_their_command() {
+        assert!(error("stats --out hand.txt").starts_with("--out only applies to the export"));
+        assert!(error("--out hand.txt").starts_with("--out only applies to the export"));
+        assert!(error("export --json").starts_with("--json only applies to the analyze"));
+    }
+
+    #[test]
+    fn command_arguments_are_checked() {
+        assert_eq!(
+            command("export"),
+            Command::Export {
+                hand: None,
+                out: None
+            }
+        );
+        assert_eq!(
+            error("export 0"),
+            "export expects a hand number from 1, not '0'"
+        );
+        assert_eq!(error("export 1 2"), "export takes at most one hand number");
+        assert!(error("analyze").starts_with("analyze expects the cards of a hand"));
+        assert_eq!(command("config"), Command::Config { check: false });
+        assert_eq!(command("config show"), Command::Config { check: false });
+        assert_eq!(command("config check"), Command::Config { check: true });
+        assert_eq!(
+            error("config fix"),
+            "unknown config action 'fix' (show or check)"
+        );
+        assert!(error("stats extra").starts_with("unexpected argument 'extra'"));
+        assert!(error("deal").starts_with("unknown command 'deal'"));
+    }
+
+    #[test]
+    fn option_values_are_checked() {
+        assert_eq!(error("--seed"), "--seed expects a number");
+        assert_eq!(error("--seed -1"), "--seed expects an unsigned number");
+        assert_eq!(
+            error("-d hard"),
+            "unknown difficulty 'hard' (easy, challenging or adaptive)"
+        );
+        assert!(error("--rules oklahoma").starts_with("unknown rules variant 'oklahoma'"));
+        assert!(error("--profile ../x").contains("may only use letters"));
+        assert!(error("--verbose").starts_with("unrecognised argument '--verbose'"));
+    }
+
+    #[test]
+    fn sim_takes_the_remaining_arguments_and_the_seed() {
+        assert_eq!(
+            command("--seed 9 sim --hands 10 --json"),
+            Command::Sim {
+                args: ["--seed", "9", "--hands", "10", "--json"]
+                    .map(String::from)
+                    .to_vec()
+            }
+        );
+        assert_eq!(
+            command("--seed 9 sim --seed=3"),
+            Command::Sim {
+                args: vec!["--seed=3".to_string()]
+            }
+        );
+    }
+
+    #[test]
+    fn read_only_commands_leave_the_data_directory_alone() {
+        let dir = tempfile::tempdir().unwrap();
+        let data_dir = dir.path().join("data");
+        let options = LaunchOptions {
+            data_dir: Some(data_dir.clone()),
+            profile: Some("ana".to_string()),
+            ..LaunchOptions::default()
+        };
+        run_stats(&options).unwrap();
+        run_config(&options).unwrap();
+        assert!(run_export(&options, None, None).is_err());
+        assert!(!data_dir.exists());
+
+        // An existing profile is read without recording it as the last one used.
+        Paths::new("ben", Some(&data_dir)).unwrap();
+        Paths::new("ana", Some(&data_dir)).unwrap();
+        let last_profile = fs_contents(&data_dir.join("last_profile"));
+        let options = LaunchOptions {
+            profile: Some("ben".to_string()),
+            ..options
+        };
+        run_stats(&options).unwrap();
+        run_config(&options).unwrap();
+        assert_eq!(fs_contents(&data_dir.join("last_profile")), last_profile);
+        assert_eq!(
+            Paths::last_profile(Some(&data_dir)).unwrap().as_deref(),
+            Some("ana")
+        );
+    }
+
+    #[test]
+    fn export_tags_the_rules_the_hand_was_played_under() {
+        use crate::{
+            archive::HandRecord,
+            bot::{take_turn, HeuristicStrategy},
+            game::{Game, PlayerId, TurnPhase},
+        };
+
+        let dir = tempfile::tempdir().unwrap();
+        let data_dir = dir.path().join("data");
+        let paths = Paths::new("ana", Some(&data_dir)).unwrap();
+        let mut game = Game::with_seed(3).unwrap();
+        let mut human = HeuristicStrategy::new(BotDifficulty::Easy);
+        let mut bot = HeuristicStrategy::new(BotDifficulty::Easy);
+        while game.phase != TurnPhase::RoundOver {
+            let seat = game.current_player;
+            let strategy = match seat {
+                PlayerId::Human => &mut human,
+                PlayerId::Bot => &mut bot,
+            };
+            take_turn(&mut game, seat, strategy).unwrap();
+        }
+        let result = game.pending_round.clone().unwrap();
+        let mut record =
+            HandRecord::from_round(&game, &result, None, "heuristic", 0, Rules::Standard);
+        // As if written by a build with another variant configured.
+        record.rules = "oklahoma".to_string();
+        archive::append(paths.archive_file(), &record).unwrap();
+
+        let out = dir.path().join("hand.txt");
+        let options = LaunchOptions {
+            data_dir: Some(data_dir),
+            profile: Some("ana".to_string()),
+            rules: Some(Rules::Standard),
+            ..LaunchOptions::default()
+        };
+        run_export(&options, None, Some(&out)).unwrap();
+        assert!(fs_contents(&out).contains("[Rules \"oklahoma\"]"));
+    }
+
+    #[test]
+    fn the_tuner_writes_a_profile_that_loads_back() {
+        let dir = tempfile::tempdir().unwrap();
+        let start = dir.path().join("start.toml");
+        std::fs::write(&start, "endgame_stock = 0\nknock_threshold = 12\n").unwrap();
+        let out = dir.path().join("quick.toml");
+        let args = format!(
+            "--tune --iterations 2 --hands 4 --seed 5 --strategy heuristic:easy --start {} --name Quick --out {}",
+            start.display(),
+            out.display()
+        );
+        run_sim(args.split_whitespace().map(str::to_string)).unwrap();
+
+        let tuned = BotParams::load(&out).unwrap();
+        assert_eq!(tuned.name, "quick");
+        assert_eq!(tuned.endgame_stock, 0);
+        assert!(tuned.knock_threshold <= 10);
+        assert_eq!(tuned, tuned.clone().clamped());
+        let copy = dir.path().join("copy.toml");
+        tuned.save(&copy).unwrap();
+        assert_eq!(BotParams::load(&copy).unwrap(), tuned);
+    }
+
+    fn fs_contents(path: &Path) -> String {
+        std::fs::read_to_string(path).unwrap()
+    }
+}
@@ -1,16 +1,44 @@
-use std::{fs, path::Path};
+use std::{
+    collections::BTreeMap,
+    env,
+    fmt::{Display, Formatter},
+    fs,
+    path::{Path, PathBuf},
+    time::Duration,
+};
 
 use anyhow::{Context, Result};
 use ratatui::style::Color;
 use serde::{Deserialize, Serialize};
 
-use crate::cards::Suit;
+use crate::{
+    bot::{BotDifficulty, DEFAULT_STRATEGY},
+    cards::Suit,
+    engine::EngineConfig,
+    game::Rules,
+    keys::{Action, KeyBindings},
+    storage::{write_atomic, Paths},
+};
+
+/// Environment variable naming one more config file, layered over the user's.
+pub const CONFIG_ENV_VAR: &str = "DEADWOOD_CONFIG";
+
+/// Prefix of the environment variables that set single keys, e.g.
+/// `DEADWOOD_BOT_DIFFICULTY=easy` or `DEADWOOD_SUIT_COLORS__HEARTS=red`.
+pub const CONFIG_ENV_PREFIX: &str = "DEADWOOD_";
 
 #[derive(Debug, Clone)]
 pub struct Config {
     persist_stats: bool,
+    archive_hands: bool,
     auto_brackets: bool,
+    bot_strategy: String,
+    bot_difficulty: BotDifficulty,
+    bot_delay: Duration,
+    rules: Rules,
+    engine: Option<EngineConfig>,
     palette: SuitColorPalette,
+    keys: KeyBindings,
 }
 
 #[derive(Debug, Clone)]
@@ -25,10 +53,34 @@ struct SuitColorPalette {
 struct ConfigFile {
     #[serde(default = "default_persist_stats")]
     persist_stats: bool,
+    #[serde(default = "default_archive_hands")]
+    archive_hands: bool,
     #[serde(default = "default_auto_brackets")]
     auto_brackets: bool,
+    #[serde(default = "default_bot_strategy")]
+    bot_strategy: String,
+    #[serde(default = "default_bot_difficulty")]
+    bot_difficulty: String,
+    #[serde(default = "default_bot_delay_ms")]
+    bot_delay_ms: u64,
+    #[serde(default = "default_rules")]
+    rules: String,
     #[serde(default)]
     suit_colors: SuitColorStrings,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    engine: Option<EngineSection>,
+    /// Action name to the keys bound to it; unlisted actions keep their defaults.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    keys: BTreeMap<String, Vec<String>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct EngineSection {
+    command: String,
+    #[serde(default)]
+    args: Vec<String>,
+    #[serde(default = "default_engine_timeout_ms")]
+    timeout_ms: u64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -43,12 +95,132 @@ impl Default for ConfigFile {
     fn default() -> Self {
         Self {
             persist_stats: default_persist_stats(),
+            archive_hands: default_archive_hands(),
             auto_brackets: default_auto_brackets(),
+            bot_strategy: default_bot_strategy(),
+            bot_difficulty: default_bot_difficulty(),
+            bot_delay_ms: default_bot_delay_ms(),
+            rules: default_rules(),
             suit_colors: SuitColorStrings::default(),
+            engine: None,
+            keys: BTreeMap::new(),
+        }
+    }
+}
+
+impl ConfigFile {
+    /// Reads each setting on its own, so one bad value falls back to its
+    /// default without discarding the rest.
+    fn read_fields(effective: &EffectiveConfig, warnings: &mut Vec<String>) -> Self {
+        let mut fields = FieldReader {
+            effective,
+            warnings,
+        };
+        let colors = SuitColorStrings::default();
+        Self {
+            persist_stats: fields.read("persist_stats", default_persist_stats()),
+            archive_hands: fields.read("archive_hands", default_archive_hands()),
+            auto_brackets: fields.read("auto_brackets", default_auto_brackets()),
+            bot_strategy: fields.read("bot_strategy", default_bot_strategy()),
+            bot_difficulty: fields.read("bot_difficulty", default_bot_difficulty()),
+            bot_delay_ms: fields.read("bot_delay_ms", default_bot_delay_ms()),
+            rules: fields.read("rules", default_rules()),
+            suit_colors: SuitColorStrings {
+                hearts: fields.read("suit_colors.hearts", colors.hearts),
+                diamonds: fields.read("suit_colors.diamonds", colors.diamonds),
+                clubs: fields.read("suit_colors.clubs", colors.clubs),
+                spades: fields.read("suit_colors.spades", colors.spades),
+            },
+            engine: fields.engine(),
+            keys: fields.keys(),
         }
     }
 }
 
+/// Pulls typed values out of the merged settings, warning about each one
+/// that has the wrong type.
+struct FieldReader<'a> {
+    effective: &'a EffectiveConfig,
+    warnings: &'a mut Vec<String>,
+}
+
+impl FieldReader<'_> {
+    fn read<T: serde::de::DeserializeOwned>(&mut self, key: &str, default: T) -> T {
+        self.try_read(key).unwrap_or(default)
+    }
+
+    /// The value at `key`, or `None` when it is unset or invalid.
+    fn try_read<T: serde::de::DeserializeOwned>(&mut self, key: &str) -> Option<T> {
+        let value = lookup(&self.effective.table, key)?;
+        match value.clone().try_into::<T>() {
+            Ok(parsed) => Some(parsed),
+            Err(err) => {
+                self.warnings.push(format!(
+                    "Invalid {key} in {}: {}. Using the default.",
+                    self.source_of(key),
+                    err.to_string().replace('\n', " ").trim()
+                ));
+                None
+            }
+        }
+    }
+
+    fn engine(&mut self) -> Option<EngineSection> {
+        match lookup(&self.effective.table, "engine")? {
+            toml::Value::Table(_) => {}
+            _ => {
+                self.warnings.push(format!(
+                    "Invalid engine in {}: expected a table. Ignoring it.",
+                    self.source_of("engine")
+                ));
+                return None;
+            }
+        }
+        if lookup(&self.effective.table, "engine.command").is_none() {
+            self.warnings
+                .push("The [engine] table has no command. Ignoring it.".to_string());
+            return None;
+        }
+        Some(EngineSection {
+            command: self.try_read("engine.command")?,
+            args: self.read("engine.args", Vec::new()),
+            timeout_ms: self.read("engine.timeout_ms", default_engine_timeout_ms()),
+        })
+    }
+
+    /// The `[keys]` table, with a single key accepted in place of a list.
+    fn keys(&mut self) -> BTreeMap<String, Vec<String>> {
+        let Some(toml::Value::Table(table)) = lookup(&self.effective.table, "keys") else {
+            return BTreeMap::new();
+        };
+        let mut keys = BTreeMap::new();
+        for (action, value) in table {
+            let bound = match value {
+                toml::Value::String(key) => Some(vec![key.clone()]),
+                toml::Value::Array(items) => items
+                    .iter()
+                    .map(|item| item.as_str().map(str::to_string))
+                    .collect(),
+                _ => None,
+            };
+            match bound {
+                Some(bound) => {
+                    keys.insert(action.clone(), bound);
+                }
+                None => self.warnings.push(format!(
+                    "Invalid keys.{action} in {}: expected a key or a list of keys. Using the default.",
+                    self.source_of(&format!("keys.{action}"))
+                )),
+            }
+        }
+        keys
+    }
+
+    fn source_of(&self, key: &str) -> String {
+        self.effective.source_label(key)
+    }
+}
+
 impl Default for SuitColorStrings {
     fn default() -> Self {
         Self {
@@ -65,31 +237,125 @@ pub struct ConfigLoadOutcome {
     pub config: Config,
     pub created: bool,
     pub warnings: Vec<String>,
+    pub effective: EffectiveConfig,
+}
+
+/// A layer settings can come from, in increasing order of precedence.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigSource {
+    Default,
+    SystemFile(PathBuf),
+    UserFile(PathBuf),
+    ProfileFile(PathBuf),
+    /// The file named by `DEADWOOD_CONFIG`.
+    EnvFile(PathBuf),
+    EnvVar(String),
+    Flag(String),
+}
+
+impl ConfigSource {
+    /// The file this layer was read from, for file layers.
+    pub fn file(&self) -> Option<&Path> {
+        match self {
+            ConfigSource::SystemFile(path)
+            | ConfigSource::UserFile(path)
+            | ConfigSource::ProfileFile(path)
+            | ConfigSource::EnvFile(path) => Some(path),
+            ConfigSource::Default | ConfigSource::EnvVar(_) | ConfigSource::Flag(_) => None,
+        }
+    }
+
+    /// Whether values from this layer take precedence over the user's config file.
+    pub fn overrides_user_file(&self) -> bool {
+        matches!(
+            self,
+            ConfigSource::ProfileFile(_)
+                | ConfigSource::EnvFile(_)
+                | ConfigSource::EnvVar(_)
+                | ConfigSource::Flag(_)
+        )
+    }
+}
+
+impl Display for ConfigSource {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConfigSource::Default => write!(f, "default"),
+            ConfigSource::SystemFile(path) => write!(f, "system file {}", path.display()),
+            ConfigSource::UserFile(path) => write!(f, "user file {}", path.display()),
+            ConfigSource::ProfileFile(path) => write!(f, "profile file {}", path.display()),
+            ConfigSource::EnvFile(path) => write!(f, "{CONFIG_ENV_VAR} file {}", path.display()),
+            ConfigSource::EnvVar(name) => write!(f, "environment {name}"),
+            ConfigSource::Flag(flag) => write!(f, "flag {flag}"),
+        }
+    }
+}
+
+/// Settings given as command-line flags, applied over every other layer.
+#[derive(Debug, Clone, Default)]
+pub struct ConfigOverrides {
+    entries: Vec<(String, String, toml::Value)>,
+}
+
+impl ConfigOverrides {
+    /// Sets the dotted `key` to `value` on behalf of `flag`.
+    pub fn set(&mut self, flag: &str, key: &str, value: impl Into<toml::Value>) {
+        self.entries
+            .push((flag.to_string(), key.to_string(), value.into()));
+    }
+}
+
+/// The merged settings and the layer each one was last set by.
+#[derive(Debug, Clone, Default)]
+pub struct EffectiveConfig {
+    table: toml::Table,
+    sources: BTreeMap<String, ConfigSource>,
+    /// Every file layer that was consulted and whether it existed.
+    files: Vec<(ConfigSource, bool)>,
+}
+
+impl EffectiveConfig {
+    /// Every setting as a dotted key, its value and where it came from, sorted by key.
+    pub fn entries(&self) -> Vec<(&str, &toml::Value, &ConfigSource)> {
+        self.sources
+            .iter()
+            .filter_map(|(key, source)| {
+                lookup(&self.table, key).map(|value| (key.as_str(), value, source))
+            })
+            .collect()
+    }
+
+    /// Where the value of a dotted key came from.
+    pub fn source(&self, key: &str) -> Option<&ConfigSource> {
+        self.sources.get(key)
+    }
+
+    /// The layer that set `key`, for warnings about its value.
+    fn source_label(&self, key: &str) -> String {
+        self.source(key)
+            .map_or_else(|| "the settings".to_string(), ConfigSource::to_string)
+    }
+
+    pub fn files(&self) -> &[(ConfigSource, bool)] {
+        &self.files
+    }
+
+    fn apply(&mut self, layer: toml::Table, source: &ConfigSource) {
+        merge_tables(&mut self.table, layer, "", source, &mut self.sources);
+    }
 }
 
 impl Config {
-    pub fn load_or_create(path: &Path) -> Result<ConfigLoadOutcome> {
-        let created;
-        let mut warnings = Vec::new();
-        let data = if path.exists() {
-            created = false;
-            let content = fs::read_to_string(path)
-                .with_context(|| format!("failed to read config file at {}", path.display()))?;
-            match toml::from_str::<ConfigFile>(&content) {
-                Ok(parsed) => parsed,
-                Err(err) => {
-                    warnings.push(format!(
-                        "Failed to parse config file at {}: {err}. Using defaults.",
-                        path.display()
-                    ));
-                    ConfigFile::default()
-                }
-            }
-        } else {
-            created = true;
-            let data = ConfigFile::default();
-            let serialized =
-                toml::to_string_pretty(&data).context("failed to serialise default config")?;
+    /// Builds the settings from every layer: built-in defaults, the system
+    /// file, the shared config file (written with defaults if it is missing),
+    /// the profile's config file, the file named by `DEADWOOD_CONFIG`,
+    /// `DEADWOOD_*` environment variables and finally `overrides`.
+    pub fn load_or_create(paths: &Paths, overrides: &ConfigOverrides) -> Result<ConfigLoadOutcome> {
+        let path = paths.config_file();
+        let created = !path.exists();
+        if created {
+            let serialized = toml::to_string_pretty(&ConfigFile::default())
+                .context("failed to serialise default config")?;
             if let Some(parent) = path.parent() {
                 fs::create_dir_all(parent).with_context(|| {
                     format!("failed to create config directory at {}", parent.display())
@@ -97,26 +363,121 @@ impl Config {
             }
             fs::write(path, serialized)
                 .with_context(|| format!("failed to write default config to {}", path.display()))?;
-            data
-        };
+        }
+        let mut outcome = Self::load(paths, overrides)?;
+        outcome.created = created;
+        Ok(outcome)
+    }
 
-        let (config, mut palette_warnings) = Config::from_file(data);
+    /// Builds the settings like [`Config::load_or_create`], but only reads:
+    /// missing files are skipped.
+    pub fn load(paths: &Paths, overrides: &ConfigOverrides) -> Result<ConfigLoadOutcome> {
+        let path = paths.config_file();
+        let mut warnings = Vec::new();
+        let mut effective = EffectiveConfig::default();
+        effective.apply(
+            toml::Table::try_from(ConfigFile::default())
+                .context("failed to serialise default config")?,
+            &ConfigSource::Default,
+        );
+        let mut files = Vec::new();
+        if let Some(system) = system_config_file() {
+            files.push(ConfigSource::SystemFile(system));
+        }
+        files.push(ConfigSource::UserFile(path.to_path_buf()));
+        files.push(ConfigSource::ProfileFile(
+            paths.profile_config_file().to_path_buf(),
+        ));
+        if let Some(env_file) = env::var_os(CONFIG_ENV_VAR).filter(|value| !value.is_empty()) {
+            files.push(ConfigSource::EnvFile(PathBuf::from(env_file)));
+        }
+        for source in files {
+            let Some(file) = source.file() else {
+                continue;
+            };
+            let exists = file.exists();
+            if exists {
+                if let Some(layer) = read_table(file, &mut warnings)? {
+                    effective.apply(layer, &source);
+                }
+            } else if matches!(source, ConfigSource::EnvFile(_)) {
+                warnings.push(format!(
+                    "{CONFIG_ENV_VAR} names {}, which does not exist.",
+                    file.display()
+                ));
+            }
+            effective.files.push((source, exists));
+        }
+        for (name, key, value) in env_overrides(&mut warnings) {
+            effective.apply(dotted_table(&key, value), &ConfigSource::EnvVar(name));
+        }
+        for (flag, key, value) in &overrides.entries {
+            effective.apply(
+                dotted_table(key, value.clone()),
+                &ConfigSource::Flag(flag.clone()),
+            );
+        }
+
+        report_unknown_keys(
+            &effective.table,
+            &schema(),
+            "",
+            &effective.sources,
+            &mut warnings,
+        );
+        let data = ConfigFile::read_fields(&effective, &mut warnings);
+
+        let (config, mut palette_warnings) = Config::from_file(data, &effective);
         warnings.append(&mut palette_warnings);
         Ok(ConfigLoadOutcome {
             config,
-            created,
+            created: false,
             warnings,
+            effective,
         })
     }
 
-    fn from_file(data: ConfigFile) -> (Self, Vec<String>) {
+    /// Turns the raw values into settings, warning about any value that names
+    /// nothing known and the layer in `effective` that set it.
+    fn from_file(data: ConfigFile, effective: &EffectiveConfig) -> (Self, Vec<String>) {
         let mut warnings = Vec::new();
-        let palette = SuitColorPalette::from_strings(&data.suit_colors, &mut warnings);
+        let palette = SuitColorPalette::from_strings(&data.suit_colors, effective, &mut warnings);
+        let keys = KeyBindings::from_config(&data.keys, &mut warnings);
+        let bot_difficulty = BotDifficulty::from_name(&data.bot_difficulty).unwrap_or_else(|| {
+            warnings.push(format!(
+                "Unrecognised bot difficulty '{}' in {}. Using {}.",
+                data.bot_difficulty,
+                effective.source_label("bot_difficulty"),
+                BotDifficulty::default().label()
+            ));
+            BotDifficulty::default()
+        });
+        let rules = Rules::from_name(&data.rules).unwrap_or_else(|| {
+            warnings.push(format!(
+                "Unrecognised rules variant '{}' in {} (supported: {}). Using {}.",
+                data.rules,
+                effective.source_label("rules"),
+                Rules::names(),
+                Rules::default().name()
+            ));
+            Rules::default()
+        });
         (
             Self {
                 persist_stats: data.persist_stats,
+                archive_hands: data.archive_hands,
                 auto_brackets: data.auto_brackets,
+                bot_strategy: data.bot_strategy,
+                bot_difficulty,
+                bot_delay: Duration::from_millis(data.bot_delay_ms),
+                rules,
+                engine: data.engine.map(|section| EngineConfig {
+                    command: section.command,
+                    args: section.args,
+                    timeout: Duration::from_millis(section.timeout_ms),
+                }),
                 palette,
+                keys,
             },
             warnings,
         )
@@ -126,22 +487,387 @@ impl Config {
         self.persist_stats
     }
 
+    /// Whether every finished hand is appended to the hand archive.
+    pub fn archive_hands(&self) -> bool {
+        self.archive_hands
+    }
+
     pub fn auto_brackets(&self) -> bool {
         self.auto_brackets
     }
 
+    pub fn bot_strategy(&self) -> &str {
+        &self.bot_strategy
+    }
+
+    pub fn bot_difficulty(&self) -> BotDifficulty {
+        self.bot_difficulty
+    }
+
+    /// Pause before each visible step of the bot's turn.
+    pub fn bot_delay(&self) -> Duration {
+        self.bot_delay
+    }
+
+    /// The rules variant hands are played and exported under.
+    pub fn rules(&self) -> Rules {
+        self.rules
+    }
+
+    pub fn set_persist_stats(&mut self, persist: bool) {
+        self.persist_stats = persist;
+    }
+
+    pub fn set_archive_hands(&mut self, archive: bool) {
+        self.archive_hands = archive;
+    }
+
+    pub fn set_auto_brackets(&mut self, auto_brackets: bool) {
+        self.auto_brackets = auto_brackets;
+    }
+
+    pub fn set_bot_delay(&mut self, delay: Duration) {
+        self.bot_delay = delay;
+    }
+
+    pub fn set_suit_color(&mut self, suit: Suit, color: Color) {
+        match suit {
+            Suit::Hearts => self.palette.hearts = color,
+            Suit::Diamonds => self.palette.diamonds = color,
+            Suit::Clubs => self.palette.clubs = color,
+            Suit::Spades => self.palette.spades = color,
+        }
+    }
+
+    /// The active key bindings: the defaults with the `[keys]` table applied.
+    pub fn keys(&self) -> &KeyBindings {
+        &self.keys
+    }
+
+    /// External engine settings from the `[engine]` table, if present.
+    pub fn engine(&self) -> Option<&EngineConfig> {
+        self.engine.as_ref()
+    }
+
     pub fn suit_color(&self, suit: Suit) -> Color {
         self.palette.color(suit)
     }
 }
 
+/// Writes `changes` (dotted key and new value) into the config file at
+/// `path`, keeping its comments, layout and every other key as they were.
+pub fn write_settings(path: &Path, changes: &[(&str, toml_edit::Value)]) -> Result<()> {
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(err) => {
+            return Err(err)
+                .with_context(|| format!("failed to read config file at {}", path.display()))
+        }
+    };
+    let mut document: toml_edit::DocumentMut = content
+        .parse()
+        .with_context(|| format!("failed to parse config file at {}", path.display()))?;
+    for (key, value) in changes {
+        let mut parts: Vec<&str> = key.split('.').collect();
+        let last = parts.pop().unwrap_or_default();
+        let mut table: &mut dyn toml_edit::TableLike = document.as_table_mut();
+        for part in parts {
+            if table
+                .get(part)
+                .and_then(toml_edit::Item::as_table_like)
+                .is_none()
+            {
+                table.insert(part, toml_edit::Item::Table(toml_edit::Table::new()));
+            }
+            table = table
+                .get_mut(part)
+                .and_then(toml_edit::Item::as_table_like_mut)
+                .with_context(|| format!("'{part}' in {} is not a table", path.display()))?;
+        }
+        match table.get_mut(last).and_then(toml_edit::Item::as_value_mut) {
+            Some(existing) => {
+                // Keep the spacing and any trailing comment around the old value.
+                let decor = existing.decor().clone();
+                *existing = value.clone();
+                *existing.decor_mut() = decor;
+            }
+            None => {
+                table.insert(last, toml_edit::Item::Value(value.clone()));
+            }
+        }
+    }
+    write_atomic(path, document.to_string().as_bytes())
+}
+
+/// Reads a TOML file into a table, reporting syntax errors as warnings.
+fn read_table(path: &Path, warnings: &mut Vec<String>) -> Result<Option<toml::Table>> {
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("failed to read config file at {}", path.display()))?;
+    match toml::from_str::<toml::Table>(&content) {
+        Ok(table) => Ok(Some(table)),
+        Err(err) => {
+            warnings.push(format!(
+                "Failed to parse config file at {}: {}. Ignoring it.",
+                path.display(),
+                err.to_string().trim_end()
+            ));
+            Ok(None)
+        }
+    }
+}
+
+/// Overlays `overrides` onto `base`, merging nested tables key by key and
+/// recording `source` against every value it sets.
+fn merge_tables(
+    base: &mut toml::Table,
+    overrides: toml::Table,
+    prefix: &str,
+    source: &ConfigSource,
+    sources: &mut BTreeMap<String, ConfigSource>,
+) {
+    for (key, value) in overrides {
+        let path = format!("{prefix}{key}");
+        match value {
+            toml::Value::Table(nested) => {
+                if !matches!(base.get(&key), Some(toml::Value::Table(_))) {
+                    sources.remove(&path);
+                    base.insert(key.clone(), toml::Value::Table(toml::Table::new()));
+                }
+                if let Some(toml::Value::Table(existing)) = base.get_mut(&key) {
+                    merge_tables(existing, nested, &format!("{path}."), source, sources);
+                }
+            }
+            value => {
+                let nested_prefix = format!("{path}.");
+                sources.retain(|existing, _| !existing.starts_with(&nested_prefix));
+                sources.insert(path, source.clone());
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+/// A table holding `value` at the dotted `key`.
+fn dotted_table(key: &str, value: toml::Value) -> toml::Table {
+    let mut value = value;
+    let mut parts: Vec<&str> = key.split('.').collect();
+    let last = parts.pop().unwrap_or_default();
+    let mut table = toml::Table::new();
+    table.insert(last.to_string(), value);
+    while let Some(part) = parts.pop() {
+        value = toml::Value::Table(table);
+        table = toml::Table::new();
+        table.insert(part.to_string(), value);
+    }
+    table
+}
+
+fn lookup<'a>(table: &'a toml::Table, key: &str) -> Option<&'a toml::Value> {
+    let (first, rest) = match key.split_once('.') {
+        Some((first, rest)) => (first, Some(rest)),
+        None => (key, None),
+    };
+    match (table.get(first), rest) {
+        (Some(toml::Value::Table(nested)), Some(rest)) => lookup(nested, rest),
+        (value, None) => value,
+        _ => None,
+    }
+}
+
+/// Where settings shared by every user of the machine live.
+fn system_config_file() -> Option<PathBuf> {
+    if cfg!(windows) {
+        env::var_os("PROGRAMDATA")
+            .map(|dir| PathBuf::from(dir).join("deadwood").join("config.toml"))
+    } else {
+        Some(PathBuf::from("/etc/deadwood/config.toml"))
+    }
+}
+
+/// Every recognised key, holding a value of the type it expects.
+fn schema() -> toml::Table {
+    toml::Table::try_from(ConfigFile {
+        engine: Some(EngineSection {
+            command: String::new(),
+            args: Vec::new(),
+            timeout_ms: default_engine_timeout_ms(),
+        }),
+        keys: Action::ALL
+            .into_iter()
+            .map(|action| {
+                let keys = action.default_keys().iter().map(|key| key.to_string());
+                (action.name().to_string(), keys.collect())
+            })
+            .collect(),
+        ..ConfigFile::default()
+    })
+    .unwrap_or_default()
+}
+
+/// Warns about every key in `table` that `schema` does not know, naming the
+/// layer that set it and the closest known key.
+fn report_unknown_keys(
+    table: &toml::Table,
+    schema: &toml::Table,
+    prefix: &str,
+    sources: &BTreeMap<String, ConfigSource>,
+    warnings: &mut Vec<String>,
+) {
+    for (key, value) in table {
+        let path = format!("{prefix}{key}");
+        match (schema.get(key), value) {
+            (Some(toml::Value::Table(nested_schema)), toml::Value::Table(nested)) => {
+                report_unknown_keys(
+                    nested,
+                    nested_schema,
+                    &format!("{path}."),
+                    sources,
+                    warnings,
+                )
+            }
+            (Some(_), _) => {}
+            (None, _) => {
+                let source = sources.get(&path).or_else(|| {
+                    let nested_prefix = format!("{path}.");
+                    sources
+                        .iter()
+                        .find(|(existing, _)| existing.starts_with(&nested_prefix))
+                        .map(|(_, source)| source)
+                });
+                let mut warning = match value {
+                    toml::Value::Table(_) => format!("Unknown table [{path}]"),
+                    _ => format!("Unknown key '{path}'"),
+                };
+                if let Some(source) = source {
+                    warning.push_str(&format!(" in {source}"));
+                }
+                if let Some(suggestion) = suggest_key(key, prefix, schema) {
+                    warning.push_str(&format!(" (did you mean '{suggestion}'?)"));
+                }
+                warning.push('.');
+                warnings.push(warning);
+            }
+        }
+    }
+}
+
+/// The known key closest to a misspelt one: a sibling a few edits away, or a
+/// key of the same name in one of the tables.
+fn suggest_key(key: &str, prefix: &str, siblings: &toml::Table) -> Option<String> {
+    let key = key.to_ascii_lowercase();
+    let nearest = siblings
+        .keys()
+        .map(|candidate| (edit_distance(&key, candidate), candidate))
+        .filter(|(distance, _)| *distance <= (key.len() / 3).max(1))
+        .min();
+    if let Some((_, candidate)) = nearest {
+        return Some(format!("{prefix}{candidate}"));
+    }
+    schema().iter().find_map(|(table, value)| match value {
+        toml::Value::Table(nested) if nested.contains_key(&key) => Some(format!("{table}.{key}")),
+        _ => None,
+    })
+}
+
+/// Levenshtein distance between two keys.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, a_char) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != *b_char);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+/// Reads `DEADWOOD_*` variables as `(variable, dotted key, value)` overrides.
+/// `__` separates table names, so `DEADWOOD_ENGINE__TIMEOUT_MS` sets
+/// `engine.timeout_ms`. Values are read according to the key's type.
+fn env_overrides(warnings: &mut Vec<String>) -> Vec<(String, String, toml::Value)> {
+    let schema = schema();
+    let mut overrides: Vec<_> = env::vars_os()
+        .filter_map(|(name, value)| Some((name.into_string().ok()?, value.into_string().ok()?)))
+        .filter(|(name, _)| name.starts_with(CONFIG_ENV_PREFIX) && name != CONFIG_ENV_VAR)
+        .filter_map(|(name, raw)| {
+            let key = name[CONFIG_ENV_PREFIX.len()..]
+                .to_ascii_lowercase()
+                .replace("__", ".");
+            let Some(expected) = lookup(&schema, &key) else {
+                warnings.push(format!("Ignoring {name}: there is no config key '{key}'."));
+                return None;
+            };
+            match env_value(&raw, expected) {
+                Some(value) => Some((name, key, value)),
+                None => {
+                    warnings.push(format!(
+                        "Ignoring {name}: expected {}, got '{raw}'.",
+                        expected.type_str()
+                    ));
+                    None
+                }
+            }
+        })
+        .collect();
+    overrides.sort_by(|a, b| a.0.cmp(&b.0));
+    overrides
+}
+
+/// Reads an environment variable's text as the same kind of value as `expected`.
+fn env_value(raw: &str, expected: &toml::Value) -> Option<toml::Value> {
+    let raw = raw.trim();
+    match expected {
+        toml::Value::String(_) => Some(toml::Value::String(raw.to_string())),
+        toml::Value::Boolean(_) => match raw.to_ascii_lowercase().as_str() {
+            "true" | "1" | "yes" | "on" => Some(toml::Value::Boolean(true)),
+            "false" | "0" | "no" | "off" => Some(toml::Value::Boolean(false)),
+            _ => None,
+        },
+        toml::Value::Integer(_) => raw.parse().ok().map(toml::Value::Integer),
+        toml::Value::Array(_) => {
+            // A TOML array (`["--fast", "-v"]`), or whitespace-separated words.
+            let parsed = toml::from_str::<toml::Table>(&format!("value = {raw}"))
+                .ok()
+                .and_then(|mut table| table.remove("value"));
+            match parsed {
+                Some(array @ toml::Value::Array(_)) => Some(array),
+                _ => Some(toml::Value::Array(
+                    raw.split_whitespace()
+                        .map(|word| toml::Value::String(word.to_string()))
+                        .collect(),
+                )),
+            }
+        }
+        _ => None,
+    }
+}
+
 impl SuitColorPalette {
-    fn from_strings(strings: &SuitColorStrings, warnings: &mut Vec<String>) -> Self {
+    fn from_strings(
+        strings: &SuitColorStrings,
+        effective: &EffectiveConfig,
+        warnings: &mut Vec<String>,
+    ) -> Self {
+        let mut parse = |value: &str, suit| {
+            parse_color(value).unwrap_or_else(|| {
+                let key = format!("suit_colors.{}", suit_label(suit));
+                warnings.push(format!(
+                    "Unrecognised colour '{value}' for {} in {}. Using default.",
+                    suit_label(suit),
+                    effective.source_label(&key)
+                ));
+                default_color(suit)
+            })
+        };
         Self {
-            hearts: parse_color_with_default(&strings.hearts, Suit::Hearts, warnings),
-            diamonds: parse_color_with_default(&strings.diamonds, Suit::Diamonds, warnings),
-            clubs: parse_color_with_default(&strings.clubs, Suit::Clubs, warnings),
-            spades: parse_color_with_default(&strings.spades, Suit::Spades, warnings),
+            hearts: parse(&strings.hearts, Suit::Hearts),
+            diamonds: parse(&strings.diamonds, Suit::Diamonds),
+            clubs: parse(&strings.clubs, Suit::Clubs),
+            spades: parse(&strings.spades, Suit::Spades),
         }
     }
 
@@ -155,18 +881,6 @@ impl SuitColorPalette {
     }
 }
 
-fn parse_color_with_default(value: &str, suit: Suit, warnings: &mut Vec<String>) -> Color {
-    if let Some(color) = parse_color(value) {
-        return color;
-    }
-    warnings.push(format!(
-        "Unrecognised colour '{}' for {}. Using default.",
-        value,
-        suit_label(suit)
-    ));
-    default_color(suit)
-}
-
 fn parse_color(value: &str) -> Option<Color> {
     let trimmed = value.trim();
     if trimmed.is_empty() {
@@ -259,6 +973,152 @@ fn default_persist_stats() -> bool {
     false
 }
 
+fn default_archive_hands() -> bool {
+    true
+}
+
 fn default_auto_brackets() -> bool {
     true
 }
+
+fn default_bot_strategy() -> String {
+    DEFAULT_STRATEGY.to_string()
+}
+
+fn default_bot_difficulty() -> String {
+    BotDifficulty::default().name().to_string()
+}
+
+fn default_bot_delay_ms() -> u64 {
+    600
+}
+
+fn default_rules() -> String {
+    Rules::default().name().to_string()
+}
+
+fn default_engine_timeout_ms() -> u64 {
+    2000
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unrecognised_values_name_the_layer_that_set_them() {
+        let dir = tempfile::tempdir().unwrap();
+        let paths = Paths::existing("ana", Some(dir.path())).unwrap();
+        fs::write(
+            paths.config_file(),
+            "bot_difficulty = \"hard\"\n[suit_colors]\nhearts = \"mauve\"\n",
+        )
+        .unwrap();
+        fs::create_dir_all(paths.profile_config_file().parent().unwrap()).unwrap();
+        fs::write(paths.profile_config_file(), "rules = \"oklahoma\"\n").unwrap();
+
+        let mut overrides = ConfigOverrides::default();
+        overrides.set("--difficulty", "bot_difficulty", "harder");
+        let outcome = Config::load(&paths, &overrides).unwrap();
+        let user_file = paths.config_file().display();
+        let profile_file = paths.profile_config_file().display();
+        let expected = [
+            format!("Unrecognised colour 'mauve' for hearts in user file {user_file}. Using default."),
+            "Unrecognised bot difficulty 'harder' in flag --difficulty. Using Challenging.".to_string(),
+            format!(
+                "Unrecognised rules variant 'oklahoma' in profile file {profile_file} (supported: {}). Using standard.",
+                Rules::names()
+            ),
+        ];
+        for warning in &expected {
+            assert!(
+                outcome.warnings.contains(warning),
+                "missing {warning:?} in {:?}",
+                outcome.warnings
+            );
+        }
+        assert_eq!(outcome.config.bot_difficulty(), BotDifficulty::Challenging);
+        assert!(!outcome.created);
+    }
+
+    #[test]
+    fn misspelt_keys_suggest_the_nearest_setting() {
+        let dir = tempfile::tempdir().unwrap();
+        let paths = Paths::existing("ana", Some(dir.path())).unwrap();
+        fs::write(paths.config_file(), "persist_stat = true\n").unwrap();
+
+        let outcome = Config::load(&paths, &ConfigOverrides::default()).unwrap();
+        let expected = format!(
+            "Unknown key 'persist_stat' in user file {} (did you mean 'persist_stats'?).",
+            paths.config_file().display()
+        );
+        assert_eq!(outcome.warnings, vec![expected]);
+        assert!(!outcome.config.persist_stats());
+    }
+
+    #[test]
+    fn a_value_of_the_wrong_type_falls_back_on_its_own() {
+        let dir = tempfile::tempdir().unwrap();
+        let paths = Paths::existing("ana", Some(dir.path())).unwrap();
+        fs::write(
+            paths.config_file(),
+            "bot_delay_ms = \"fast\"\nbot_difficulty = \"easy\"\nauto_brackets = false\n",
+        )
+        .unwrap();
+
+        let outcome = Config::load(&paths, &ConfigOverrides::default()).unwrap();
+        assert_eq!(outcome.warnings.len(), 1, "{:?}", outcome.warnings);
+        assert!(outcome.warnings[0].starts_with(&format!(
+            "Invalid bot_delay_ms in user file {}:",
+            paths.config_file().display()
+        )));
+        assert!(outcome.warnings[0].ends_with("Using the default."));
+        assert_eq!(
+            outcome.config.bot_delay(),
+            Duration::from_millis(default_bot_delay_ms())
+        );
+        assert_eq!(outcome.config.bot_difficulty(), BotDifficulty::Easy);
+        assert!(!outcome.config.auto_brackets());
+    }
+
+    #[test]
+    fn saving_settings_keeps_the_example_files_comments_and_layout() {
+        let example =
+            fs::read_to_string(concat!(env!("CARGO_MANIFEST_DIR"), "/config.example.toml"))
+                .unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        fs::write(&path, &example).unwrap();
+
+        write_settings(&path, &[]).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), example);
+
+        write_settings(
+            &path,
+            &[
+                ("bot_delay_ms", 250.into()),
+                ("bot_difficulty", "easy".into()),
+                ("suit_colors.hearts", "#FF005D".into()),
+            ],
+        )
+        .unwrap();
+        let expected = example
+            .replace("bot_delay_ms = 600\n", "bot_delay_ms = 250\n")
+            .replace(
+                "bot_difficulty = \"challenging\"\n",
+                "bot_difficulty = \"easy\"\n",
+            )
+            .replace("hearts = \"Red\"\n", "hearts = \"#FF005D\"\n");
+        assert_ne!(expected, example);
+        assert_eq!(fs::read_to_string(&path).unwrap(), expected);
+
+        write_settings(&path, &[("keys.rules", "f2".into())]).unwrap();
+        let saved = fs::read_to_string(&path).unwrap();
+        assert_eq!(
+            saved.replacen("[keys]\nrules = \"f2\"\n\n", "", 1),
+            expected
+        );
+        let table: toml::Table = saved.parse().unwrap();
+        assert_eq!(table["keys"]["rules"].as_str(), Some("f2"));
+    }
+}
@@ -0,0 +1,320 @@
+//! Daily deal challenge: one hand a day whose shuffle comes from the date, so
+//! everyone playing that day gets the same cards, plus a local leaderboard.
+
+use std::{collections::BTreeMap, fs, path::Path};
+
+use anyhow::{Context, Result};
+use chrono::{Days, Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    bot::BotDifficulty,
+    game::{seed_from_text, PlayerId},
+    storage::{set_aside, write_atomic},
+};
+
+/// Leaderboard files keep dates as `YYYY-MM-DD`.
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// The daily deal is always dealt by the bot, so you draw first.
+pub const DAILY_DEALER: PlayerId = PlayerId::Bot;
+
+/// Today's date on this machine.
+pub fn today() -> NaiveDate {
+    Local::now().date_naive()
+}
+
+/// The shuffle seed for `date`'s deal.
+pub fn seed_for(date: NaiveDate) -> u64 {
+    seed_from_text(&format!("deadwood daily {}", date.format(DATE_FORMAT)))
+}
+
+/// How one profile did on one day's deal.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DailyResult {
+    pub winner: Option<PlayerId>,
+    /// Points from the player's side: negative when the bot won.
+    pub points: i32,
+    pub difficulty: BotDifficulty,
+    /// Seconds since the Unix epoch when the hand finished, or started if it
+    /// is unfinished.
+    #[serde(default)]
+    pub timestamp: u64,
+    /// False from the moment the deal starts until the hand ends, so leaving
+    /// mid-hand still uses up the day's attempt.
+    #[serde(default = "default_finished")]
+    pub finished: bool,
+}
+
+fn default_finished() -> bool {
+    true
+}
+
+impl DailyResult {
+    /// The entry recorded when the deal starts: no winner and no points yet.
+    pub fn started(difficulty: BotDifficulty, timestamp: u64) -> Self {
+        Self {
+            winner: None,
+            points: 0,
+            difficulty,
+            timestamp,
+            finished: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DailyStreaks {
+    /// Consecutive days played up to today (or yesterday, if today is still open).
+    pub played: u32,
+    pub best_played: u32,
+    /// Consecutive daily wins in that run, counting back from the latest day.
+    pub won: u32,
+}
+
+/// Results keyed by date, then profile, shared by every profile on the machine.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Leaderboard {
+    #[serde(default)]
+    days: BTreeMap<String, BTreeMap<String, DailyResult>>,
+    /// Set when the file on disk could not be loaded, so the next save moves it
+    /// aside instead of overwriting it.
+    #[serde(skip)]
+    unreadable: bool,
+}
+
+impl Leaderboard {
+    /// Loads the leaderboard; a missing file is an empty board.
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let contents = fs::read_to_string(path)
+            .with_context(|| format!("failed to read daily leaderboard at {}", path.display()))?;
+        serde_json::from_str(&contents)
+            .with_context(|| format!("failed to parse daily leaderboard at {}", path.display()))
+    }
+
+    /// An empty board to use when [`Leaderboard::load`] failed.
+    pub fn replacing_unreadable() -> Self {
+        Self {
+            unreadable: true,
+            ..Self::default()
+        }
+    }
+
+    pub fn save(&mut self, path: &Path) -> Result<()> {
+        let contents =
+            serde_json::to_string_pretty(self).context("failed to serialise daily leaderboard")?;
+        if self.unreadable && path.exists() {
+            set_aside(path)?;
+        }
+        self.unreadable = false;
+        write_atomic(path, contents.as_bytes())
+            .with_context(|| format!("failed to write daily leaderboard to {}", path.display()))
+    }
+
+    pub fn record(&mut self, date: NaiveDate, profile: &str, result: DailyResult) {
+        self.days
+            .entry(date.format(DATE_FORMAT).to_string())
+            .or_default()
+            .insert(profile.to_string(), result);
+    }
+
+    pub fn result(&self, date: NaiveDate, profile: &str) -> Option<&DailyResult> {
+        self.days
+            .get(&date.format(DATE_FORMAT).to_string())?
+            .get(profile)
+    }
+
+    /// Every profile's result for `date`, best score first and unfinished hands last.
+    pub fn standings(&self, date: NaiveDate) -> Vec<(&str, &DailyResult)> {
+        let mut standings: Vec<_> = self
+            .days
+            .get(&date.format(DATE_FORMAT).to_string())
+            .map(|day| {
+                day.iter()
+                    .map(|(profile, result)| (profile.as_str(), result))
+                    .collect()
+            })
+            .unwrap_or_default();
+        standings.sort_by(|a, b| {
+            (b.1.finished, b.1.points)
+                .cmp(&(a.1.finished, a.1.points))
+                .then(a.0.cmp(b.0))
+        });
+        standings
+    }
+
+    /// `profile`'s results, newest first.
+    pub fn history(&self, profile: &str) -> Vec<(NaiveDate, &DailyResult)> {
+        self.days
+            .iter()
+            .rev()
+            .filter_map(|(date, day)| {
+                let date = NaiveDate::parse_from_str(date, DATE_FORMAT).ok()?;
+                Some((date, day.get(profile)?))
+            })
+            .collect()
+    }
+
+    pub fn streaks(&self, profile: &str, today: NaiveDate) -> DailyStreaks {
+        let mut history = self.history(profile);
+        history.reverse();
+        let mut streaks = DailyStreaks::default();
+        let mut run = 0;
+        let mut wins = 0;
+        let mut previous: Option<NaiveDate> = None;
+        for (date, result) in &history {
+            let consecutive =
+                previous.and_then(|day| day.checked_add_days(Days::new(1))) == Some(*date);
+            if !consecutive {
+                run = 0;
+                wins = 0;
+            }
+            run += 1;
+            wins = if result.winner == Some(PlayerId::Human) {
+                wins + 1
+            } else {
+                0
+            };
+            streaks.best_played = streaks.best_played.max(run);
+            previous = Some(*date);
+        }
+        let yesterday = today.checked_sub_days(Days::new(1));
+        if previous == Some(today) || previous == yesterday {
+            streaks.played = run;
+            streaks.won = wins;
+        }
+        streaks
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(text: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(text, DATE_FORMAT).unwrap()
+    }
+
+    fn result(winner: Option<PlayerId>, points: i32) -> DailyResult {
+        DailyResult {
+            winner,
+            points,
+            difficulty: BotDifficulty::Challenging,
+            timestamp: 0,
+            finished: true,
+        }
+    }
+
+    fn won() -> DailyResult {
+        result(Some(PlayerId::Human), 25)
+    }
+
+    fn lost() -> DailyResult {
+        result(Some(PlayerId::Bot), -12)
+    }
+
+    #[test]
+    fn seed_is_stable_across_builds() {
+        assert_eq!(seed_for(day("2026-10-18")), 0x99c1_4242_1f75_a95b);
+        assert_eq!(seed_for(day("2024-02-29")), 0xa58d_32e7_2ce7_ff3c);
+        assert_ne!(seed_for(day("2026-10-18")), seed_for(day("2026-10-19")));
+    }
+
+    #[test]
+    fn streaks_count_consecutive_days_up_to_today_or_yesterday() {
+        let mut board = Leaderboard::default();
+        for date in ["2026-10-01", "2026-10-02", "2026-10-03", "2026-10-04"] {
+            board.record(day(date), "ana", won());
+        }
+        board.record(day("2026-10-10"), "ana", lost());
+        board.record(day("2026-10-11"), "ana", won());
+        board.record(day("2026-10-12"), "ana", won());
+        board.record(day("2026-10-12"), "ben", lost());
+
+        let streaks = board.streaks("ana", day("2026-10-12"));
+        assert_eq!(
+            streaks,
+            DailyStreaks {
+                played: 3,
+                best_played: 4,
+                won: 2,
+            }
+        );
+        // Today is still open, so yesterday's run carries on.
+        assert_eq!(board.streaks("ana", day("2026-10-13")), streaks);
+        let lapsed = board.streaks("ana", day("2026-10-14"));
+        assert_eq!((lapsed.played, lapsed.best_played, lapsed.won), (0, 4, 0));
+        assert_eq!(board.streaks("ben", day("2026-10-12")).won, 0);
+        assert_eq!(
+            board.streaks("cy", day("2026-10-12")),
+            DailyStreaks::default()
+        );
+    }
+
+    #[test]
+    fn an_unfinished_deal_keeps_the_run_but_breaks_the_wins() {
+        let mut board = Leaderboard::default();
+        board.record(day("2026-10-01"), "ana", won());
+        board.record(
+            day("2026-10-02"),
+            "ana",
+            DailyResult::started(BotDifficulty::Easy, 0),
+        );
+        let streaks = board.streaks("ana", day("2026-10-02"));
+        assert_eq!((streaks.played, streaks.won), (2, 0));
+    }
+
+    #[test]
+    fn standings_rank_by_points_then_name_with_unfinished_hands_last() {
+        let date = day("2026-10-18");
+        let mut board = Leaderboard::default();
+        board.record(date, "dee", DailyResult::started(BotDifficulty::Easy, 0));
+        board.record(date, "cy", lost());
+        board.record(date, "ben", won());
+        board.record(date, "ana", won());
+        board.record(date, "eli", result(None, 0));
+        board.record(day("2026-10-17"), "fay", won());
+
+        let names: Vec<&str> = board
+            .standings(date)
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect();
+        assert_eq!(names, ["ana", "ben", "eli", "cy", "dee"]);
+        assert!(board.standings(day("2026-10-19")).is_empty());
+    }
+
+    #[test]
+    fn entries_saved_before_unfinished_hands_count_as_finished() {
+        let board: Leaderboard = serde_json::from_str(
+            r#"{"days": {"2026-10-18": {"ana": {"winner": "human", "points": 30, "difficulty": "easy"}}}}"#,
+        )
+        .unwrap();
+        let entry = board.result(day("2026-10-18"), "ana").unwrap();
+        assert!(entry.finished);
+        assert_eq!(entry.points, 30);
+    }
+
+    #[test]
+    fn an_unreadable_leaderboard_is_moved_aside_on_save() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("daily.json");
+        fs::write(&path, "{ not json").unwrap();
+        assert!(Leaderboard::load(&path).is_err());
+
+        let mut board = Leaderboard::replacing_unreadable();
+        board.record(day("2026-10-18"), "ana", won());
+        board.save(&path).unwrap();
+        let aside = dir.path().join("daily.json.unreadable");
+        assert_eq!(fs::read_to_string(&aside).unwrap(), "{ not json");
+        let loaded = Leaderboard::load(&path).unwrap();
+        assert_eq!(loaded.result(day("2026-10-18"), "ana"), Some(&won()));
+
+        // Later saves overwrite the new file as usual.
+        board.save(&path).unwrap();
+        assert!(!dir.path().join("daily.json.unreadable.2").exists());
+    }
+}
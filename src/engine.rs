@@ -0,0 +1,419 @@
+//! Bots running as separate processes, speaking a line-based protocol on
+//! stdin/stdout in the spirit of UCI.
+//!
+//! Every message is one line of space-separated tokens. Cards use the same
+//! labels as the TUI (`7H`, `10S`, `KD`).
+//!
+//! ```text
+//! deadwood -> engine                          engine -> deadwood
+//! deadwood 1                                  id name <free text>   (optional)
+//! option difficulty <easy|challenging|adaptive>
+//! isready                                     ready
+//! newround dealer <me|opponent>
+//! position hand <cards> discard <cards|-> stock <n> opponent <n> score <me> <opp> picked <cards|->
+//! go draw                                     draw <stock|discard>
+//! go discard                                  discard <card> [knock]
+//! illegal <reason>
+//! result <win|loss|draw> points <n>
+//! quit
+//! ```
+//!
+//! `position` always precedes `go`. The discard pile is listed bottom to top;
+//! `picked` lists the cards the opponent took from it and still holds.
+//! Engines may print `info <text>` lines at any time; they are shown in the bot
+//! reasoning panel. A reply that is late, malformed or illegal is reported back
+//! with `illegal` and the built-in heuristic plays that decision instead. After
+//! `MAX_FAILURES` consecutive failures, or if the process exits, the heuristic
+//! takes over for the rest of the session.
+
+use std::{
+    io::{BufRead, BufReader, Write},
+    process::{Child, ChildStdin, Command, Stdio},
+    sync::mpsc::{self, Receiver, RecvTimeoutError},
+    thread,
+    time::Duration,
+};
+
+use anyhow::{anyhow, bail, Context, Result};
+
+use crate::{
+    bot::{BotDifficulty, HeuristicStrategy, Strategy, TurnTrace},
+    cards::Card,
+    game::{DrawSource, Game, PlayerId, RoundResult},
+    meld::analyze_hand,
+};
+
+pub const ENGINE_STRATEGY: &str = "engine";
+pub const PROTOCOL_VERSION: u32 = 1;
+const MAX_FAILURES: u32 = 3;
+
+#[derive(Debug, Clone)]
+pub struct EngineConfig {
+    pub command: String,
+    pub args: Vec<String>,
+    pub timeout: Duration,
+}
+
+pub struct EngineStrategy {
+    child: Child,
+    stdin: ChildStdin,
+    lines: Receiver<String>,
+    timeout: Duration,
+    engine_name: Option<String>,
+    fallback: HeuristicStrategy,
+    failures: u32,
+    disabled: bool,
+    pending_knock: bool,
+    trace: TurnTrace,
+}
+
+impl EngineStrategy {
+    pub fn spawn(config: &EngineConfig, difficulty: BotDifficulty) -> Result<Self> {
+        let mut child = Command::new(&config.command)
+            .args(&config.args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .with_context(|| format!("failed to launch engine '{}'", config.command))?;
+
+        let stdin = child
+            .stdin
+            .take()
+            .ok_or_else(|| anyhow!("engine stdin unavailable"))?;
+        let stdout = child
+            .stdout
+            .take()
+            .ok_or_else(|| anyhow!("engine stdout unavailable"))?;
+
+        let (sender, lines) = mpsc::channel();
+        thread::spawn(move || {
+            for line in BufReader::new(stdout).lines() {
+                let Ok(line) = line else {
+                    break;
+                };
+                if sender.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+
+        let mut engine = Self {
+            child,
+            stdin,
+            lines,
+            timeout: config.timeout,
+            engine_name: None,
+            fallback: HeuristicStrategy::new(difficulty),
+            failures: 0,
+            disabled: false,
+            pending_knock: false,
+            trace: TurnTrace::default(),
+        };
+        engine.handshake(difficulty)?;
+        Ok(engine)
+    }
+
+    fn handshake(&mut self, difficulty: BotDifficulty) -> Result<()> {
+        self.send(&format!("deadwood {PROTOCOL_VERSION}"))?;
+        self.send(&format!("option difficulty {}", difficulty.name()))?;
+        self.send("isready")?;
+        loop {
+            let line = self.receive()?;
+            let mut tokens = line.split_whitespace();
+            match (tokens.next(), tokens.next()) {
+                (Some("ready"), _) => return Ok(()),
+                (Some("id"), Some("name")) => {
+                    let name = tokens.collect::<Vec<_>>().join(" ");
+                    self.engine_name = Some(name);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn send(&mut self, line: &str) -> Result<()> {
+        writeln!(self.stdin, "{line}")
+            .and_then(|_| self.stdin.flush())
+            .context("failed to write to engine")
+    }
+
+    /// Next non-`info` line from the engine, recording any `info` output as trace notes.
+    fn receive(&mut self) -> Result<String> {
+        loop {
+            let line = match self.lines.recv_timeout(self.timeout) {
+                Ok(line) => line,
+                Err(RecvTimeoutError::Timeout) => {
+                    bail!("no reply within {} ms", self.timeout.as_millis())
+                }
+                Err(RecvTimeoutError::Disconnected) => bail!("engine exited"),
+            };
+            let trimmed = line.trim();
+            if trimmed.is_empty() {
+                continue;
+            }
+            if let Some(info) = trimmed.strip_prefix("info") {
+                self.trace.notes.push(format!("Engine: {}", info.trim()));
+                continue;
+            }
+            return Ok(trimmed.to_string());
+        }
+    }
+
+    fn request(&mut self, game: &Game, seat: PlayerId, go: &str) -> Result<String> {
+        // Discard anything left over from an earlier request that timed out.
+        while self.lines.try_recv().is_ok() {}
+        let position = position_line(game, seat);
+        self.send(&position)?;
+        self.send(&format!("go {go}"))?;
+        self.receive()
+    }
+
+    fn record_failure(&mut self, err: &anyhow::Error) {
+        self.failures += 1;
+        let _ = self.send(&format!("illegal {err}"));
+        self.trace.notes.push(format!(
+            "Engine error: {err}. Heuristic played this decision."
+        ));
+        if self.failures >= MAX_FAILURES || matches!(self.child.try_wait(), Ok(Some(_))) {
+            self.disabled = true;
+            self.trace
+                .notes
+                .push("Engine disabled; the heuristic bot takes over.".to_string());
+        }
+    }
+
+    fn engine_draw(&mut self, game: &Game, seat: PlayerId) -> Result<DrawSource> {
+        let reply = self.request(game, seat, "draw")?;
+        let mut tokens = reply.split_whitespace();
+        let source = match (tokens.next(), tokens.next()) {
+            (Some("draw"), Some("stock")) => DrawSource::Stock,
+            (Some("draw"), Some("discard")) => DrawSource::Discard,
+            _ => bail!("expected 'draw stock' or 'draw discard', got '{reply}'"),
+        };
+        if source == DrawSource::Discard && game.discard.is_empty() {
+            bail!("discard pile is empty");
+        }
+        Ok(source)
+    }
+
+    fn engine_discard(&mut self, game: &Game, seat: PlayerId) -> Result<(usize, bool)> {
+        let reply = self.request(game, seat, "discard")?;
+        let tokens: Vec<&str> = reply.split_whitespace().collect();
+        let (label, knock) = match tokens.as_slice() {
+            ["discard", card] => (*card, false),
+            ["discard", card, "knock"] => (*card, true),
+            _ => bail!("expected 'discard <card> [knock]', got '{reply}'"),
+        };
+
+        let hand = &game.player(seat).hand;
+        let index = hand
+            .iter()
+            .position(|card| card.to_string().eq_ignore_ascii_case(label))
+            .ok_or_else(|| anyhow!("{label} is not in hand"))?;
+
+        if knock {
+            let mut remaining = hand.clone();
+            remaining.remove(index);
+            let deadwood = analyze_hand(&remaining).deadwood_value;
+            if deadwood > 10 {
+                bail!("cannot knock with {deadwood} deadwood");
+            }
+        }
+        Ok((index, knock))
+    }
+}
+
+impl Strategy for EngineStrategy {
+    fn name(&self) -> &str {
+        ENGINE_STRATEGY
+    }
+
+    fn on_round_start(&mut self, game: &Game, seat: PlayerId) {
+        self.fallback.on_round_start(game, seat);
+        if self.disabled {
+            return;
+        }
+        let dealer = if game.dealer == seat {
+            "me"
+        } else {
+            "opponent"
+        };
+        if let Err(err) = self.send(&format!("newround dealer {dealer}")) {
+            self.record_failure(&err);
+        }
+    }
+
+    fn choose_draw(&mut self, game: &Game, seat: PlayerId) -> DrawSource {
+        self.trace = TurnTrace::default();
+        if !self.disabled {
+            match self.engine_draw(game, seat) {
+                Ok(source) => {
+                    self.failures = 0;
+                    return source;
+                }
+                Err(err) => self.record_failure(&err),
+            }
+        }
+        let source = self.fallback.choose_draw(game, seat);
+        self.trace.draw = self
+            .fallback
+            .last_trace()
+            .and_then(|trace| trace.draw.clone());
+        source
+    }
+
+    fn choose_discard(&mut self, game: &Game, seat: PlayerId) -> usize {
+        self.pending_knock = false;
+        if !self.disabled {
+            match self.engine_discard(game, seat) {
+                Ok((index, knock)) => {
+                    self.failures = 0;
+                    self.pending_knock = knock;
+                    return index;
+                }
+                Err(err) => self.record_failure(&err),
+            }
+        }
+        let index = self.fallback.choose_discard(game, seat);
+        self.pending_knock = self.fallback.decide_knock(game, seat, index);
+        if let Some(trace) = self.fallback.last_trace() {
+            self.trace.discard = trace.discard.clone();
+            self.trace.knock = trace.knock.clone();
+        }
+        index
+    }
+
+    fn decide_knock(&mut self, _game: &Game, _seat: PlayerId, _discard_index: usize) -> bool {
+        self.pending_knock
+    }
+
+    fn on_round_end(&mut self, game: &Game, seat: PlayerId, result: &RoundResult) {
+        self.fallback.on_round_end(game, seat, result);
+        if self.disabled {
+            return;
+        }
+        let outcome = match result.winner {
+            Some(winner) if winner == seat => "win",
+            Some(_) => "loss",
+            None => "draw",
+        };
+        let line = format!("result {outcome} points {}", result.points_awarded);
+        if let Err(err) = self.send(&line) {
+            self.record_failure(&err);
+        }
+    }
+
+    fn last_trace(&self) -> Option<&TurnTrace> {
+        Some(&self.trace)
+    }
+}
+
+impl Drop for EngineStrategy {
+    fn drop(&mut self) {
+        let _ = self.send("quit");
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+fn position_line(game: &Game, seat: PlayerId) -> String {
+    let discard = if game.discard.is_empty() {
+        "-".to_string()
+    } else {
+        card_list(&game.discard)
+    };
+    let picked = game.known_cards(seat.other());
+    let picked = if picked.is_empty() {
+        "-".to_string()
+    } else {
+        card_list(&picked)
+    };
+    let (mine, theirs) = match seat {
+        PlayerId::Human => (game.scoreboard.human, game.scoreboard.bot),
+        PlayerId::Bot => (game.scoreboard.bot, game.scoreboard.human),
+    };
+    format!(
+        "position hand {} discard {discard} stock {} opponent {} score {mine} {theirs} picked {picked}",
+        card_list(&game.player(seat).hand),
+        game.stock.len(),
+        game.player(seat.other()).hand.len(),
+    )
+}
+
+fn card_list(cards: &[Card]) -> String {
+    cards
+        .iter()
+        .map(|card| card.to_string())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        bot::take_turn,
+        game::{ActionOutcome, TurnPhase},
+    };
+
+    fn mock_engine(mode: &str, timeout_ms: u64) -> EngineConfig {
+        EngineConfig {
+            command: "sh".to_string(),
+            args: vec![
+                concat!(env!("CARGO_MANIFEST_DIR"), "/scripts/mock_engine.sh").to_string(),
+                mode.to_string(),
+            ],
+            timeout: Duration::from_millis(timeout_ms),
+        }
+    }
+
+    fn play_round(engine: &mut EngineStrategy) -> Game {
+        let mut game = Game::new().unwrap();
+        let mut opponent = HeuristicStrategy::new(BotDifficulty::Challenging);
+        engine.on_round_start(&game, PlayerId::Bot);
+        while game.phase != TurnPhase::RoundOver {
+            let seat = game.current_player;
+            let strategy: &mut dyn Strategy = match seat {
+                PlayerId::Bot => engine,
+                PlayerId::Human => &mut opponent,
+            };
+            if take_turn(&mut game, seat, strategy).unwrap() == ActionOutcome::RoundEnded {
+                break;
+            }
+        }
+        engine.on_round_end(&game, PlayerId::Bot, game.pending_round.as_ref().unwrap());
+        game
+    }
+
+    #[test]
+    fn mock_engine_plays_a_full_round() {
+        let mut engine =
+            EngineStrategy::spawn(&mock_engine("play", 2000), BotDifficulty::Easy).unwrap();
+        assert_eq!(engine.engine_name.as_deref(), Some("mock"));
+        let game = play_round(&mut engine);
+        assert_eq!(game.phase, TurnPhase::RoundOver);
+        assert!(!engine.disabled);
+        assert_eq!(engine.failures, 0);
+    }
+
+    #[test]
+    fn illegal_replies_fall_back_to_heuristic() {
+        let mut engine =
+            EngineStrategy::spawn(&mock_engine("illegal", 2000), BotDifficulty::Easy).unwrap();
+        let game = play_round(&mut engine);
+        assert_eq!(game.phase, TurnPhase::RoundOver);
+        assert!(engine.disabled);
+        assert_eq!(engine.failures, MAX_FAILURES);
+    }
+
+    #[test]
+    fn silent_engine_times_out() {
+        let mut engine =
+            EngineStrategy::spawn(&mock_engine("silent", 100), BotDifficulty::Easy).unwrap();
+        let game = Game::new().unwrap();
+        engine.choose_draw(&game, game.current_player);
+        assert_eq!(engine.failures, 1);
+        assert!(engine.trace.notes[0].contains("no reply"));
+    }
+}
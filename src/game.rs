@@ -1,7 +1,7 @@
 use std::fmt::{Display, Formatter};
 
 use anyhow::{anyhow, Result};
-use rand::{seq::SliceRandom, thread_rng};
+use rand::{rngs::StdRng, seq::SliceRandom, thread_rng, Rng, SeedableRng};
 use serde::{Deserialize, Serialize};
 
 use crate::{
@@ -11,7 +11,8 @@ use crate::{
 
 const BIG_GIN_BONUS: i32 = 31;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum PlayerId {
     Human,
     Bot,
@@ -26,12 +27,67 @@ impl PlayerId {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum DrawSource {
     Stock,
     Discard,
 }
 
+/// The rule sets a hand can be played under.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Rules {
+    /// Knock on 10 or less, 25-point gin and undercut bonuses, 31 for Big Gin.
+    #[default]
+    Standard,
+}
+
+impl Rules {
+    pub const ALL: [Rules; 1] = [Rules::Standard];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Rules::Standard => "standard",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|rules| rules.name().eq_ignore_ascii_case(name.trim()))
+    }
+
+    /// Every variant name, for error messages.
+    pub fn names() -> String {
+        Self::ALL.map(Rules::name).join(", ")
+    }
+}
+
+/// The cards as dealt at the start of the round.
+#[derive(Debug, Clone)]
+pub struct Deal {
+    pub dealer: PlayerId,
+    pub human: Vec<Card>,
+    pub bot: Vec<Card>,
+    /// The first card turned up to start the discard pile.
+    pub upcard: Option<Card>,
+}
+
+/// One action in the current round, in the order it was played.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Move {
+    Draw {
+        player: PlayerId,
+        source: DrawSource,
+        card: Card,
+    },
+    Discard {
+        player: PlayerId,
+        card: Card,
+        knock: bool,
+    },
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum TurnPhase {
     AwaitDraw,
@@ -39,7 +95,7 @@ pub enum TurnPhase {
     RoundOver,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct Player {
     pub hand: Vec<Card>,
 }
@@ -165,10 +221,28 @@ pub struct Game {
     pub scoreboard: Scoreboard,
     pub pending_round: Option<RoundResult>,
     pub last_round_winner: Option<PlayerId>,
+    /// Seed used to shuffle the current round's deck.
+    pub round_seed: u64,
+    /// The hands and upcard this round started from.
+    pub deal: Deal,
+    /// Draws and discards made so far this round.
+    pub moves: Vec<Move>,
+    /// Seed to use for the next shuffle instead of a random one.
+    next_seed: Option<u64>,
+    rng: StdRng,
 }
 
 impl Game {
     pub fn new() -> Result<Self> {
+        Self::with_rng(StdRng::from_entropy())
+    }
+
+    /// A game whose deals are fully determined by `seed`.
+    pub fn with_seed(seed: u64) -> Result<Self> {
+        Self::with_rng(StdRng::seed_from_u64(seed))
+    }
+
+    fn with_rng(rng: StdRng) -> Result<Self> {
         let mut game = Self {
             human: Player::new(),
             bot: Player::new(),
@@ -180,6 +254,16 @@ impl Game {
             scoreboard: Scoreboard::default(),
             pending_round: None,
             last_round_winner: None,
+            round_seed: 0,
+            deal: Deal {
+                dealer: PlayerId::Bot,
+                human: Vec::new(),
+                bot: Vec::new(),
+                upcard: None,
+            },
+            moves: Vec::new(),
+            next_seed: None,
+            rng,
         };
 
         game.start_round()?;
@@ -191,8 +275,13 @@ impl Game {
         self.bot.hand.clear();
         self.stock = build_deck();
         self.discard.clear();
+        self.moves.clear();
 
-        let mut rng = thread_rng();
+        self.round_seed = match self.next_seed.take() {
+            Some(seed) => seed,
+            None => self.rng.gen(),
+        };
+        let mut rng = StdRng::seed_from_u64(self.round_seed);
         self.stock.shuffle(&mut rng);
 
         for _ in 0..HAND_SIZE {
@@ -206,6 +295,12 @@ impl Game {
 
         let starter = self.draw_from_stock()?;
         self.discard.push(starter);
+        self.deal = Deal {
+            dealer: self.dealer,
+            human: self.human.hand.clone(),
+            bot: self.bot.hand.clone(),
+            upcard: Some(starter),
+        };
         self.current_player = self.dealer.other();
         self.phase = TurnPhase::AwaitDraw;
         self.pending_round = None;
@@ -213,6 +308,80 @@ impl Game {
         Ok(())
     }
 
+    /// Sets up a round from a recorded deal instead of a shuffle, for replays.
+    /// The stock order is not recorded, so the stock holds the remaining cards
+    /// in deck order and [`Game::apply`] pulls out each recorded stock draw.
+    pub fn from_deal(deal: Deal) -> Result<Self> {
+        let mut game = Self::with_seed(0)?;
+        let upcard = deal
+            .upcard
+            .ok_or_else(|| anyhow!("the deal has no upcard"))?;
+        let mut dealt: Vec<Card> = deal.human.iter().chain(&deal.bot).copied().collect();
+        dealt.push(upcard);
+        let mut unique = dealt.clone();
+        unique.sort();
+        unique.dedup();
+        if deal.human.len() != HAND_SIZE || deal.bot.len() != HAND_SIZE {
+            return Err(anyhow!("each hand must be dealt {HAND_SIZE} cards"));
+        }
+        if unique.len() != dealt.len() {
+            return Err(anyhow!("the deal contains a card twice"));
+        }
+
+        game.human.hand = deal.human.clone();
+        game.human.sort_hand();
+        game.bot.hand = deal.bot.clone();
+        game.bot.sort_hand();
+        game.stock = build_deck()
+            .into_iter()
+            .filter(|card| !dealt.contains(card))
+            .collect();
+        game.discard = vec![upcard];
+        game.dealer = deal.dealer;
+        game.current_player = deal.dealer.other();
+        game.phase = TurnPhase::AwaitDraw;
+        game.moves.clear();
+        game.deal = deal;
+        Ok(game)
+    }
+
+    /// Replays a recorded move under the normal rules.
+    pub fn apply(&mut self, mv: Move) -> Result<ActionOutcome> {
+        match mv {
+            Move::Draw {
+                player,
+                source,
+                card,
+            } => {
+                let available = match source {
+                    DrawSource::Stock => self.stock.iter().position(|&held| held == card),
+                    DrawSource::Discard => {
+                        (self.discard.last() == Some(&card)).then(|| self.discard.len() - 1)
+                    }
+                };
+                let index = available.ok_or_else(|| anyhow!("{card} cannot be drawn here"))?;
+                if source == DrawSource::Stock {
+                    let card = self.stock.remove(index);
+                    self.stock.push(card);
+                }
+                self.draw(player, source)
+            }
+            Move::Discard {
+                player,
+                card,
+                knock,
+            } => {
+                let index = self
+                    .player(player)
+                    .hand
+                    .iter()
+                    .position(|&held| held == card)
+                    .ok_or_else(|| anyhow!("{card} is not in the discarding hand"))?;
+                self.discard(player, index, knock)
+            }
+        }
+    }
+
     pub fn restart_with_starting_player(&mut self, starter: PlayerId) -> Result<()> {
         self.dealer = starter.other();
         self.start_round()
@@ -250,6 +419,11 @@ impl Game {
                 .pop()
                 .ok_or_else(|| anyhow!("discard pile empty"))?,
         };
+        self.moves.push(Move::Draw {
+            player,
+            source,
+            card,
+        });
 
         {
             let player_ref = self.player_mut(player);
@@ -311,6 +485,11 @@ impl Game {
             card
         };
         self.discard.push(card);
+        self.moves.push(Move::Discard {
+            player,
+            card,
+            knock: declare_knock,
+        });
 
         if declare_knock {
             let result = self.resolve_knock(player)?;
@@ -324,42 +503,11 @@ impl Game {
 
     pub fn resolve_knock(&mut self, knocker: PlayerId) -> Result<RoundResult> {
         let opponent = knocker.other();
-        let knocker_hand = self.player(knocker).hand.clone();
-        let opponent_hand = self.player(opponent).hand.clone();
-
-        let knocker_analysis = analyze_hand(&knocker_hand);
-        if knocker_analysis.deadwood_value > 10 {
-            return Err(anyhow!("deadwood too high to knock"));
-        }
-
-        let gin = knocker_analysis.deadwood_value == 0;
-        let opponent_analysis = analyze_hand(&opponent_hand);
-
-        let (opponent_deadwood_cards, laid_off) = if gin {
-            (opponent_analysis.deadwood.clone(), Vec::new())
-        } else {
-            layoff_cards(&opponent_analysis.deadwood, &knocker_analysis.melds)
-        };
-
-        let opponent_deadwood_value: u32 = opponent_deadwood_cards
-            .iter()
-            .map(|c| c.rank.value() as u32)
-            .sum();
-
-        let mut winner = knocker;
-        let mut points = opponent_deadwood_value as i32 - knocker_analysis.deadwood_value as i32;
-        let mut undercut = false;
-
-        if opponent_deadwood_value <= knocker_analysis.deadwood_value as u32 && !gin {
-            winner = opponent;
-            undercut = true;
-            points = (knocker_analysis.deadwood_value as i32 - opponent_deadwood_value as i32) + 25;
-        } else {
-            if gin {
-                points += 25;
-            }
-        }
+        let score = score_knock(&self.player(knocker).hand, &self.player(opponent).hand)
+            .ok_or_else(|| anyhow!("deadwood too high to knock"))?;
 
+        let winner = if score.undercut { opponent } else { knocker };
+        let mut points = score.points;
         if winner == PlayerId::Bot && points < 0 {
             points = 0;
         }
@@ -369,11 +517,11 @@ impl Game {
             points_awarded: points.abs(),
             reason: RoundEndReason::Knock {
                 knocker,
-                knocker_deadwood: knocker_analysis.deadwood_value,
-                opponent_deadwood: opponent_deadwood_value,
-                laid_off,
-                gin,
-                undercut,
+                knocker_deadwood: score.knocker_deadwood,
+                opponent_deadwood: score.opponent_deadwood,
+                laid_off: score.laid_off,
+                gin: score.gin,
+                undercut: score.undercut,
             },
             human_hand: self.human.hand.clone(),
             bot_hand: self.bot.hand.clone(),
@@ -414,6 +562,17 @@ impl Game {
         self.start_round()
     }
 
+    /// Starts the next round with a fixed shuffle seed and dealer, so the same
+    /// deal comes up wherever it is played.
+    pub fn start_seeded_round(&mut self, seed: u64, dealer: PlayerId) -> Result<()> {
+        if self.phase != TurnPhase::RoundOver {
+            return Err(anyhow!("round still in progress"));
+        }
+        self.dealer = dealer;
+        self.next_seed = Some(seed);
+        self.start_round()
+    }
+
     fn advance_turn(&mut self) {
         self.current_player = self.current_player.other();
         self.phase = TurnPhase::AwaitDraw;
@@ -432,6 +591,113 @@ impl Game {
             PlayerId::Bot => &mut self.bot,
         }
     }
+
+    /// Cards `player` is known to hold: taken from the discard pile and not
+    /// thrown back since.
+    pub fn known_cards(&self, player: PlayerId) -> Vec<Card> {
+        let mut known = Vec::new();
+        for entry in &self.moves {
+            match *entry {
+                Move::Draw {
+                    player: who,
+                    source: DrawSource::Discard,
+                    card,
+                } if who == player => known.push(card),
+                Move::Discard {
+                    player: who, card, ..
+                } if who == player => known.retain(|c| *c != card),
+                _ => {}
+            }
+        }
+        known
+    }
+
+    /// Cards `player` has discarded this round.
+    pub fn discards_by(&self, player: PlayerId) -> Vec<Card> {
+        self.moves
+            .iter()
+            .filter_map(|entry| match *entry {
+                Move::Discard {
+                    player: who, card, ..
+                } if who == player => Some(card),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Number of turns `player` has completed this round.
+    pub fn turns_taken(&self, player: PlayerId) -> usize {
+        self.moves
+            .iter()
+            .filter(|entry| matches!(entry, Move::Discard { player: who, .. } if *who == player))
+            .count()
+    }
+}
+
+/// How a knock would be scored for the given hands.
+#[derive(Debug, Clone)]
+pub struct KnockScore {
+    pub knocker_deadwood: u32,
+    /// Opponent deadwood after any layoffs.
+    pub opponent_deadwood: u32,
+    pub laid_off: Vec<Card>,
+    pub gin: bool,
+    pub undercut: bool,
+    /// Points awarded to the hand's winner (the opponent when undercut).
+    pub points: i32,
+}
+
+impl KnockScore {
+    /// Points from the knocker's point of view: negative when undercut.
+    pub fn knocker_points(&self) -> i32 {
+        if self.undercut {
+            -self.points
+        } else {
+            self.points
+        }
+    }
+}
+
+/// Scores a knock by `knocker_hand` against `opponent_hand`, including layoffs,
+/// the gin bonus and undercuts. Returns `None` if the knock would be illegal.
+pub fn score_knock(knocker_hand: &[Card], opponent_hand: &[Card]) -> Option<KnockScore> {
+    let knocker_analysis = analyze_hand(knocker_hand);
+    if knocker_analysis.deadwood_value > 10 {
+        return None;
+    }
+
+    let gin = knocker_analysis.deadwood_value == 0;
+    let opponent_analysis = analyze_hand(opponent_hand);
+
+    let (opponent_deadwood_cards, laid_off) = if gin {
+        (opponent_analysis.deadwood.clone(), Vec::new())
+    } else {
+        layoff_cards(&opponent_analysis.deadwood, &knocker_analysis.melds)
+    };
+
+    let opponent_deadwood_value: u32 = opponent_deadwood_cards
+        .iter()
+        .map(|c| c.rank.value() as u32)
+        .sum();
+
+    let mut points = opponent_deadwood_value as i32 - knocker_analysis.deadwood_value as i32;
+    let mut undercut = false;
+
+    if opponent_deadwood_value <= knocker_analysis.deadwood_value && !gin {
+        undercut = true;
+        points = (knocker_analysis.deadwood_value as i32 - opponent_deadwood_value as i32) + 25;
+    } else if gin {
+        points += 25;
+    }
+
+    Some(KnockScore {
+        knocker_deadwood: knocker_analysis.deadwood_value,
+        opponent_deadwood: opponent_deadwood_value,
+        laid_off,
+        gin,
+        undercut,
+        points,
+    })
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -440,7 +706,16 @@ pub enum ActionOutcome {
     RoundEnded,
 }
 
-fn build_deck() -> Vec<Card> {
+/// A shuffle seed derived from `text` with FNV-1a, which keeps it stable
+/// across builds and platforms, unlike the standard library's hasher.
+pub fn seed_from_text(text: &str) -> u64 {
+    text.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// All 52 cards, unshuffled.
+pub fn build_deck() -> Vec<Card> {
     let mut deck = Vec::with_capacity(52);
     for &suit in Suit::ALL.iter() {
         for &rank in Rank::ALL.iter() {
@@ -496,3 +771,136 @@ impl Game {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bot::{take_turn, BotDifficulty, HeuristicStrategy};
+
+    fn played_out(seed: u64) -> Game {
+        let mut game = Game::with_seed(seed).unwrap();
+        let mut bot = HeuristicStrategy::new(BotDifficulty::Easy);
+        let mut human = HeuristicStrategy::new(BotDifficulty::Easy);
+        while game.phase != TurnPhase::RoundOver {
+            let seat = game.current_player;
+            let strategy = match seat {
+                PlayerId::Bot => &mut bot,
+                PlayerId::Human => &mut human,
+            };
+            take_turn(&mut game, seat, strategy).unwrap();
+        }
+        game
+    }
+
+    fn sorted(cards: &[Card]) -> Vec<Card> {
+        let mut cards = cards.to_vec();
+        cards.sort();
+        cards
+    }
+
+    #[test]
+    fn from_deal_rebuilds_the_dealt_position() {
+        let dealt = Game::with_seed(11).unwrap();
+        let game = Game::from_deal(dealt.deal.clone()).unwrap();
+        assert_eq!(game.human.hand, sorted(&dealt.human.hand));
+        assert_eq!(game.bot.hand, sorted(&dealt.bot.hand));
+        assert_eq!(game.discard, dealt.discard);
+        assert_eq!(sorted(&game.stock), sorted(&dealt.stock));
+        assert_eq!(game.current_player, dealt.current_player);
+        assert_eq!(game.phase, TurnPhase::AwaitDraw);
+        assert!(game.moves.is_empty());
+    }
+
+    #[test]
+    fn from_deal_rejects_impossible_deals() {
+        let dealt = Game::with_seed(12).unwrap();
+
+        let mut repeated = dealt.deal.clone();
+        repeated.bot[0] = repeated.human[0];
+        let err = Game::from_deal(repeated).unwrap_err();
+        assert_eq!(err.to_string(), "the deal contains a card twice");
+
+        let mut short = dealt.deal.clone();
+        short.human.pop();
+        assert!(Game::from_deal(short).is_err());
+
+        let mut no_upcard = dealt.deal.clone();
+        no_upcard.upcard = None;
+        assert!(Game::from_deal(no_upcard).is_err());
+    }
+
+    #[test]
+    fn apply_replays_a_played_hand_to_the_same_result() {
+        for seed in 0..20 {
+            let played = played_out(seed);
+            let mut game = Game::from_deal(played.deal.clone()).unwrap();
+            for &mv in &played.moves {
+                game.apply(mv).unwrap();
+            }
+            assert_eq!(game.moves, played.moves, "seed {seed}");
+            assert_eq!(sorted(&game.human.hand), sorted(&played.human.hand));
+            assert_eq!(sorted(&game.bot.hand), sorted(&played.bot.hand));
+            assert_eq!(game.discard, played.discard);
+            let original = played.pending_round.unwrap();
+            // Running out of stock ends the hand on a draw that is not a move.
+            if matches!(original.reason, RoundEndReason::StockDepleted) {
+                assert_eq!(game.phase, TurnPhase::AwaitDraw, "seed {seed}");
+                continue;
+            }
+            let replayed = game.pending_round.expect("the replayed hand ends");
+            assert_eq!(replayed.winner, original.winner, "seed {seed}");
+            assert_eq!(replayed.points_awarded, original.points_awarded);
+        }
+    }
+
+    #[test]
+    fn apply_rejects_moves_the_rules_forbid() {
+        let mut game = Game::from_deal(Game::with_seed(13).unwrap().deal).unwrap();
+        let player = game.current_player;
+        let top = *game.discard.last().unwrap();
+        let held = game.player(player).hand[0];
+        let stock_card = game.stock[0];
+
+        let err = game
+            .apply(Move::Draw {
+                player,
+                source: DrawSource::Discard,
+                card: held,
+            })
+            .unwrap_err();
+        assert_eq!(err.to_string(), format!("{held} cannot be drawn here"));
+        assert!(game
+            .apply(Move::Draw {
+                player: player.other(),
+                source: DrawSource::Stock,
+                card: stock_card,
+            })
+            .is_err());
+        assert!(game
+            .apply(Move::Discard {
+                player,
+                card: held,
+                knock: false,
+            })
+            .is_err());
+
+        game.apply(Move::Draw {
+            player,
+            source: DrawSource::Stock,
+            card: stock_card,
+        })
+        .unwrap();
+        assert!(game.player(player).hand.contains(&stock_card));
+        let err = game
+            .apply(Move::Discard {
+                player,
+                card: top,
+                knock: false,
+            })
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            format!("{top} is not in the discarding hand")
+        );
+    }
+}
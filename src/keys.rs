@@ -0,0 +1,658 @@
+//! Key bindings: the actions a player can take, the keys bound to them and
+//! the `[keys]` config table that remaps them.
+
+use std::{collections::BTreeMap, fmt::Display, str::FromStr};
+
+use anyhow::{anyhow, bail, Error, Result};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+/// Where an action can be used: a phase of the hand, the replay viewer or a
+/// screen drawn over the game. Two actions may share a key only if they are
+/// never available at the same time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    Draw,
+    Discard,
+    RoundOver,
+    Replay,
+    Settings,
+    DifficultyPicker,
+    BotReasoning,
+    DailyDeal,
+    PuzzleVerdict,
+}
+
+impl Phase {
+    /// The phases of a hand being played, without the replay viewer.
+    pub const HAND: [Phase; 3] = [Phase::Draw, Phase::Discard, Phase::RoundOver];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Phase::Draw => "the draw phase",
+            Phase::Discard => "the discard phase",
+            Phase::RoundOver => "the round summary",
+            Phase::Replay => "the replay viewer",
+            Phase::Settings => "the settings screen",
+            Phase::DifficultyPicker => "the difficulty picker",
+            Phase::BotReasoning => "the bot's reasoning",
+            Phase::DailyDeal => "the daily deal",
+            Phase::PuzzleVerdict => "a solved puzzle",
+        }
+    }
+}
+
+/// Something a key can be bound to, named in the `[keys]` table by [`Action::name`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Action {
+    DrawStock,
+    DrawDiscard,
+    MoveLeft,
+    MoveRight,
+    MoveUp,
+    MoveDown,
+    Discard,
+    ToggleKnock,
+    Hint,
+    NextRound,
+    BotReasoning,
+    Replay,
+    ReplayBack,
+    ReplayForward,
+    ReplayFirst,
+    ReplayLast,
+    ReplayShowBot,
+    ReplayPreviousHand,
+    ReplayNextHand,
+    Stats,
+    Export,
+    DailyDeal,
+    Puzzles,
+    BotDifficulty,
+    Settings,
+    Rules,
+    Quit,
+}
+
+impl Action {
+    pub const ALL: [Action; 27] = [
+        Action::DrawStock,
+        Action::DrawDiscard,
+        Action::MoveLeft,
+        Action::MoveRight,
+        Action::MoveUp,
+        Action::MoveDown,
+        Action::Discard,
+        Action::ToggleKnock,
+        Action::Hint,
+        Action::NextRound,
+        Action::BotReasoning,
+        Action::Replay,
+        Action::ReplayBack,
+        Action::ReplayForward,
+        Action::ReplayFirst,
+        Action::ReplayLast,
+        Action::ReplayShowBot,
+        Action::ReplayPreviousHand,
+        Action::ReplayNextHand,
+        Action::Stats,
+        Action::Export,
+        Action::DailyDeal,
+        Action::Puzzles,
+        Action::BotDifficulty,
+        Action::Settings,
+        Action::Rules,
+        Action::Quit,
+    ];
+
+    /// The action's key in the `[keys]` table.
+    pub fn name(self) -> &'static str {
+        match self {
+            Action::DrawStock => "draw_stock",
+            Action::DrawDiscard => "draw_discard",
+            Action::MoveLeft => "move_left",
+            Action::MoveRight => "move_right",
+            Action::MoveUp => "move_up",
+            Action::MoveDown => "move_down",
+            Action::Discard => "discard",
+            Action::ToggleKnock => "toggle_knock",
+            Action::Hint => "hint",
+            Action::NextRound => "next_round",
+            Action::BotReasoning => "bot_reasoning",
+            Action::Replay => "replay",
+            Action::ReplayBack => "replay_back",
+            Action::ReplayForward => "replay_forward",
+            Action::ReplayFirst => "replay_first",
+            Action::ReplayLast => "replay_last",
+            Action::ReplayShowBot => "replay_show_bot",
+            Action::ReplayPreviousHand => "replay_previous_hand",
+            Action::ReplayNextHand => "replay_next_hand",
+            Action::Stats => "stats",
+            Action::Export => "export",
+            Action::DailyDeal => "daily_deal",
+            Action::Puzzles => "puzzles",
+            Action::BotDifficulty => "bot_difficulty",
+            Action::Settings => "settings",
+            Action::Rules => "rules",
+            Action::Quit => "quit",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Action::ALL
+            .into_iter()
+            .find(|action| action.name().eq_ignore_ascii_case(name.trim()))
+    }
+
+    /// Short description used in the on-screen controls.
+    pub fn label(self) -> &'static str {
+        match self {
+            Action::DrawStock => "stock",
+            Action::DrawDiscard => "discard",
+            Action::MoveLeft => "move left",
+            Action::MoveRight => "move right",
+            Action::MoveUp => "move up",
+            Action::MoveDown => "move down",
+            Action::Discard => "discard",
+            Action::ToggleKnock => "toggle knock",
+            Action::Hint => "hint",
+            Action::NextRound => "next round",
+            Action::BotReasoning => "bot reasoning",
+            Action::Replay => "replay",
+            Action::ReplayBack => "back",
+            Action::ReplayForward => "forward",
+            Action::ReplayFirst => "first move",
+            Action::ReplayLast => "last move",
+            Action::ReplayShowBot => "show bot hand",
+            Action::ReplayPreviousHand => "previous hand",
+            Action::ReplayNextHand => "next hand",
+            Action::Stats => "stats",
+            Action::Export => "export",
+            Action::DailyDeal => "daily deal",
+            Action::Puzzles => "puzzles",
+            Action::BotDifficulty => "bot difficulty",
+            Action::Settings => "settings",
+            Action::Rules => "rules",
+            Action::Quit => "quit",
+        }
+    }
+
+    /// Longer description used in the rules overlay.
+    pub fn description(self) -> &'static str {
+        match self {
+            Action::DrawStock => "Draw from the stock",
+            Action::DrawDiscard => "Take the top discard",
+            Action::MoveLeft => "Select the card to the left",
+            Action::MoveRight => "Select the card to the right",
+            Action::MoveUp => "Select the line above in a menu",
+            Action::MoveDown => "Select the line below in a menu",
+            Action::Discard => "Discard the selected card",
+            Action::ToggleKnock => "Knock with this discard (toggle)",
+            Action::Hint => "Ask for a hint",
+            Action::NextRound => "Start the next round",
+            Action::BotReasoning => "Show the bot's reasoning for the hand",
+            Action::Replay => "Replay the hand (or close the replay)",
+            Action::ReplayBack => "Step back one move",
+            Action::ReplayForward => "Step forward one move",
+            Action::ReplayFirst => "Jump to the deal",
+            Action::ReplayLast => "Jump to the last move",
+            Action::ReplayShowBot => "Show or hide the bot's hand",
+            Action::ReplayPreviousHand => "Replay the previous archived hand",
+            Action::ReplayNextHand => "Replay the next archived hand",
+            Action::Stats => "Show statistics",
+            Action::Export => "Export the hand",
+            Action::DailyDeal => "Open the daily deal",
+            Action::Puzzles => "Open the puzzles",
+            Action::BotDifficulty => "Choose the bot difficulty",
+            Action::Settings => "Open the settings",
+            Action::Rules => "Show these rules",
+            Action::Quit => "Quit",
+        }
+    }
+
+    /// The keys an action has when the config does not set any.
+    pub fn default_keys(self) -> &'static [&'static str] {
+        match self {
+            Action::DrawStock => &["s"],
+            Action::DrawDiscard => &["d"],
+            Action::MoveLeft => &["left", "h"],
+            Action::MoveRight => &["right", "l"],
+            Action::MoveUp => &["up"],
+            Action::MoveDown => &["down"],
+            Action::Discard => &["enter", "space"],
+            Action::ToggleKnock => &["k"],
+            Action::Hint => &["t"],
+            Action::NextRound => &["enter", "n"],
+            Action::BotReasoning => &["w"],
+            Action::Replay => &["r"],
+            Action::ReplayBack => &["left", "h"],
+            Action::ReplayForward => &["right", "l", "space"],
+            Action::ReplayFirst => &["home", "g"],
+            Action::ReplayLast => &["end"],
+            Action::ReplayShowBot => &["f"],
+            Action::ReplayPreviousHand => &["["],
+            Action::ReplayNextHand => &["]"],
+            Action::Stats => &["i"],
+            Action::Export => &["x"],
+            Action::DailyDeal => &["d"],
+            Action::Puzzles => &["p"],
+            Action::BotDifficulty => &["b"],
+            Action::Settings => &["o"],
+            Action::Rules => &["?"],
+            Action::Quit => &["q", "esc"],
+        }
+    }
+
+    /// The phases in which the action's keys are listened for. Moving and
+    /// discarding also drive the menus: left and right step through the
+    /// bot's turns or change a setting, and the discard keys confirm.
+    pub fn phases(self) -> &'static [Phase] {
+        match self {
+            Action::DrawStock | Action::DrawDiscard => &[Phase::Draw],
+            Action::MoveLeft | Action::MoveRight => &[
+                Phase::Discard,
+                Phase::Settings,
+                Phase::DifficultyPicker,
+                Phase::BotReasoning,
+            ],
+            Action::MoveUp | Action::MoveDown => &[Phase::Settings, Phase::DifficultyPicker],
+            Action::Discard => &[Phase::Discard, Phase::Settings, Phase::DifficultyPicker],
+            Action::ToggleKnock => &[Phase::Discard],
+            Action::Hint => &[Phase::Draw, Phase::Discard],
+            Action::NextRound => &[Phase::RoundOver, Phase::DailyDeal, Phase::PuzzleVerdict],
+            Action::Replay => &[Phase::RoundOver, Phase::Replay, Phase::PuzzleVerdict],
+            Action::ReplayBack
+            | Action::ReplayForward
+            | Action::ReplayFirst
+            | Action::ReplayLast
+            | Action::ReplayShowBot
+            | Action::ReplayPreviousHand
+            | Action::ReplayNextHand => &[Phase::Replay],
+            Action::Stats | Action::Export => &[Phase::RoundOver],
+            Action::BotReasoning => &[Phase::RoundOver, Phase::BotReasoning],
+            Action::DailyDeal => &[Phase::RoundOver, Phase::DailyDeal],
+            Action::Puzzles => &[Phase::RoundOver, Phase::PuzzleVerdict],
+            Action::BotDifficulty => &[Phase::RoundOver, Phase::DifficultyPicker],
+            Action::Settings => &[
+                Phase::Draw,
+                Phase::Discard,
+                Phase::RoundOver,
+                Phase::Settings,
+            ],
+            Action::Rules | Action::Quit => &[
+                Phase::Draw,
+                Phase::Discard,
+                Phase::RoundOver,
+                Phase::Replay,
+                Phase::PuzzleVerdict,
+            ],
+        }
+    }
+}
+
+/// A key plus the modifiers that must be held with it, e.g. `ctrl+s`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyChord {
+    code: KeyCode,
+    modifiers: KeyModifiers,
+}
+
+impl KeyChord {
+    /// Letters match in either case, and Shift is ignored for characters so
+    /// that keys such as `?` work on any layout.
+    pub fn matches(&self, event: &KeyEvent) -> bool {
+        let relevant = match event.code {
+            KeyCode::Char(_) => KeyModifiers::CONTROL | KeyModifiers::ALT,
+            _ => KeyModifiers::CONTROL | KeyModifiers::ALT | KeyModifiers::SHIFT,
+        };
+        if event.modifiers & relevant != self.modifiers & relevant {
+            return false;
+        }
+        match (self.code, event.code) {
+            (KeyCode::Char(bound), KeyCode::Char(pressed)) => bound.eq_ignore_ascii_case(&pressed),
+            (bound, pressed) => bound == pressed,
+        }
+    }
+}
+
+impl FromStr for KeyChord {
+    type Err = Error;
+
+    /// Parses `s`, `?`, `enter`, `left`, `f5`, `ctrl+s`, `alt+shift+tab`, ...
+    fn from_str(text: &str) -> Result<Self> {
+        let text = text.trim();
+        let (prefix, key) = match text.rsplit_once('+') {
+            // A trailing `+` is the plus key itself: `+` or `ctrl++`.
+            Some((prefix, "")) if !prefix.is_empty() => (prefix.strip_suffix('+'), "+"),
+            Some((prefix, key)) if !prefix.is_empty() => (Some(prefix), key),
+            _ => (None, text),
+        };
+        let mut modifiers = KeyModifiers::NONE;
+        for modifier in prefix.into_iter().flat_map(|prefix| prefix.split('+')) {
+            modifiers |= match modifier.trim().to_ascii_lowercase().as_str() {
+                "ctrl" | "control" => KeyModifiers::CONTROL,
+                "alt" | "meta" | "option" => KeyModifiers::ALT,
+                "shift" => KeyModifiers::SHIFT,
+                other => bail!("unknown modifier '{other}' in '{text}' (ctrl, alt or shift)"),
+            };
+        }
+        let mut chars = key.chars();
+        let code = match (chars.next(), chars.next()) {
+            (None, _) => bail!("missing key in '{text}'"),
+            (Some(c), None) => KeyCode::Char(c.to_ascii_lowercase()),
+            _ => match key.to_ascii_lowercase().as_str() {
+                "left" => KeyCode::Left,
+                "right" => KeyCode::Right,
+                "up" => KeyCode::Up,
+                "down" => KeyCode::Down,
+                "enter" | "return" => KeyCode::Enter,
+                "esc" | "escape" => KeyCode::Esc,
+                "space" => KeyCode::Char(' '),
+                "tab" => KeyCode::Tab,
+                "backspace" => KeyCode::Backspace,
+                "delete" | "del" => KeyCode::Delete,
+                "insert" | "ins" => KeyCode::Insert,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                "pageup" => KeyCode::PageUp,
+                "pagedown" => KeyCode::PageDown,
+                name => match name.strip_prefix('f').and_then(|n| n.parse::<u8>().ok()) {
+                    Some(number @ 1..=12) => KeyCode::F(number),
+                    _ => bail!("unknown key '{key}' in '{text}'"),
+                },
+            },
+        };
+        if matches!(code, KeyCode::Char(_)) {
+            // Shift only changes which character arrives, so it is never required.
+            modifiers.remove(KeyModifiers::SHIFT);
+        }
+        Ok(Self { code, modifiers })
+    }
+}
+
+impl Display for KeyChord {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            write!(f, "Ctrl+")?;
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            write!(f, "Alt+")?;
+        }
+        if self.modifiers.contains(KeyModifiers::SHIFT) {
+            write!(f, "Shift+")?;
+        }
+        match self.code {
+            KeyCode::Char(' ') => write!(f, "Space"),
+            KeyCode::Char(c) => write!(f, "{}", c.to_ascii_uppercase()),
+            KeyCode::Left => write!(f, "←"),
+            KeyCode::Right => write!(f, "→"),
+            KeyCode::Up => write!(f, "↑"),
+            KeyCode::Down => write!(f, "↓"),
+            KeyCode::PageUp => write!(f, "PgUp"),
+            KeyCode::PageDown => write!(f, "PgDn"),
+            KeyCode::F(number) => write!(f, "F{number}"),
+            other => write!(f, "{other:?}"),
+        }
+    }
+}
+
+/// The keys bound to every action.
+#[derive(Debug, Clone)]
+pub struct KeyBindings {
+    keys: BTreeMap<Action, Vec<KeyChord>>,
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        let keys = Action::ALL
+            .into_iter()
+            .map(|action| {
+                let chords = action
+                    .default_keys()
+                    .iter()
+                    .map(|key| key.parse().expect("default key bindings parse"))
+                    .collect();
+                (action, chords)
+            })
+            .collect();
+        Self { keys }
+    }
+}
+
+impl KeyBindings {
+    /// Applies the `[keys]` table over the defaults. Entries that do not
+    /// parse, or that would share a key with another action in the same
+    /// phase, keep their default keys and add a warning.
+    pub fn from_config(table: &BTreeMap<String, Vec<String>>, warnings: &mut Vec<String>) -> Self {
+        let defaults = Self::default();
+        let mut bindings = defaults.clone();
+        let mut custom = Vec::new();
+        for (name, keys) in table {
+            // Unknown names are reported with the other unknown config keys.
+            let Some(action) = Action::from_name(name) else {
+                continue;
+            };
+            match parse_keys(keys) {
+                Ok(chords) => {
+                    bindings.keys.insert(action, chords);
+                    custom.push(action);
+                }
+                Err(err) => warnings.push(format!(
+                    "Invalid keys.{}: {err}. Keeping {}.",
+                    action.name(),
+                    defaults.label(action)
+                )),
+            }
+        }
+
+        // Undo one custom binding per clash until none are left; swapping two
+        // actions' keys is fine because both changes are applied first.
+        while let Some((action, chord, reason)) = bindings.first_conflict(&custom) {
+            custom.retain(|&entry| entry != action);
+            bindings.keys.insert(action, defaults.keys[&action].clone());
+            warnings.push(format!(
+                "keys.{}: {chord} {reason}. Keeping {}.",
+                action.name(),
+                defaults.label(action)
+            ));
+        }
+        bindings
+    }
+
+    /// The first custom binding that shares a key with another action in a
+    /// phase where both are available, as (custom action, key, why it clashes).
+    fn first_conflict(&self, custom: &[Action]) -> Option<(Action, KeyChord, String)> {
+        for &action in custom.iter().rev() {
+            for other in Action::ALL {
+                if other == action {
+                    continue;
+                }
+                let Some(phase) = action
+                    .phases()
+                    .iter()
+                    .find(|phase| other.phases().contains(phase))
+                else {
+                    continue;
+                };
+                if let Some(chord) = self.keys[&action]
+                    .iter()
+                    .find(|chord| self.keys[&other].contains(chord))
+                {
+                    let reason =
+                        format!("is already bound to {} in {}", other.name(), phase.label());
+                    return Some((action, *chord, reason));
+                }
+            }
+        }
+        None
+    }
+
+    /// Whether `event` is one of the keys bound to `action`.
+    pub fn pressed(&self, action: Action, event: &KeyEvent) -> bool {
+        self.keys[&action].iter().any(|chord| chord.matches(event))
+    }
+
+    /// Every key bound to `action`, e.g. `Q/Esc`.
+    pub fn label(&self, action: Action) -> String {
+        self.keys[&action]
+            .iter()
+            .map(KeyChord::to_string)
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+
+    /// The first key bound to `action`, as shown in the controls line.
+    pub fn primary(&self, action: Action) -> String {
+        self.keys[&action]
+            .first()
+            .map(KeyChord::to_string)
+            .unwrap_or_default()
+    }
+
+    /// A controls line such as `S=stock, D=discard, ?=rules`, using each
+    /// action's first key. Moving left and right share one entry.
+    pub fn controls(&self, actions: &[Action]) -> String {
+        let mut parts = Vec::new();
+        for &action in actions {
+            match action {
+                Action::MoveLeft if actions.contains(&Action::MoveRight) => parts.push(format!(
+                    "{}/{} move",
+                    self.primary(Action::MoveLeft),
+                    self.primary(Action::MoveRight)
+                )),
+                Action::MoveRight if actions.contains(&Action::MoveLeft) => {}
+                _ => parts.push(format!("{}={}", self.primary(action), action.label())),
+            }
+        }
+        parts.join(", ")
+    }
+}
+
+fn parse_keys(keys: &[String]) -> Result<Vec<KeyChord>> {
+    if keys.is_empty() {
+        return Err(anyhow!("at least one key is needed"));
+    }
+    let mut chords: Vec<KeyChord> = Vec::new();
+    for key in keys {
+        let chord = key.parse()?;
+        if !chords.contains(&chord) {
+            chords.push(chord);
+        }
+    }
+    Ok(chords)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bind(entries: &[(&str, &[&str])]) -> (KeyBindings, Vec<String>) {
+        let table = entries
+            .iter()
+            .map(|(name, keys)| {
+                let keys = keys.iter().map(|key| key.to_string()).collect();
+                (name.to_string(), keys)
+            })
+            .collect();
+        let mut warnings = Vec::new();
+        let bindings = KeyBindings::from_config(&table, &mut warnings);
+        (bindings, warnings)
+    }
+
+    #[test]
+    fn default_bindings_have_no_conflicts() {
+        assert_eq!(KeyBindings::default().first_conflict(&Action::ALL), None);
+        let (_, warnings) = bind(&[]);
+        assert!(warnings.is_empty(), "{warnings:?}");
+    }
+
+    #[test]
+    fn a_key_shared_within_a_phase_reverts_the_custom_binding() {
+        let (bindings, warnings) = bind(&[("hint", &["s"])]);
+        assert_eq!(bindings.label(Action::Hint), "T");
+        assert_eq!(bindings.label(Action::DrawStock), "S");
+        assert_eq!(
+            warnings,
+            ["keys.hint: S is already bound to draw_stock in the draw phase. Keeping T."]
+        );
+    }
+
+    #[test]
+    fn keys_may_be_shared_by_actions_never_available_together() {
+        // Drawing and the round summary never overlap, nor do discarding and replays.
+        let (bindings, warnings) = bind(&[("stats", &["s"]), ("replay_show_bot", &["k"])]);
+        assert!(warnings.is_empty(), "{warnings:?}");
+        assert_eq!(bindings.label(Action::Stats), "S");
+        assert_eq!(bindings.label(Action::ReplayShowBot), "K");
+    }
+
+    #[test]
+    fn two_actions_may_swap_keys() {
+        let (bindings, warnings) = bind(&[("draw_stock", &["d"]), ("draw_discard", &["s"])]);
+        assert!(warnings.is_empty(), "{warnings:?}");
+        assert_eq!(bindings.label(Action::DrawStock), "D");
+        assert_eq!(bindings.label(Action::DrawDiscard), "S");
+    }
+
+    #[test]
+    fn reverting_one_clash_reports_any_clash_it_uncovers() {
+        // Hint gives up D and takes back T, which draw_stock was bound to.
+        let (bindings, warnings) = bind(&[("draw_stock", &["t"]), ("hint", &["d"])]);
+        assert_eq!(bindings.label(Action::DrawStock), "S");
+        assert_eq!(bindings.label(Action::Hint), "T");
+        assert_eq!(
+            warnings,
+            [
+                "keys.hint: D is already bound to draw_discard in the draw phase. Keeping T.",
+                "keys.draw_stock: T is already bound to hint in the draw phase. Keeping S.",
+            ]
+        );
+    }
+
+    #[test]
+    fn overlays_check_the_actions_they_listen_for() {
+        let (bindings, warnings) = bind(&[
+            ("settings", &["up"]),
+            ("bot_reasoning", &["l"]),
+            ("bot_difficulty", &["space"]),
+            ("daily_deal", &["n"]),
+        ]);
+        assert_eq!(bindings.label(Action::Settings), "O");
+        assert_eq!(bindings.label(Action::DailyDeal), "D");
+        assert_eq!(
+            warnings,
+            [
+                "keys.settings: ↑ is already bound to move_up in the settings screen. Keeping O.",
+                "keys.daily_deal: N is already bound to next_round in the round summary. Keeping D.",
+                "keys.bot_reasoning: L is already bound to move_right in the bot's reasoning. Keeping W.",
+                "keys.bot_difficulty: Space is already bound to discard in the difficulty picker. Keeping B.",
+            ]
+        );
+    }
+
+    #[test]
+    fn menu_keys_stay_free_for_actions_not_used_in_the_menu() {
+        let (bindings, warnings) = bind(&[
+            ("draw_stock", &["up"]),
+            ("replay_first", &["enter"]),
+            ("move_up", &["k"]),
+        ]);
+        assert!(warnings.is_empty(), "{warnings:?}");
+        assert_eq!(bindings.label(Action::DrawStock), "↑");
+        assert_eq!(bindings.label(Action::ReplayFirst), "Enter");
+        assert_eq!(bindings.label(Action::MoveUp), "K");
+    }
+
+    #[test]
+    fn unreadable_keys_keep_the_defaults() {
+        let (bindings, warnings) = bind(&[("hint", &["hyper+t"]), ("rules", &[])]);
+        assert_eq!(bindings.label(Action::Hint), "T");
+        assert_eq!(bindings.label(Action::Rules), "?");
+        assert_eq!(
+            warnings,
+            [
+                "Invalid keys.hint: unknown modifier 'hyper' in 'hyper+t' (ctrl, alt or shift). Keeping T.",
+                "Invalid keys.rules: at least one key is needed. Keeping ?.",
+            ]
+        );
+    }
+}
@@ -0,0 +1,21 @@
+pub mod app;
+pub mod archive;
+pub mod bot;
+pub mod cards;
+pub mod cli;
+pub mod config;
+pub mod daily;
+pub mod engine;
+pub mod game;
+pub mod keys;
+pub mod meld;
+pub mod notation;
+pub mod params;
+pub mod profiles;
+pub mod puzzle;
+pub mod replay;
+pub mod settings;
+pub mod sim;
+pub mod stats;
+pub mod storage;
+pub mod ui;
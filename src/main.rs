@@ -1,3 +1,5 @@
+use std::{path::Path, time::Duration};
+
 use anyhow::Result;
 use crossterm::{
     event::{self, Event, KeyEventKind},
@@ -6,25 +8,46 @@ use crossterm::{
 };
 use ratatui::{backend::CrosstermBackend, Terminal};
 
-mod app;
-mod bot;
-mod cards;
-mod config;
-mod game;
-mod meld;
-mod storage;
-mod ui;
+use deadwood::{
+    app::{App, LaunchOptions},
+    cli::{self, Cli, Command, USAGE},
+    profiles::{PickerOutcome, ProfilePicker},
+    storage::{Paths, DEFAULT_PROFILE},
+    ui,
+};
 
-use app::App;
+/// How often the loop wakes up without input to advance timed events.
+const TICK_RATE: Duration = Duration::from_millis(50);
 
 fn main() -> Result<()> {
+    let Cli { options, command } = cli::parse(std::env::args().skip(1))?;
+    match command {
+        Command::Play => play(options),
+        Command::Stats => cli::run_stats(&options),
+        Command::Export { hand, out } => cli::run_export(&options, hand, out.as_deref()),
+        Command::Analyze { cards, json } => cli::run_analyze(&cards, json),
+        Command::Sim { args } => cli::run_sim(args),
+        Command::Config { check: false } => cli::run_config(&options),
+        Command::Config { check: true } => cli::run_config_check(&options),
+        Command::Help => {
+            println!("{USAGE}");
+            Ok(())
+        }
+        Command::Version => {
+            println!("deadwood {}", env!("CARGO_PKG_VERSION"));
+            Ok(())
+        }
+    }
+}
+
+fn play(options: LaunchOptions) -> Result<()> {
     enable_raw_mode()?;
     let mut stdout = std::io::stdout();
     execute!(stdout, EnterAlternateScreen)?;
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
-    let result = run(&mut terminal);
+    let result = run(&mut terminal, options);
 
     disable_raw_mode()?;
     execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
@@ -33,8 +56,18 @@ fn main() -> Result<()> {
     result
 }
 
-fn run<B: ratatui::backend::Backend>(terminal: &mut Terminal<B>) -> Result<()> {
-    let mut app = App::new()?;
+fn run<B: ratatui::backend::Backend>(
+    terminal: &mut Terminal<B>,
+    mut options: LaunchOptions,
+) -> Result<()> {
+    if options.profile.is_none() {
+        match pick_profile(terminal, options.data_dir.as_deref())? {
+            Some(profile) => options.profile = Some(profile),
+            None => return Ok(()),
+        }
+    }
+
+    let mut app = App::new(options)?;
     loop {
         app.update()?;
 
@@ -44,6 +77,9 @@ fn run<B: ratatui::backend::Backend>(terminal: &mut Terminal<B>) -> Result<()> {
 
         terminal.draw(|frame| ui::draw(frame, &app))?;
 
+        if !event::poll(TICK_RATE)? {
+            continue;
+        }
         if let Event::Key(key) = event::read()? {
             if key.kind == KeyEventKind::Press {
                 app.handle_key(key)?;
@@ -56,3 +92,34 @@ fn run<B: ratatui::backend::Backend>(terminal: &mut Terminal<B>) -> Result<()> {
 
     Ok(())
 }
+
+/// Shows the profile picker until a profile is chosen (or created), or the
+/// player quits. With at most one profile there is nothing to pick, so that
+/// profile (or the default one) is used straight away.
+fn pick_profile<B: ratatui::backend::Backend>(
+    terminal: &mut Terminal<B>,
+    data_dir: Option<&Path>,
+) -> Result<Option<String>> {
+    let (mut profiles, last) = Paths::profiles(data_dir)?;
+    if profiles.len() <= 1 {
+        return Ok(Some(
+            profiles
+                .pop()
+                .unwrap_or_else(|| DEFAULT_PROFILE.to_string()),
+        ));
+    }
+    let mut picker = ProfilePicker::new(profiles, last.as_deref());
+    loop {
+        terminal.draw(|frame| ui::draw_profile_picker(frame, &picker))?;
+        if let Event::Key(key) = event::read()? {
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            match picker.handle_key(key) {
+                PickerOutcome::Pending => {}
+                PickerOutcome::Chosen(profile) => return Ok(Some(profile)),
+                PickerOutcome::Quit => return Ok(None),
+            }
+        }
+    }
+}
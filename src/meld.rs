@@ -57,7 +57,7 @@ pub struct MeldAnalysis {
 
 impl MeldAnalysis {
     fn new(melds: Vec<Meld>, deadwood: Vec<Card>) -> Self {
-        let deadwood_value = deadwood.iter().map(|c| c.rank.value() as u32).sum();
+        let deadwood_value = deadwood_value(&deadwood);
         Self {
             melds,
             deadwood,
@@ -69,12 +69,49 @@ impl MeldAnalysis {
 pub fn analyze_hand(cards: &[Card]) -> MeldAnalysis {
     let mut sorted = cards.to_vec();
     sorted.sort();
-    let candidates = generate_candidates(&sorted);
     let mut best = MeldAnalysis::new(vec![], sorted.clone());
-    search_candidates(&sorted, &candidates, &mut vec![], &mut vec![], &mut best);
+    for_each_arrangement(&sorted, |melds, deadwood| {
+        let value = deadwood_value(deadwood);
+        if value < best.deadwood_value
+            || (value == best.deadwood_value && melds.len() > best.melds.len())
+        {
+            best = MeldAnalysis::new(melds.to_vec(), deadwood.to_vec());
+        }
+    });
+    best
+}
+
+/// Every way of melding `cards` that reaches the lowest deadwood. The
+/// arrangement [`analyze_hand`] picks comes first, then the others with the
+/// most melds first.
+pub fn best_arrangements(cards: &[Card]) -> Vec<MeldAnalysis> {
+    let mut sorted = cards.to_vec();
+    sorted.sort();
+    let mut best: Vec<MeldAnalysis> = vec![];
+    for_each_arrangement(&sorted, |melds, deadwood| {
+        let value = deadwood_value(deadwood);
+        match best.first().map(|analysis| analysis.deadwood_value) {
+            Some(lowest) if value > lowest => {}
+            Some(lowest) if value == lowest => {
+                best.push(MeldAnalysis::new(melds.to_vec(), deadwood.to_vec()))
+            }
+            _ => best = vec![MeldAnalysis::new(melds.to_vec(), deadwood.to_vec())],
+        }
+    });
+    best.sort_by_key(|analysis| std::cmp::Reverse(analysis.melds.len()));
     best
 }
 
+fn deadwood_value(cards: &[Card]) -> u32 {
+    cards.iter().map(|c| c.rank.value() as u32).sum()
+}
+
+/// Calls `visit` with the melds and deadwood of every arrangement of `sorted`.
+fn for_each_arrangement(sorted: &[Card], mut visit: impl FnMut(&[Meld], &[Card])) {
+    let candidates = generate_candidates(sorted);
+    search_candidates(sorted, &candidates, &mut vec![], &mut vec![], &mut visit);
+}
+
 fn generate_candidates(cards: &[Card]) -> Vec<Meld> {
     let mut candidates = Vec::new();
     candidates.extend(generate_sets(cards));
@@ -88,8 +125,8 @@ fn generate_sets(cards: &[Card]) -> Vec<Meld> {
         .iter()
         .cloned()
         .into_group_map_by(|card| card.rank)
-        .into_iter()
-        .flat_map(|(_, group)| {
+        .into_values()
+        .flat_map(|group| {
             (3..=group.len()).flat_map(move |size| {
                 group
                     .iter()
@@ -157,16 +194,10 @@ fn search_candidates(
     candidates: &[Meld],
     current_melds: &mut Vec<Meld>,
     deadwood: &mut Vec<Card>,
-    best: &mut MeldAnalysis,
+    visit: &mut impl FnMut(&[Meld], &[Card]),
 ) {
     if remaining.is_empty() {
-        let analysis = MeldAnalysis::new(current_melds.clone(), deadwood.clone());
-        if analysis.deadwood_value < best.deadwood_value
-            || (analysis.deadwood_value == best.deadwood_value
-                && analysis.melds.len() > best.melds.len())
-        {
-            *best = analysis;
-        }
+        visit(current_melds, deadwood);
         return;
     }
 
@@ -174,7 +205,7 @@ fn search_candidates(
     let rest = &remaining[1..];
 
     deadwood.push(card);
-    search_candidates(rest, candidates, current_melds, deadwood, best);
+    search_candidates(rest, candidates, current_melds, deadwood, visit);
     deadwood.pop();
 
     for meld in candidates.iter().filter(|m| m.contains(card)) {
@@ -192,7 +223,7 @@ fn search_candidates(
                 .cloned()
                 .collect();
             reduced.sort();
-            search_candidates(&reduced, candidates, current_melds, deadwood, best);
+            search_candidates(&reduced, candidates, current_melds, deadwood, visit);
             current_melds.pop();
         }
     }
@@ -217,3 +248,61 @@ pub fn layoff_cards(deadwood: &[Card], knocker_melds: &[Meld]) -> (Vec<Card>, Ve
 
     (remaining, laid_off)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cards(text: &str) -> Vec<Card> {
+        let mut cards: Vec<Card> = text
+            .split_whitespace()
+            .map(|card| card.parse().unwrap())
+            .collect();
+        cards.sort();
+        cards
+    }
+
+    #[test]
+    fn tied_arrangements_are_all_listed() {
+        // The KH can finish the kings or the heart run; either way 20 is left over.
+        let hand = cards("JH QH KH KS KD 2C 3C 4C");
+        let arrangements = best_arrangements(&hand);
+        assert_eq!(arrangements.len(), 2);
+        let picked = analyze_hand(&hand);
+        assert_eq!(arrangements[0].melds, picked.melds);
+        assert_eq!(arrangements[0].deadwood, picked.deadwood);
+
+        let mut leftovers = Vec::new();
+        for arrangement in &arrangements {
+            assert_eq!(arrangement.deadwood_value, 20);
+            assert_eq!(arrangement.melds.len(), 2);
+            leftovers.push(arrangement.deadwood.clone());
+        }
+        leftovers.sort();
+        assert_eq!(leftovers, [cards("JH QH"), cards("KS KD")]);
+    }
+
+    #[test]
+    fn ties_with_more_melds_come_first() {
+        // A six-card run melds as one run or as two runs of three.
+        let hand = cards("3H 4H 5H 6H 7H 8H KS");
+        let arrangements = best_arrangements(&hand);
+        let meld_counts: Vec<usize> = arrangements
+            .iter()
+            .map(|arrangement| arrangement.melds.len())
+            .collect();
+        assert_eq!(meld_counts, [2, 1]);
+        for arrangement in &arrangements {
+            assert_eq!(arrangement.deadwood, cards("KS"));
+        }
+    }
+
+    #[test]
+    fn a_single_best_arrangement_stands_alone() {
+        let hand = cards("7H 8H 9H 7S 7D 7C");
+        let arrangements = best_arrangements(&hand);
+        assert_eq!(arrangements.len(), 1);
+        assert_eq!(arrangements[0].deadwood_value, 0);
+        assert_eq!(arrangements[0].melds.len(), 2);
+    }
+}
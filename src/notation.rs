@@ -0,0 +1,612 @@
+//! Portable text notation for a complete hand, in the spirit of PGN.
+//!
+//! A hand is a block of `[Tag "value"]` header lines followed by the move
+//! list:
+//!
+//! ```text
+//! [Event "Deadwood hand"]
+//! [Seed "6311119817046432122"]
+//! [Timestamp "1792333264"]
+//! [Human "alice"]
+//! [Bot "heuristic"]
+//! [Difficulty "challenging"]
+//! [Rules "standard"]
+//! [Dealer "bot"]
+//! [HumanDeal "AD AS 2C 3C 3H 4H 6S 9D 10H QD"]
+//! [BotDeal "2D 4C 4D 4S 7C 7H 8D 8H 9H JS"]
+//! [Upcard "6D"]
+//! [Result "human 23"]
+//! [Termination "knock"]
+//!
+//! 1. H d6D QD
+//! 2. B s5S JS
+//! 3. H s2H 10H#
+//! ```
+//!
+//! Each numbered turn names the player (`H` or `B`), the draw (`s` from the
+//! stock or `d` from the discard pile, followed by the card) and the card
+//! discarded, with `#` marking a knock. Cards use their `Display` labels.
+//! `Result` is `human N`, `bot N` or `draw`; `Termination` is one of `knock`,
+//! `gin`, `undercut`, `big-gin` or `stock`. Unknown tags are kept, `{...}`
+//! comments are ignored, and tag names are case-insensitive.
+
+use std::{fmt::Write as _, fs, path::Path};
+
+use anyhow::{anyhow, bail, Context, Result};
+
+use crate::{
+    archive::{DealRecord, HandAnalysis, HandRecord, MoveRecord, OutcomeKind, OutcomeRecord},
+    bot::BotDifficulty,
+    cards::Card,
+    game::{score_knock, DrawSource, PlayerId},
+    meld::analyze_hand,
+};
+
+/// The only rule set Deadwood plays today.
+pub const STANDARD_RULES: &str = "standard";
+
+/// How a hand finished, as written in the `Termination` tag.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Termination {
+    Knock,
+    Gin,
+    Undercut,
+    BigGin,
+    Stock,
+}
+
+impl Termination {
+    pub fn name(self) -> &'static str {
+        match self {
+            Termination::Knock => "knock",
+            Termination::Gin => "gin",
+            Termination::Undercut => "undercut",
+            Termination::BigGin => "big-gin",
+            Termination::Stock => "stock",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "knock" => Some(Termination::Knock),
+            "gin" => Some(Termination::Gin),
+            "undercut" => Some(Termination::Undercut),
+            "big-gin" | "big gin" => Some(Termination::BigGin),
+            "stock" => Some(Termination::Stock),
+            _ => None,
+        }
+    }
+}
+
+/// A hand as written in the notation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NotatedHand {
+    pub seed: Option<u64>,
+    pub timestamp: Option<u64>,
+    pub human: String,
+    pub bot: String,
+    pub difficulty: Option<BotDifficulty>,
+    pub rules: String,
+    pub deal: DealRecord,
+    pub moves: Vec<MoveRecord>,
+    pub winner: Option<PlayerId>,
+    pub points: i32,
+    pub termination: Termination,
+    /// Tags this version does not understand, in file order.
+    pub extra_tags: Vec<(String, String)>,
+}
+
+impl NotatedHand {
+    /// Notation for an archived hand, naming the human player `human`.
+    pub fn from_record(record: &HandRecord, human: &str) -> Self {
+        let outcome = &record.outcome;
+        let termination = match outcome.kind {
+            OutcomeKind::StockDepleted => Termination::Stock,
+            OutcomeKind::BigGin => Termination::BigGin,
+            OutcomeKind::Knock if outcome.gin => Termination::Gin,
+            OutcomeKind::Knock if outcome.undercut => Termination::Undercut,
+            OutcomeKind::Knock => Termination::Knock,
+        };
+        Self {
+            seed: Some(record.seed),
+            timestamp: Some(record.timestamp),
+            human: human.to_string(),
+            bot: record.bot.clone(),
+            difficulty: record.difficulty,
+            rules: record.rules.clone(),
+            deal: record.deal.clone(),
+            moves: record.moves.clone(),
+            winner: outcome.winner,
+            points: outcome.points,
+            termination,
+            extra_tags: Vec::new(),
+        }
+    }
+
+    /// Rebuilds the archive record by replaying the moves over the deal and
+    /// rescoring the final hands.
+    pub fn to_record(&self) -> Result<HandRecord> {
+        let mut human = self.deal.human.clone();
+        let mut bot = self.deal.bot.clone();
+        for (index, mv) in self.moves.iter().enumerate() {
+            let (player, card, drawn) = match *mv {
+                MoveRecord::Draw { player, card, .. } => (player, card, true),
+                MoveRecord::Discard { player, card, .. } => (player, card, false),
+            };
+            let hand = match player {
+                PlayerId::Human => &mut human,
+                PlayerId::Bot => &mut bot,
+            };
+            if drawn {
+                hand.push(card);
+            } else {
+                let position = hand.iter().position(|&held| held == card).ok_or_else(|| {
+                    anyhow!("move {}: {card} is not in the discarding hand", index + 1)
+                })?;
+                hand.remove(position);
+            }
+        }
+
+        let mut outcome = OutcomeRecord {
+            kind: OutcomeKind::StockDepleted,
+            winner: self.winner,
+            points: self.points,
+            knocker: None,
+            knocker_deadwood: 0,
+            opponent_deadwood: 0,
+            gin: false,
+            undercut: false,
+            laid_off: Vec::new(),
+        };
+        match self.termination {
+            Termination::Stock => {}
+            Termination::BigGin => {
+                let player = self
+                    .winner
+                    .ok_or_else(|| anyhow!("a big gin needs a winner"))?;
+                let opponent = match player {
+                    PlayerId::Human => &bot,
+                    PlayerId::Bot => &human,
+                };
+                outcome.kind = OutcomeKind::BigGin;
+                outcome.knocker = Some(player);
+                outcome.opponent_deadwood = analyze_hand(opponent).deadwood_value;
+                outcome.gin = true;
+            }
+            Termination::Knock | Termination::Gin | Termination::Undercut => {
+                let knocker = self
+                    .moves
+                    .iter()
+                    .rev()
+                    .find_map(|mv| match *mv {
+                        MoveRecord::Discard {
+                            player,
+                            knock: true,
+                            ..
+                        } => Some(player),
+                        _ => None,
+                    })
+                    .ok_or_else(|| anyhow!("the moves end without a knock"))?;
+                let (knocker_hand, opponent_hand) = match knocker {
+                    PlayerId::Human => (&human, &bot),
+                    PlayerId::Bot => (&bot, &human),
+                };
+                let score = score_knock(knocker_hand, opponent_hand)
+                    .ok_or_else(|| anyhow!("the knocking hand has more than 10 deadwood"))?;
+                outcome.kind = OutcomeKind::Knock;
+                outcome.knocker = Some(knocker);
+                outcome.knocker_deadwood = score.knocker_deadwood;
+                outcome.opponent_deadwood = score.opponent_deadwood;
+                outcome.gin = score.gin;
+                outcome.undercut = score.undercut;
+                outcome.laid_off = score.laid_off;
+            }
+        }
+
+        Ok(HandRecord {
+            version: crate::archive::ARCHIVE_VERSION,
+            timestamp: self.timestamp.unwrap_or_default(),
+            seed: self.seed.unwrap_or_default(),
+            difficulty: self.difficulty,
+            bot: self.bot.clone(),
+            hints_used: 0,
+            rules: self.rules.clone(),
+            deal: self.deal.clone(),
+            moves: self.moves.clone(),
+            outcome,
+            human: HandAnalysis::of(&human),
+            bot_hand: HandAnalysis::of(&bot),
+        })
+    }
+}
+
+/// Writes `hand` in the text notation.
+pub fn write(hand: &NotatedHand) -> String {
+    let mut out = String::new();
+    let mut tag = |name: &str, value: &str| {
+        let escaped = value.replace('\\', "\\\\").replace('"', "\\\"");
+        let _ = writeln!(out, "[{name} \"{escaped}\"]");
+    };
+    tag("Event", "Deadwood hand");
+    if let Some(seed) = hand.seed {
+        tag("Seed", &seed.to_string());
+    }
+    if let Some(timestamp) = hand.timestamp {
+        tag("Timestamp", &timestamp.to_string());
+    }
+    tag("Human", &hand.human);
+    tag("Bot", &hand.bot);
+    if let Some(difficulty) = hand.difficulty {
+        tag("Difficulty", difficulty.name());
+    }
+    tag("Rules", &hand.rules);
+    tag("Dealer", player_name(hand.deal.dealer));
+    tag("HumanDeal", &card_list(&hand.deal.human));
+    tag("BotDeal", &card_list(&hand.deal.bot));
+    if let Some(upcard) = hand.deal.upcard {
+        tag("Upcard", &upcard.to_string());
+    }
+    let result = match hand.winner {
+        Some(winner) => format!("{} {}", player_name(winner), hand.points),
+        None => "draw".to_string(),
+    };
+    tag("Result", &result);
+    tag("Termination", hand.termination.name());
+    for (name, value) in &hand.extra_tags {
+        tag(name, value);
+    }
+
+    out.push('\n');
+    let mut turn = 0;
+    for mv in &hand.moves {
+        match *mv {
+            MoveRecord::Draw {
+                player,
+                source,
+                card,
+            } => {
+                turn += 1;
+                let prefix = match source {
+                    DrawSource::Stock => 's',
+                    DrawSource::Discard => 'd',
+                };
+                let _ = write!(out, "{turn}. {} {prefix}{card}", player_letter(player));
+            }
+            MoveRecord::Discard { card, knock, .. } => {
+                let _ = writeln!(out, " {card}{}", if knock { "#" } else { "" });
+            }
+        }
+    }
+    if matches!(hand.moves.last(), Some(MoveRecord::Draw { .. })) {
+        out.push('\n');
+    }
+    out
+}
+
+/// Parses a hand written in the text notation.
+pub fn parse(text: &str) -> Result<NotatedHand> {
+    let mut tags = Vec::new();
+    let mut body = String::new();
+    for (index, line) in text.lines().enumerate() {
+        let trimmed = line.trim();
+        if trimmed.starts_with('[') {
+            tags.push(parse_tag(trimmed).with_context(|| format!("line {}", index + 1))?);
+        } else {
+            body.push_str(line);
+            body.push('\n');
+        }
+    }
+
+    let mut seed = None;
+    let mut timestamp = None;
+    let mut human = String::new();
+    let mut bot = String::new();
+    let mut difficulty = None;
+    let mut rules = STANDARD_RULES.to_string();
+    let mut dealer = None;
+    let mut human_deal = None;
+    let mut bot_deal = None;
+    let mut upcard = None;
+    let mut result = None;
+    let mut termination = None;
+    let mut extra_tags = Vec::new();
+    for (name, value) in tags {
+        match name.to_ascii_lowercase().as_str() {
+            "event" => {}
+            "seed" => seed = Some(parse_number(&name, &value)?),
+            "timestamp" => timestamp = Some(parse_number(&name, &value)?),
+            "human" => human = value,
+            "bot" => bot = value,
+            "difficulty" => {
+                difficulty = Some(
+                    BotDifficulty::from_name(&value)
+                        .ok_or_else(|| anyhow!("unknown difficulty '{value}'"))?,
+                )
+            }
+            "rules" => rules = value,
+            "dealer" => dealer = Some(parse_player(&value)?),
+            "humandeal" => human_deal = Some(parse_cards(&value)?),
+            "botdeal" => bot_deal = Some(parse_cards(&value)?),
+            "upcard" => upcard = Some(parse_card(&value)?),
+            "result" => result = Some(parse_result(&value)?),
+            "termination" => {
+                termination = Some(
+                    Termination::from_name(&value)
+                        .ok_or_else(|| anyhow!("unknown termination '{value}'"))?,
+                )
+            }
+            _ => extra_tags.push((name, value)),
+        }
+    }
+
+    let (winner, points) = result.ok_or_else(|| anyhow!("missing Result tag"))?;
+    Ok(NotatedHand {
+        seed,
+        timestamp,
+        human,
+        bot,
+        difficulty,
+        rules,
+        deal: DealRecord {
+            dealer: dealer.ok_or_else(|| anyhow!("missing Dealer tag"))?,
+            human: human_deal.ok_or_else(|| anyhow!("missing HumanDeal tag"))?,
+            bot: bot_deal.ok_or_else(|| anyhow!("missing BotDeal tag"))?,
+            upcard,
+        },
+        moves: parse_moves(&body)?,
+        winner,
+        points,
+        termination: termination.ok_or_else(|| anyhow!("missing Termination tag"))?,
+        extra_tags,
+    })
+}
+
+pub fn read_file(path: &Path) -> Result<NotatedHand> {
+    let text = fs::read_to_string(path)
+        .with_context(|| format!("failed to read hand file {}", path.display()))?;
+    parse(&text).with_context(|| format!("failed to parse hand file {}", path.display()))
+}
+
+pub fn write_file(path: &Path, hand: &NotatedHand) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("failed to create directory {}", parent.display()))?;
+    }
+    fs::write(path, write(hand))
+        .with_context(|| format!("failed to write hand file {}", path.display()))
+}
+
+fn parse_tag(line: &str) -> Result<(String, String)> {
+    let inner = line
+        .strip_prefix('[')
+        .and_then(|rest| rest.strip_suffix(']'))
+        .ok_or_else(|| anyhow!("tag lines look like [Name \"value\"]"))?;
+    let (name, value) = inner
+        .split_once(char::is_whitespace)
+        .ok_or_else(|| anyhow!("tag '{inner}' has no value"))?;
+    let value = value
+        .trim()
+        .strip_prefix('"')
+        .and_then(|rest| rest.strip_suffix('"'))
+        .ok_or_else(|| anyhow!("the value of tag {name} must be quoted"))?;
+    let mut unescaped = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            unescaped.extend(chars.next());
+        } else {
+            unescaped.push(c);
+        }
+    }
+    Ok((name.to_string(), unescaped))
+}
+
+fn parse_moves(body: &str) -> Result<Vec<MoveRecord>> {
+    let mut moves = Vec::new();
+    let mut player = None;
+    let mut uncommented = String::with_capacity(body.len());
+    let mut depth = 0usize;
+    for c in body.chars() {
+        match c {
+            '{' => depth += 1,
+            '}' => depth = depth.saturating_sub(1),
+            _ if depth == 0 => uncommented.push(c),
+            _ => {}
+        }
+    }
+
+    for token in uncommented.split_whitespace() {
+        if token.ends_with('.') && token[..token.len() - 1].chars().all(|c| c.is_ascii_digit()) {
+            continue;
+        }
+        match token {
+            "H" | "h" => {
+                player = Some(PlayerId::Human);
+                continue;
+            }
+            "B" | "b" => {
+                player = Some(PlayerId::Bot);
+                continue;
+            }
+            _ => {}
+        }
+        let player = player.ok_or_else(|| anyhow!("move '{token}' comes before H or B"))?;
+        let source = match token.chars().next() {
+            Some('s') | Some('S') => Some(DrawSource::Stock),
+            Some('d') | Some('D') => Some(DrawSource::Discard),
+            _ => None,
+        };
+        if let Some(source) = source {
+            moves.push(MoveRecord::Draw {
+                player,
+                source,
+                card: parse_card(&token[1..])?,
+            });
+        } else {
+            let (label, knock) = match token.strip_suffix('#') {
+                Some(label) => (label, true),
+                None => (token, false),
+            };
+            moves.push(MoveRecord::Discard {
+                player,
+                card: parse_card(label)?,
+                knock,
+            });
+        }
+    }
+    Ok(moves)
+}
+
+fn parse_result(value: &str) -> Result<(Option<PlayerId>, i32)> {
+    let value = value.trim();
+    if value.eq_ignore_ascii_case("draw") {
+        return Ok((None, 0));
+    }
+    let (player, points) = value
+        .split_once(char::is_whitespace)
+        .ok_or_else(|| anyhow!("Result must be 'human N', 'bot N' or 'draw'"))?;
+    let points = points
+        .trim()
+        .parse()
+        .map_err(|_| anyhow!("Result points '{points}' are not a number"))?;
+    Ok((Some(parse_player(player)?), points))
+}
+
+fn parse_player(value: &str) -> Result<PlayerId> {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "human" | "h" => Ok(PlayerId::Human),
+        "bot" | "b" => Ok(PlayerId::Bot),
+        other => bail!("unknown player '{other}' (human or bot)"),
+    }
+}
+
+fn parse_number(name: &str, value: &str) -> Result<u64> {
+    value
+        .trim()
+        .parse()
+        .map_err(|_| anyhow!("{name} must be an unsigned number, not '{value}'"))
+}
+
+fn parse_cards(value: &str) -> Result<Vec<Card>> {
+    value.split_whitespace().map(parse_card).collect()
+}
+
+fn parse_card(label: &str) -> Result<Card> {
+    Ok(label.parse()?)
+}
+
+fn card_list(cards: &[Card]) -> String {
+    cards
+        .iter()
+        .map(|card| card.to_string())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn player_name(player: PlayerId) -> &'static str {
+    match player {
+        PlayerId::Human => "human",
+        PlayerId::Bot => "bot",
+    }
+}
+
+fn player_letter(player: PlayerId) -> &'static str {
+    match player {
+        PlayerId::Human => "H",
+        PlayerId::Bot => "B",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        bot::{take_turn, HeuristicStrategy, Strategy},
+        game::{ActionOutcome, Game, Rules, TurnPhase},
+    };
+
+    fn play_hand(seed: u64) -> HandRecord {
+        let mut game = Game::with_seed(seed).unwrap();
+        let mut human = HeuristicStrategy::new(BotDifficulty::Easy);
+        let mut bot = HeuristicStrategy::new(BotDifficulty::Challenging);
+        while game.phase != TurnPhase::RoundOver {
+            let seat = game.current_player;
+            let strategy: &mut dyn Strategy = match seat {
+                PlayerId::Human => &mut human,
+                PlayerId::Bot => &mut bot,
+            };
+            if take_turn(&mut game, seat, strategy).unwrap() == ActionOutcome::RoundEnded {
+                break;
+            }
+        }
+        let result = game.pending_round.clone().unwrap();
+        HandRecord::from_round(
+            &game,
+            &result,
+            Some(BotDifficulty::Challenging),
+            "heuristic",
+            0,
+            Rules::Standard,
+        )
+    }
+
+    #[test]
+    fn record_round_trips_through_text() {
+        for seed in 0..20 {
+            let record = play_hand(seed);
+            let text = write(&NotatedHand::from_record(&record, "alice"));
+            let parsed = parse(&text).unwrap();
+            assert_eq!(parsed.human, "alice");
+            assert_eq!(parsed.to_record().unwrap(), record, "seed {seed}:\n{text}");
+            assert_eq!(write(&parsed), text);
+        }
+    }
+
+    #[test]
+    fn parses_hand_written_text() {
+        let text = r#"
+[Seed "7"]
+[dealer "human"]
+[HumanDeal "AH 2H 3H 4S 5S 6S 7C 8C 9C KD"]
+[BotDeal "AS 2S 3S 4D 5D 6D 7H 8H 9H QC"]
+[Upcard "KS"]
+[Result "human 8"]
+[Termination "knock"]
+[Annotator "someone \"quoted\""]
+
+{ bot opens }
+1. B dKS QC
+2. H s10D kd#
+"#;
+        let hand = parse(text).unwrap();
+        assert_eq!(hand.seed, Some(7));
+        assert_eq!(hand.deal.dealer, PlayerId::Human);
+        assert_eq!(hand.deal.upcard, Card::from_label("KS"));
+        assert_eq!(hand.moves.len(), 4);
+        assert_eq!(
+            hand.moves[3],
+            MoveRecord::Discard {
+                player: PlayerId::Human,
+                card: Card::from_label("KD").unwrap(),
+                knock: true,
+            }
+        );
+        assert_eq!(
+            hand.extra_tags,
+            vec![("Annotator".to_string(), "someone \"quoted\"".to_string())]
+        );
+        let record = hand.to_record().unwrap();
+        assert_eq!(record.outcome.knocker, Some(PlayerId::Human));
+        assert_eq!(record.human.deadwood_value, 10);
+    }
+
+    #[test]
+    fn rejects_broken_text() {
+        assert!(parse("[Result \"human 3\"]\n1. H s7Z 3H").is_err());
+        assert!(parse("[Dealer \"bot\"]\n").is_err());
+        let hand = NotatedHand::from_record(&play_hand(3), "you");
+        let mut text = write(&hand);
+        text.push_str("99. H sQQ");
+        assert!(parse(&text).is_err());
+    }
+}
@@ -0,0 +1,322 @@
+//! Tunable numbers behind the heuristic bot, stored as TOML profiles.
+//!
+//! A profile only needs the fields it changes; anything missing falls back to
+//! the Challenging defaults.
+
+use std::{fs, path::Path};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::bot::BotDifficulty;
+
+/// How the heuristic bot decides whether to knock.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum KnockRule {
+    /// Knock under the threshold once the hand is strong or the round runs late.
+    Classic,
+    /// Knock when the expected points beat waiting another turn, judged against
+    /// sampled opponent hands.
+    RiskAware,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BotParams {
+    /// Strategy name the profile registers under.
+    pub name: String,
+    pub description: String,
+    pub knock_rule: KnockRule,
+    /// Highest deadwood the bot will knock with.
+    pub knock_threshold: u32,
+    /// Chance of throwing a random card instead of the best one (and not knocking).
+    pub random_discard_chance: f64,
+    /// Take the top discard if it raises deadwood by at most this much.
+    pub take_discard_margin: i32,
+    /// Weight of a card's value when scoring discards; small values only break ties.
+    pub discard_value_weight: f64,
+    /// Classic rule: the round counts as late after this many discards...
+    pub late_round_discards: usize,
+    /// ...or once the stock is down to this many cards.
+    pub late_round_stock: usize,
+    /// Classic rule: knock early with deadwood at or below this.
+    pub strong_hand_deadwood: u32,
+    /// Risk-aware rule: only knock when expected points are above this.
+    pub min_knock_points: f64,
+    /// Risk-aware rule: opponent hands sampled per knock decision.
+    pub risk_samples: usize,
+    /// Risk-aware rule: most stock draws replayed into each sampled opponent hand.
+    pub replayed_draws: usize,
+    /// Search the rest of the hand once the stock is down to this many cards (0 = never).
+    pub endgame_stock: usize,
+    /// Positions the endgame search may visit per decision. Opponent hands are
+    /// searched in turn until it runs out, so a large enough budget covers them all.
+    pub endgame_budget: usize,
+    /// Best-scoring discards the endgame search considers at each of the bot's turns.
+    pub endgame_breadth: usize,
+}
+
+impl Default for BotParams {
+    fn default() -> Self {
+        Self::for_difficulty(BotDifficulty::Challenging)
+    }
+}
+
+impl BotParams {
+    pub fn for_difficulty(difficulty: BotDifficulty) -> Self {
+        let base = Self {
+            name: crate::bot::DEFAULT_STRATEGY.to_string(),
+            description: String::new(),
+            knock_rule: KnockRule::RiskAware,
+            knock_threshold: 10,
+            random_discard_chance: 0.0,
+            take_discard_margin: 0,
+            discard_value_weight: 0.1,
+            late_round_discards: 8,
+            late_round_stock: 15,
+            strong_hand_deadwood: 3,
+            min_knock_points: 0.0,
+            risk_samples: 16,
+            replayed_draws: 4,
+            endgame_stock: 4,
+            endgame_budget: 20_000,
+            endgame_breadth: 3,
+        };
+        match difficulty {
+            BotDifficulty::Easy => Self {
+                knock_threshold: 6,
+                random_discard_chance: 0.2,
+                endgame_stock: 0,
+                ..base
+            },
+            BotDifficulty::Challenging => base,
+            BotDifficulty::Adaptive => Self::for_level(0.5),
+        }
+    }
+
+    /// Parameters on a sliding scale from Easy (0.0) to Challenging (1.0):
+    /// weaker levels throw more random cards, knock only with less deadwood
+    /// and skip the endgame search. Every value stays between the two.
+    pub fn for_level(level: f64) -> Self {
+        let level = level.clamp(0.0, 1.0);
+        let easy = Self::for_difficulty(BotDifficulty::Easy);
+        let base = Self::for_difficulty(BotDifficulty::Challenging);
+        let between = |low: f64, high: f64| low + (high - low) * level;
+        Self {
+            knock_threshold: between(easy.knock_threshold as f64, base.knock_threshold as f64)
+                .round() as u32,
+            random_discard_chance: between(easy.random_discard_chance, base.random_discard_chance),
+            min_knock_points: between(easy.min_knock_points, base.min_knock_points),
+            risk_samples: between(easy.risk_samples as f64, base.risk_samples as f64).round()
+                as usize,
+            replayed_draws: between(easy.replayed_draws as f64, base.replayed_draws as f64).round()
+                as usize,
+            endgame_stock: if level >= 0.6 {
+                base.endgame_stock
+            } else {
+                easy.endgame_stock
+            },
+            ..base
+        }
+    }
+
+    /// The original fixed knock rule, kept as a baseline.
+    pub fn classic(difficulty: BotDifficulty) -> Self {
+        let knock_threshold = match difficulty {
+            BotDifficulty::Easy => 6,
+            BotDifficulty::Challenging => 8,
+            BotDifficulty::Adaptive => 7,
+        };
+        Self {
+            name: crate::bot::CLASSIC_STRATEGY.to_string(),
+            knock_rule: KnockRule::Classic,
+            knock_threshold,
+            endgame_stock: 0,
+            ..Self::for_difficulty(difficulty)
+        }
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let contents = fs::read_to_string(path)
+            .with_context(|| format!("failed to read bot profile at {}", path.display()))?;
+        let table: toml::Table = toml::from_str(&contents)
+            .with_context(|| format!("failed to parse bot profile at {}", path.display()))?;
+        let named = table.contains_key("name");
+        let mut params: Self = table
+            .try_into()
+            .with_context(|| format!("failed to parse bot profile at {}", path.display()))?;
+        // Unnamed profiles take their file name rather than shadowing the built-in bot.
+        if !named {
+            if let Some(stem) = path.file_stem() {
+                params.name = stem.to_string_lossy().into_owned();
+            }
+        }
+        Ok(params.clamped())
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            if !parent.as_os_str().is_empty() {
+                fs::create_dir_all(parent).with_context(|| {
+                    format!("failed to create profile directory {}", parent.display())
+                })?;
+            }
+        }
+        let contents = toml::to_string_pretty(self).context("failed to serialise bot profile")?;
+        fs::write(path, contents)
+            .with_context(|| format!("failed to write bot profile to {}", path.display()))
+    }
+
+    /// Loads every `*.toml` profile in `dir`, reporting files that fail to load
+    /// as warnings. A missing directory simply yields no profiles.
+    pub fn load_dir(dir: &Path) -> (Vec<Self>, Vec<String>) {
+        let mut profiles = Vec::new();
+        let mut warnings = Vec::new();
+        let Ok(entries) = fs::read_dir(dir) else {
+            return (profiles, warnings);
+        };
+        let mut paths: Vec<_> = entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == "toml"))
+            .collect();
+        paths.sort();
+        for path in paths {
+            match Self::load(&path) {
+                Ok(params) => profiles.push(params),
+                Err(err) => warnings.push(format!("{err:#}")),
+            }
+        }
+        (profiles, warnings)
+    }
+
+    /// Pulls every value back into the range the bot can use.
+    pub fn clamped(mut self) -> Self {
+        self.knock_threshold = self.knock_threshold.min(10);
+        self.random_discard_chance = self.random_discard_chance.clamp(0.0, 1.0);
+        self.discard_value_weight = self.discard_value_weight.max(0.0);
+        self.strong_hand_deadwood = self.strong_hand_deadwood.min(10);
+        self.risk_samples = self.risk_samples.clamp(1, 256);
+        self.replayed_draws = self.replayed_draws.min(20);
+        self.endgame_stock = self.endgame_stock.min(31);
+        self.endgame_budget = self.endgame_budget.clamp(1_000, 10_000_000);
+        self.endgame_breadth = self.endgame_breadth.clamp(1, 11);
+        if self.name.trim().is_empty() {
+            self.name = crate::bot::DEFAULT_STRATEGY.to_string();
+        }
+        self.name = self.name.trim().to_ascii_lowercase();
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bot::StrategyRegistry;
+
+    #[test]
+    fn profiles_fill_in_missing_fields_and_take_their_file_name() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("Gin-Hunter.toml");
+        fs::write(&path, "knock_threshold = 4\nknock_rule = \"classic\"\n").unwrap();
+        assert_eq!(
+            BotParams::load(&path).unwrap(),
+            BotParams {
+                name: "gin-hunter".to_string(),
+                knock_threshold: 4,
+                knock_rule: KnockRule::Classic,
+                ..BotParams::default()
+            }
+        );
+
+        fs::write(
+            &path,
+            "name = \" Sharp \"\ndescription = \"Knocks early.\"\n",
+        )
+        .unwrap();
+        let named = BotParams::load(&path).unwrap();
+        assert_eq!(named.name, "sharp");
+        assert_eq!(named.description, "Knocks early.");
+
+        fs::write(&path, "knock_threshold = \"low\"\n").unwrap();
+        let err = format!("{:#}", BotParams::load(&path).unwrap_err());
+        assert!(err.starts_with("failed to parse bot profile at"), "{err}");
+    }
+
+    #[test]
+    fn clamping_pulls_values_into_range() {
+        let wild = BotParams {
+            name: "  ".to_string(),
+            knock_threshold: 15,
+            random_discard_chance: 1.5,
+            discard_value_weight: -1.0,
+            strong_hand_deadwood: 20,
+            risk_samples: 0,
+            replayed_draws: 50,
+            endgame_stock: 60,
+            endgame_budget: 10,
+            endgame_breadth: 0,
+            ..BotParams::default()
+        };
+        assert_eq!(
+            wild.clamped(),
+            BotParams {
+                name: crate::bot::DEFAULT_STRATEGY.to_string(),
+                knock_threshold: 10,
+                random_discard_chance: 1.0,
+                discard_value_weight: 0.0,
+                strong_hand_deadwood: 10,
+                risk_samples: 1,
+                replayed_draws: 20,
+                endgame_stock: 31,
+                endgame_budget: 1_000,
+                endgame_breadth: 1,
+                ..BotParams::default()
+            }
+        );
+        assert_eq!(BotParams::default().clamped(), BotParams::default());
+    }
+
+    #[test]
+    fn adaptive_levels_stay_between_easy_and_challenging() {
+        let easy = BotParams::for_difficulty(BotDifficulty::Easy);
+        let challenging = BotParams::for_difficulty(BotDifficulty::Challenging);
+        assert_eq!(BotParams::for_level(0.0), easy);
+        assert_eq!(BotParams::for_level(-1.0), easy);
+        assert_eq!(BotParams::for_level(1.0), challenging);
+        for step in 0..=10 {
+            let params = BotParams::for_level(step as f64 / 10.0);
+            assert!((0.0..=easy.random_discard_chance).contains(&params.random_discard_chance));
+            assert!((easy.knock_threshold..=challenging.knock_threshold)
+                .contains(&params.knock_threshold));
+        }
+    }
+
+    #[test]
+    fn profiles_named_after_builtin_strategies_are_not_registered() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("heuristic.toml"), "knock_threshold = 2\n").unwrap();
+        fs::write(dir.path().join("patient.toml"), "knock_threshold = 3\n").unwrap();
+        fs::write(dir.path().join("broken.toml"), "knock_threshold = [\n").unwrap();
+        fs::write(dir.path().join("notes.txt"), "not a profile").unwrap();
+
+        let (profiles, warnings) = BotParams::load_dir(dir.path());
+        let names: Vec<&str> = profiles.iter().map(|params| params.name.as_str()).collect();
+        assert_eq!(names, ["heuristic", "patient"]);
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("broken.toml"), "{warnings:?}");
+
+        let mut registry = StrategyRegistry::builtin();
+        let mut rejected = Vec::new();
+        for profile in profiles {
+            if let Err(err) = registry.register_profile(profile) {
+                rejected.push(err.to_string());
+            }
+        }
+        assert_eq!(
+            rejected,
+            ["bot profile 'heuristic' uses the name of a built-in strategy. Skipping it"]
+        );
+        assert!(registry.names().any(|name| name == "patient"));
+    }
+}
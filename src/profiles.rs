@@ -0,0 +1,163 @@
+//! Startup picker for choosing or creating a player profile.
+
+use crossterm::event::{KeyCode, KeyEvent};
+
+use crate::storage::{validate_profile_name, DEFAULT_PROFILE};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PickerOutcome {
+    Pending,
+    Chosen(String),
+    Quit,
+}
+
+#[derive(Debug, Clone)]
+pub struct ProfilePicker {
+    profiles: Vec<String>,
+    selected: usize,
+    /// Name being typed for a new profile, if the player pressed `n`.
+    new_name: Option<String>,
+    error: Option<String>,
+}
+
+impl ProfilePicker {
+    /// Starts on `last` (the profile used most recently) when it exists.
+    pub fn new(mut profiles: Vec<String>, last: Option<&str>) -> Self {
+        if profiles.is_empty() {
+            profiles.push(DEFAULT_PROFILE.to_string());
+        }
+        let selected = last
+            .and_then(|last| profiles.iter().position(|name| name == last))
+            .unwrap_or(0);
+        Self {
+            profiles,
+            selected,
+            new_name: None,
+            error: None,
+        }
+    }
+
+    pub fn handle_key(&mut self, key_event: KeyEvent) -> PickerOutcome {
+        if let Some(name) = self.new_name.as_mut() {
+            match key_event.code {
+                KeyCode::Char(c) => name.push(c),
+                KeyCode::Backspace => {
+                    name.pop();
+                }
+                KeyCode::Enter => match validate_profile_name(name) {
+                    Ok(name) => return PickerOutcome::Chosen(name),
+                    Err(err) => self.error = Some(err.to_string()),
+                },
+                KeyCode::Esc => {
+                    self.new_name = None;
+                    self.error = None;
+                }
+                _ => {}
+            }
+            return PickerOutcome::Pending;
+        }
+
+        let count = self.profiles.len();
+        match key_event.code {
+            KeyCode::Up => self.selected = (self.selected + count - 1) % count,
+            KeyCode::Down => self.selected = (self.selected + 1) % count,
+            KeyCode::Enter => return PickerOutcome::Chosen(self.profiles[self.selected].clone()),
+            KeyCode::Char(c) if c.eq_ignore_ascii_case(&'n') => {
+                self.new_name = Some(String::new());
+                self.error = None;
+            }
+            KeyCode::Char(c) if c.eq_ignore_ascii_case(&'q') => return PickerOutcome::Quit,
+            KeyCode::Esc => return PickerOutcome::Quit,
+            _ => {}
+        }
+        PickerOutcome::Pending
+    }
+
+    pub fn profiles(&self) -> &[String] {
+        &self.profiles
+    }
+
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    pub fn new_name(&self) -> Option<&str> {
+        self.new_name.as_deref()
+    }
+
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn picker(last: Option<&str>) -> ProfilePicker {
+        let profiles = ["ana", "ben", "default"].map(String::from).to_vec();
+        ProfilePicker::new(profiles, last)
+    }
+
+    fn press(picker: &mut ProfilePicker, code: KeyCode) -> PickerOutcome {
+        picker.handle_key(KeyEvent::from(code))
+    }
+
+    fn type_text(picker: &mut ProfilePicker, text: &str) {
+        for c in text.chars() {
+            assert_eq!(press(picker, KeyCode::Char(c)), PickerOutcome::Pending);
+        }
+    }
+
+    #[test]
+    fn starts_on_the_profile_used_last() {
+        assert_eq!(picker(Some("ben")).selected(), 1);
+        assert_eq!(picker(Some("gone")).selected(), 0);
+        assert_eq!(picker(None).selected(), 0);
+        assert_eq!(ProfilePicker::new(Vec::new(), None).profiles(), ["default"]);
+    }
+
+    #[test]
+    fn arrows_wrap_and_enter_chooses() {
+        let mut picker = picker(Some("ana"));
+        assert_eq!(press(&mut picker, KeyCode::Up), PickerOutcome::Pending);
+        assert_eq!(picker.selected(), 2);
+        press(&mut picker, KeyCode::Down);
+        press(&mut picker, KeyCode::Down);
+        assert_eq!(
+            press(&mut picker, KeyCode::Enter),
+            PickerOutcome::Chosen("ben".to_string())
+        );
+    }
+
+    #[test]
+    fn n_creates_a_profile_with_a_checked_name() {
+        let mut picker = picker(None);
+        press(&mut picker, KeyCode::Char('N'));
+        assert_eq!(picker.new_name(), Some(""));
+        // Keys that would act on the list are typed while naming.
+        type_text(&mut picker, "Q n!");
+        assert_eq!(press(&mut picker, KeyCode::Enter), PickerOutcome::Pending);
+        assert!(picker.error().unwrap().contains("may only use letters"));
+
+        for _ in 0..3 {
+            press(&mut picker, KeyCode::Backspace);
+        }
+        type_text(&mut picker, "_2");
+        assert_eq!(
+            press(&mut picker, KeyCode::Enter),
+            PickerOutcome::Chosen("q_2".to_string())
+        );
+    }
+
+    #[test]
+    fn esc_leaves_naming_and_then_quits() {
+        let mut picker = picker(None);
+        press(&mut picker, KeyCode::Char('n'));
+        type_text(&mut picker, "cleo");
+        assert_eq!(press(&mut picker, KeyCode::Esc), PickerOutcome::Pending);
+        assert_eq!(picker.new_name(), None);
+        assert_eq!(press(&mut picker, KeyCode::Esc), PickerOutcome::Quit);
+        assert_eq!(press(&mut picker, KeyCode::Char('q')), PickerOutcome::Quit);
+    }
+}
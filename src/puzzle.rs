@@ -0,0 +1,834 @@
+//! Training puzzles: a single decision from a set-up position, checked against
+//! an answer key or, when the puzzle has none, the same evaluation the hints use.
+
+use std::{
+    collections::{BTreeMap, HashSet},
+    fs,
+    path::Path,
+};
+
+use anyhow::{bail, Context, Result};
+use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    bot::{self, BotDifficulty, Hint},
+    cards::{Card, HAND_SIZE},
+    game::{build_deck, seed_from_text, Deal, DrawSource, Game, Move, PlayerId, TurnPhase},
+    meld::analyze_hand,
+    params::BotParams,
+    storage::{set_aside, write_atomic},
+};
+
+/// A puzzle file: `[[puzzle]]` tables in TOML, or `{"puzzle": [...]}` in JSON.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PuzzleFile {
+    #[serde(default, rename = "puzzle")]
+    pub puzzles: Vec<Puzzle>,
+}
+
+/// One position, seen from your side of the table on your turn.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Puzzle {
+    /// Unique name; solve counts are kept under it.
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Ten cards asks for the draw; eleven asks for the discard and the knock.
+    pub hand: Vec<Card>,
+    /// Bottom to top, so the last card is the one you may take.
+    #[serde(default)]
+    pub discard_pile: Vec<Card>,
+    /// Cards the opponent took from the discard pile and still holds.
+    #[serde(default)]
+    pub opponent_pickups: Vec<Card>,
+    /// Cards left in the stock; defaults to every card not otherwise placed.
+    #[serde(default)]
+    pub stock: Option<usize>,
+    /// The expected answer. Without one the hint evaluation decides.
+    #[serde(default)]
+    pub answer: Option<AnswerKey>,
+}
+
+/// The parts of the answer a puzzle checks; parts left out are not graded.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AnswerKey {
+    #[serde(default)]
+    pub draw: Option<DrawSource>,
+    #[serde(default)]
+    pub discard: Option<Card>,
+    #[serde(default)]
+    pub knock: Option<bool>,
+    /// Shown after answering.
+    #[serde(default)]
+    pub explanation: Option<String>,
+}
+
+/// What the puzzle asks for, decided by the size of the hand.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Question {
+    Draw,
+    Discard,
+}
+
+/// The player's answer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Answer {
+    Draw(DrawSource),
+    Discard { card: Card, knock: bool },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Verdict {
+    pub correct: bool,
+    /// The expected answer and why, for display.
+    pub explanation: String,
+    /// Hand index of the expected discard, to highlight it.
+    pub solution_index: Option<usize>,
+}
+
+impl Puzzle {
+    pub fn question(&self) -> Question {
+        if self.hand.len() > HAND_SIZE {
+            Question::Discard
+        } else {
+            Question::Draw
+        }
+    }
+
+    /// Checks that the cards can make up one deck and the position is playable.
+    pub fn validate(&self) -> Result<()> {
+        if self.name.trim().is_empty() {
+            bail!("a puzzle has no name");
+        }
+        if self.hand.len() != HAND_SIZE && self.hand.len() != HAND_SIZE + 1 {
+            bail!(
+                "the hand must hold {HAND_SIZE} cards (draw puzzle) or {} (discard puzzle), not {}",
+                HAND_SIZE + 1,
+                self.hand.len()
+            );
+        }
+        if self.opponent_pickups.len() > HAND_SIZE {
+            bail!("the opponent cannot hold more than {HAND_SIZE} picked-up cards");
+        }
+        let mut seen = HashSet::new();
+        for card in self
+            .hand
+            .iter()
+            .chain(&self.discard_pile)
+            .chain(&self.opponent_pickups)
+        {
+            if !seen.insert(*card) {
+                bail!("the {card} appears twice");
+            }
+        }
+        let available = self.unplaced_count();
+        let stock = self.stock_count();
+        if stock > available {
+            bail!(
+                "a stock of {stock} leaves too few cards for the opponent ({available} unplaced)"
+            );
+        }
+        if stock <= 2 {
+            bail!("the stock must hold more than two cards or the hand is already over");
+        }
+        if let Some(key) = &self.answer {
+            match self.question() {
+                Question::Draw if key.discard.is_some() || key.knock.is_some() => {
+                    bail!("a draw puzzle's answer can only give `draw`")
+                }
+                Question::Discard if key.draw.is_some() => {
+                    bail!("a discard puzzle's answer can only give `discard` and `knock`")
+                }
+                _ => {}
+            }
+            if key.draw == Some(DrawSource::Discard) && self.discard_pile.is_empty() {
+                bail!("the answer takes the discard but the discard pile is empty");
+            }
+            if let Some(card) = key.discard {
+                if !self.hand.contains(&card) {
+                    bail!("the answer discards the {card}, which is not in the hand");
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Cards neither in your hand, the discard pile nor the opponent's pickups.
+    fn unplaced_count(&self) -> usize {
+        52usize.saturating_sub(self.hand.len() + self.discard_pile.len() + HAND_SIZE)
+    }
+
+    fn stock_count(&self) -> usize {
+        self.stock.unwrap_or_else(|| self.unplaced_count())
+    }
+
+    /// Sets up the position as a game on your turn. The opponent's unknown
+    /// cards and the stock are dealt from the unseen cards with a shuffle
+    /// fixed by the puzzle's name, so the evaluation never changes.
+    pub fn position(&self) -> Result<Game> {
+        self.validate()
+            .with_context(|| format!("puzzle '{}' is invalid", self.name))?;
+        let seed = seed_from_text(&self.name);
+        let mut game = Game::with_seed(seed)?;
+
+        let placed: HashSet<Card> = self
+            .hand
+            .iter()
+            .chain(&self.discard_pile)
+            .chain(&self.opponent_pickups)
+            .copied()
+            .collect();
+        let mut unseen: Vec<Card> = build_deck()
+            .into_iter()
+            .filter(|card| !placed.contains(card))
+            .collect();
+        unseen.shuffle(&mut StdRng::seed_from_u64(seed));
+
+        let hidden = HAND_SIZE - self.opponent_pickups.len();
+        let mut opponent = self.opponent_pickups.clone();
+        opponent.extend(unseen.drain(..hidden));
+
+        game.human.hand = self.hand.clone();
+        game.human.sort_hand();
+        game.bot.hand = opponent;
+        game.bot.sort_hand();
+        game.stock = unseen.into_iter().take(self.stock_count()).collect();
+        game.discard = self.discard_pile.clone();
+        game.dealer = PlayerId::Bot;
+        game.current_player = PlayerId::Human;
+        game.phase = match self.question() {
+            Question::Draw => TurnPhase::AwaitDraw,
+            Question::Discard => TurnPhase::AwaitDiscard,
+        };
+        game.round_seed = seed;
+        game.deal = Deal {
+            dealer: PlayerId::Bot,
+            human: game.human.hand.clone(),
+            bot: game.bot.hand.clone(),
+            upcard: self.discard_pile.first().copied(),
+        };
+        // The pickups are recorded as draws so the opponent model knows about them.
+        game.moves = self
+            .opponent_pickups
+            .iter()
+            .map(|&card| Move::Draw {
+                player: PlayerId::Bot,
+                source: DrawSource::Discard,
+                card,
+            })
+            .collect();
+        Ok(game)
+    }
+
+    /// Grades `answer` against the answer key, or the hint evaluation of `game`
+    /// (the puzzle's position) when there is no key.
+    pub fn check(&self, game: &Game, answer: Answer) -> Verdict {
+        match &self.answer {
+            Some(key) => self.check_key(game, key, answer),
+            None => self.check_engine(game, answer),
+        }
+    }
+
+    fn check_key(&self, game: &Game, key: &AnswerKey, answer: Answer) -> Verdict {
+        let mut expected = Vec::new();
+        let correct = match answer {
+            Answer::Draw(source) => {
+                if let Some(draw) = key.draw {
+                    expected.push(describe_draw(draw));
+                }
+                key.draw.is_none_or(|draw| draw == source)
+            }
+            Answer::Discard { card, knock } => {
+                if let Some(discard) = key.discard {
+                    expected.push(format!("discard the {discard}"));
+                }
+                if let Some(should_knock) = key.knock {
+                    expected.push(if should_knock { "knock" } else { "play on" }.to_string());
+                }
+                key.discard.is_none_or(|discard| discard == card)
+                    && key.knock.is_none_or(|should_knock| should_knock == knock)
+                    && legal_answer(game, card, knock)
+            }
+        };
+        let mut explanation = format!("Answer: {}.", sentence(&expected));
+        if let Some(extra) = &key.explanation {
+            explanation.push(' ');
+            explanation.push_str(extra);
+        }
+        Verdict {
+            correct,
+            explanation,
+            solution_index: key
+                .discard
+                .and_then(|card| game.human.hand.iter().position(|&held| held == card)),
+        }
+    }
+
+    fn check_engine(&self, game: &Game, answer: Answer) -> Verdict {
+        match (bot::suggest(game, PlayerId::Human), answer) {
+            (Some(Hint::Draw(trace)), Answer::Draw(source)) => {
+                let reason = match (trace.top_discard, trace.deadwood_with_discard) {
+                    (Some(card), Some(with_discard)) => format!(
+                        "With the {card} your deadwood goes {} -> {with_discard} before discarding.",
+                        trace.current_deadwood
+                    ),
+                    _ => "There is no discard to take.".to_string(),
+                };
+                Verdict {
+                    correct: source == trace.choice,
+                    explanation: format!(
+                        "Answer: {}. {reason}",
+                        capitalise(&describe_draw(trace.choice))
+                    ),
+                    solution_index: None,
+                }
+            }
+            (
+                Some(Hint::Discard {
+                    index,
+                    candidate,
+                    knock,
+                }),
+                Answer::Discard {
+                    card,
+                    knock: knocked,
+                },
+            ) => {
+                // Any discard that scores as well as the best one is accepted.
+                let scores = bot::evaluate_discards(
+                    &game.human.hand,
+                    &BotParams::for_difficulty(BotDifficulty::Challenging),
+                );
+                let best_discard = scores
+                    .iter()
+                    .any(|other| other.card == card && other.score >= candidate.score);
+                let knock_advice = if knock.knock {
+                    "knock"
+                } else if knock.deadwood_after_discard <= 10 {
+                    "play on rather than knock"
+                } else {
+                    "play on"
+                };
+                Verdict {
+                    correct: best_discard
+                        && knocked == knock.knock
+                        && legal_answer(game, card, knocked),
+                    explanation: format!(
+                        "Answer: discard the {} (leaves {} deadwood) and {knock_advice}.",
+                        candidate.card, candidate.deadwood
+                    ),
+                    solution_index: Some(index),
+                }
+            }
+            _ => Verdict {
+                correct: false,
+                explanation: "That answer does not fit this puzzle.".to_string(),
+                solution_index: None,
+            },
+        }
+    }
+}
+
+/// A knock is only a legal answer when the hand left behind has 10 or less deadwood.
+fn legal_answer(game: &Game, card: Card, knock: bool) -> bool {
+    if !knock {
+        return true;
+    }
+    let remaining: Vec<Card> = game
+        .human
+        .hand
+        .iter()
+        .copied()
+        .filter(|&held| held != card)
+        .collect();
+    analyze_hand(&remaining).deadwood_value <= 10
+}
+
+fn describe_draw(source: DrawSource) -> String {
+    match source {
+        DrawSource::Stock => "draw from the stock".to_string(),
+        DrawSource::Discard => "take the discard".to_string(),
+    }
+}
+
+fn sentence(parts: &[String]) -> String {
+    if parts.is_empty() {
+        return "Any move".to_string();
+    }
+    capitalise(&parts.join(" and "))
+}
+
+fn capitalise(text: &str) -> String {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Loads the puzzles in a `.toml` or `.json` file and checks each one.
+pub fn load_file(path: &Path) -> Result<Vec<Puzzle>> {
+    let contents = fs::read_to_string(path)
+        .with_context(|| format!("failed to read puzzles at {}", path.display()))?;
+    let file: PuzzleFile = if path.extension().is_some_and(|ext| ext == "json") {
+        serde_json::from_str(&contents)
+            .with_context(|| format!("failed to parse puzzles at {}", path.display()))?
+    } else {
+        toml::from_str(&contents)
+            .with_context(|| format!("failed to parse puzzles at {}", path.display()))?
+    };
+    for puzzle in &file.puzzles {
+        puzzle.validate().with_context(|| {
+            format!("puzzle '{}' in {} is invalid", puzzle.name, path.display())
+        })?;
+    }
+    Ok(file.puzzles)
+}
+
+/// Loads every `*.toml` and `*.json` puzzle file in `dir`, in file name order,
+/// reporting files that fail to load and repeated names as warnings. A missing
+/// directory simply yields no puzzles.
+pub fn load_dir(dir: &Path) -> (Vec<Puzzle>, Vec<String>) {
+    let mut puzzles: Vec<Puzzle> = Vec::new();
+    let mut warnings = Vec::new();
+    let Ok(entries) = fs::read_dir(dir) else {
+        return (puzzles, warnings);
+    };
+    let mut paths: Vec<_> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|ext| ext == "toml" || ext == "json")
+        })
+        .collect();
+    paths.sort();
+    for path in paths {
+        match load_file(&path) {
+            Ok(loaded) => {
+                for puzzle in loaded {
+                    if puzzles.iter().any(|other| other.name == puzzle.name) {
+                        warnings.push(format!(
+                            "Skipped a second puzzle named '{}' in {}.",
+                            puzzle.name,
+                            path.display()
+                        ));
+                    } else {
+                        puzzles.push(puzzle);
+                    }
+                }
+            }
+            Err(err) => warnings.push(format!("{err:#}")),
+        }
+    }
+    (puzzles, warnings)
+}
+
+/// How often one puzzle has been tried and solved.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PuzzleScore {
+    pub attempts: u32,
+    pub solved: u32,
+    /// Whether the first attempt was correct.
+    #[serde(default)]
+    pub first_try: bool,
+}
+
+/// A profile's puzzle results, keyed by puzzle name.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PuzzleProgress {
+    #[serde(default)]
+    puzzles: BTreeMap<String, PuzzleScore>,
+    /// Set when the file on disk could not be loaded, so the next save moves it
+    /// aside instead of overwriting it.
+    #[serde(skip)]
+    unreadable: bool,
+}
+
+impl PuzzleProgress {
+    /// Loads the progress file; a missing file means nothing has been tried yet.
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let contents = fs::read_to_string(path)
+            .with_context(|| format!("failed to read puzzle progress at {}", path.display()))?;
+        serde_json::from_str(&contents)
+            .with_context(|| format!("failed to parse puzzle progress at {}", path.display()))
+    }
+
+    /// Empty progress to use when [`PuzzleProgress::load`] failed.
+    pub fn replacing_unreadable() -> Self {
+        Self {
+            unreadable: true,
+            ..Self::default()
+        }
+    }
+
+    pub fn save(&mut self, path: &Path) -> Result<()> {
+        let contents =
+            serde_json::to_string_pretty(self).context("failed to serialise puzzle progress")?;
+        if self.unreadable && path.exists() {
+            set_aside(path)?;
+        }
+        self.unreadable = false;
+        write_atomic(path, contents.as_bytes())
+            .with_context(|| format!("failed to write puzzle progress to {}", path.display()))
+    }
+
+    pub fn record(&mut self, name: &str, correct: bool) {
+        let score = self.puzzles.entry(name.to_string()).or_default();
+        if score.attempts == 0 {
+            score.first_try = correct;
+        }
+        score.attempts += 1;
+        if correct {
+            score.solved += 1;
+        }
+    }
+
+    pub fn score(&self, name: &str) -> PuzzleScore {
+        self.puzzles.get(name).copied().unwrap_or_default()
+    }
+
+    /// Solve counts over `puzzles`: how many were solved at least once, solved
+    /// on the first try, and the share of all attempts that were correct.
+    pub fn summary<'a>(&self, puzzles: impl IntoIterator<Item = &'a Puzzle>) -> SolveSummary {
+        let mut summary = SolveSummary::default();
+        for puzzle in puzzles {
+            let score = self.score(&puzzle.name);
+            summary.puzzles += 1;
+            summary.attempts += score.attempts;
+            summary.correct += score.solved;
+            if score.solved > 0 {
+                summary.solved += 1;
+            }
+            if score.first_try && score.attempts > 0 {
+                summary.first_try += 1;
+            }
+        }
+        summary
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SolveSummary {
+    pub puzzles: u32,
+    pub solved: u32,
+    pub first_try: u32,
+    pub attempts: u32,
+    pub correct: u32,
+}
+
+impl SolveSummary {
+    pub fn solve_rate(&self) -> Option<f64> {
+        (self.attempts > 0).then(|| f64::from(self.correct) / f64::from(self.attempts))
+    }
+}
+
+/// A run through a list of puzzles, showing one position at a time.
+#[derive(Debug, Clone)]
+pub struct PuzzleRun {
+    puzzles: Vec<Puzzle>,
+    index: usize,
+    game: Game,
+    verdict: Option<Verdict>,
+}
+
+impl PuzzleRun {
+    pub fn new(puzzles: Vec<Puzzle>) -> Result<Self> {
+        let Some(first) = puzzles.first() else {
+            bail!("there are no puzzles to play");
+        };
+        let game = first.position()?;
+        Ok(Self {
+            puzzles,
+            index: 0,
+            game,
+            verdict: None,
+        })
+    }
+
+    pub fn puzzle(&self) -> &Puzzle {
+        &self.puzzles[self.index]
+    }
+
+    pub fn puzzles(&self) -> &[Puzzle] {
+        &self.puzzles
+    }
+
+    /// 1-based number of the current puzzle.
+    pub fn number(&self) -> usize {
+        self.index + 1
+    }
+
+    /// The puzzle's position, which answering never changes.
+    pub fn game(&self) -> &Game {
+        &self.game
+    }
+
+    pub fn verdict(&self) -> Option<&Verdict> {
+        self.verdict.as_ref()
+    }
+
+    /// Grades the answer; later answers to the same puzzle are ignored until
+    /// it is retried.
+    pub fn answer(&mut self, answer: Answer) -> Option<&Verdict> {
+        if self.verdict.is_some() {
+            return None;
+        }
+        self.verdict = Some(self.puzzle().check(&self.game, answer));
+        self.verdict.as_ref()
+    }
+
+    pub fn retry(&mut self) {
+        self.verdict = None;
+    }
+
+    /// Moves to the next puzzle, returning false after the last one.
+    pub fn advance(&mut self) -> Result<bool> {
+        if self.index + 1 >= self.puzzles.len() {
+            return Ok(false);
+        }
+        self.game = self.puzzles[self.index + 1].position()?;
+        self.index += 1;
+        self.verdict = None;
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cards(text: &str) -> Vec<Card> {
+        text.split_whitespace()
+            .map(|card| card.parse().unwrap())
+            .collect()
+    }
+
+    fn card(text: &str) -> Card {
+        text.parse().unwrap()
+    }
+
+    fn puzzle(hand: &str, discard_pile: &str) -> Puzzle {
+        Puzzle {
+            name: "test".to_string(),
+            description: None,
+            hand: cards(hand),
+            discard_pile: cards(discard_pile),
+            opponent_pickups: Vec::new(),
+            stock: None,
+            answer: None,
+        }
+    }
+
+    /// Three melds and a spare 5H; the 10C on offer makes Gin.
+    const NEAR_GIN: &str = "AS 2S 3S 4D 4C 4H 7C 8C 9C 5H";
+
+    #[test]
+    fn validate_rejects_impossible_positions() {
+        let base = || puzzle(NEAR_GIN, "KS 10C");
+        base().validate().unwrap();
+        type Breakage = fn(&mut Puzzle);
+        let cases: [(&str, Breakage); 9] = [
+            ("has no name", |p| p.name = " ".to_string()),
+            ("not 9", |p| {
+                p.hand.pop();
+            }),
+            ("the AS appears twice", |p| p.discard_pile.push(card("AS"))),
+            ("more than 10 picked-up", |p| {
+                p.opponent_pickups = cards("2H 3H 6H 7H 8H 9H JH QH KH AH 2D");
+            }),
+            ("too few cards for the opponent", |p| p.stock = Some(31)),
+            ("more than two cards", |p| p.stock = Some(2)),
+            ("can only give `draw`", |p| {
+                p.answer = Some(AnswerKey {
+                    knock: Some(true),
+                    ..AnswerKey::default()
+                })
+            }),
+            ("discard pile is empty", |p| {
+                p.discard_pile.clear();
+                p.answer = Some(AnswerKey {
+                    draw: Some(DrawSource::Discard),
+                    ..AnswerKey::default()
+                })
+            }),
+            ("not in the hand", |p| {
+                p.hand.push(card("KD"));
+                p.answer = Some(AnswerKey {
+                    discard: Some(card("QD")),
+                    ..AnswerKey::default()
+                })
+            }),
+        ];
+        for (message, break_it) in cases {
+            let mut puzzle = base();
+            break_it(&mut puzzle);
+            let err = puzzle.validate().unwrap_err().to_string();
+            assert!(err.contains(message), "expected '{message}' in '{err}'");
+        }
+    }
+
+    #[test]
+    fn position_places_every_card_once() {
+        let mut draw = puzzle(NEAR_GIN, "KS 10C");
+        draw.opponent_pickups = cards("QH QD");
+        draw.stock = Some(20);
+        let game = draw.position().unwrap();
+        assert_eq!(game.phase, TurnPhase::AwaitDraw);
+        assert_eq!(game.current_player, PlayerId::Human);
+        assert_eq!(game.human.hand.len(), HAND_SIZE);
+        assert_eq!(game.bot.hand.len(), HAND_SIZE);
+        assert!(game.bot.hand.contains(&card("QH")) && game.bot.hand.contains(&card("QD")));
+        assert_eq!(game.stock.len(), 20);
+        assert_eq!(game.discard, cards("KS 10C"));
+        assert_eq!(game.moves.len(), 2);
+
+        let mut seen = HashSet::new();
+        for card in game
+            .human
+            .hand
+            .iter()
+            .chain(&game.bot.hand)
+            .chain(&game.stock)
+            .chain(&game.discard)
+        {
+            assert!(seen.insert(*card), "{card} placed twice");
+        }
+        // Ten unseen cards stay out of play because the stock was set to 20.
+        assert_eq!(seen.len(), 52 - 10);
+
+        // The hidden cards depend only on the name.
+        assert_eq!(draw.position().unwrap().bot.hand, game.bot.hand);
+        draw.name = "another name".to_string();
+        assert_ne!(draw.position().unwrap().stock, game.stock);
+    }
+
+    #[test]
+    fn default_stock_holds_every_unplaced_card() {
+        let discard = puzzle(&format!("{NEAR_GIN} KD"), "KS 10C");
+        let game = discard.position().unwrap();
+        assert_eq!(game.phase, TurnPhase::AwaitDiscard);
+        assert_eq!(game.stock.len(), 52 - 11 - 2 - HAND_SIZE);
+    }
+
+    #[test]
+    fn answer_key_grades_only_the_parts_it_sets() {
+        let mut draw = puzzle(NEAR_GIN, "KS 10C");
+        draw.answer = Some(AnswerKey {
+            draw: Some(DrawSource::Discard),
+            explanation: Some("Gin.".to_string()),
+            ..AnswerKey::default()
+        });
+        let game = draw.position().unwrap();
+        let verdict = draw.check(&game, Answer::Draw(DrawSource::Discard));
+        assert!(verdict.correct);
+        assert_eq!(verdict.explanation, "Answer: Take the discard. Gin.");
+        assert!(!draw.check(&game, Answer::Draw(DrawSource::Stock)).correct);
+
+        let mut discard = puzzle(&format!("{NEAR_GIN} KD"), "KS 10C");
+        discard.answer = Some(AnswerKey {
+            discard: Some(card("KD")),
+            knock: Some(true),
+            ..AnswerKey::default()
+        });
+        let game = discard.position().unwrap();
+        let answer = |card_text: &str, knock| Answer::Discard {
+            card: card(card_text),
+            knock,
+        };
+        let verdict = discard.check(&game, answer("KD", true));
+        assert!(verdict.correct);
+        assert_eq!(verdict.explanation, "Answer: Discard the KD and knock.");
+        assert_eq!(
+            verdict.solution_index,
+            game.human.hand.iter().position(|&held| held == card("KD"))
+        );
+        assert!(!discard.check(&game, answer("KD", false)).correct);
+        assert!(!discard.check(&game, answer("5H", true)).correct);
+
+        // With only `knock` set, any discard counts, but a knock must be legal.
+        discard.answer = Some(AnswerKey {
+            knock: Some(true),
+            ..AnswerKey::default()
+        });
+        assert!(discard.check(&game, answer("5H", true)).correct);
+        assert!(!discard.check(&game, answer("2S", true)).correct);
+    }
+
+    #[test]
+    fn without_a_key_the_hint_evaluation_decides() {
+        let draw = puzzle(NEAR_GIN, "KS 10C");
+        let game = draw.position().unwrap();
+        let verdict = draw.check(&game, Answer::Draw(DrawSource::Discard));
+        assert!(verdict.correct, "{}", verdict.explanation);
+        assert!(verdict
+            .explanation
+            .starts_with("Answer: Take the discard. With the 10C"));
+        assert!(!draw.check(&game, Answer::Draw(DrawSource::Stock)).correct);
+
+        let discard = puzzle(&format!("{NEAR_GIN} KD"), "KS 10C");
+        let game = discard.position().unwrap();
+        let graded: Vec<Verdict> = [false, true]
+            .into_iter()
+            .map(|knock| {
+                discard.check(
+                    &game,
+                    Answer::Discard {
+                        card: card("KD"),
+                        knock,
+                    },
+                )
+            })
+            .collect();
+        assert_eq!(graded.iter().filter(|verdict| verdict.correct).count(), 1);
+        assert!(graded[0].explanation.starts_with("Answer: discard the KD"));
+        let breaks_a_meld = Answer::Discard {
+            card: card("2S"),
+            knock: false,
+        };
+        assert!(!discard.check(&game, breaks_a_meld).correct);
+        let wrong_kind = discard.check(&game, Answer::Draw(DrawSource::Stock));
+        assert_eq!(
+            wrong_kind.explanation,
+            "That answer does not fit this puzzle."
+        );
+    }
+
+    #[test]
+    fn bundled_puzzles_load_and_set_up() {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("puzzles");
+        let (puzzles, warnings) = load_dir(&dir);
+        assert!(warnings.is_empty(), "{warnings:?}");
+        assert_eq!(puzzles, load_file(&dir.join("basics.toml")).unwrap());
+        assert!(puzzles.len() >= 4);
+        for puzzle in &puzzles {
+            puzzle
+                .position()
+                .unwrap_or_else(|err| panic!("{}: {err:#}", puzzle.name));
+        }
+        assert!(PuzzleRun::new(puzzles).is_ok());
+    }
+
+    #[test]
+    fn unreadable_progress_is_moved_aside_on_save() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("puzzles.json");
+        fs::write(&path, "[1, 2").unwrap();
+        assert!(PuzzleProgress::load(&path).is_err());
+
+        let mut progress = PuzzleProgress::replacing_unreadable();
+        progress.record("test", true);
+        progress.save(&path).unwrap();
+        assert_eq!(
+            fs::read_to_string(dir.path().join("puzzles.json.unreadable")).unwrap(),
+            "[1, 2"
+        );
+        let loaded = PuzzleProgress::load(&path).unwrap();
+        assert_eq!(loaded.score("test").solved, 1);
+    }
+}
@@ -0,0 +1,216 @@
+//! Step-by-step replay of a recorded hand.
+
+use anyhow::{Context, Result};
+
+use crate::{
+    archive::{HandRecord, MoveRecord, OutcomeKind},
+    cards::Card,
+    game::{DrawSource, Game, PlayerId, TurnPhase},
+    meld::analyze_hand,
+};
+
+pub struct Replay {
+    record: HandRecord,
+    title: String,
+    /// The position before any move, then after each one.
+    positions: Vec<Game>,
+    /// Deadwood (you, bot) at each position.
+    deadwood: Vec<(u32, u32)>,
+    step: usize,
+    face_up: bool,
+}
+
+impl Replay {
+    /// Rebuilds every position of `record` by replaying its moves, failing if
+    /// any move breaks the rules.
+    pub fn new(record: HandRecord, title: impl Into<String>) -> Result<Self> {
+        let mut game = Game::from_deal(record.deal.clone().into())?;
+        let mut positions = vec![game.clone()];
+        for (index, mv) in record.moves.iter().enumerate() {
+            game.apply((*mv).into())
+                .with_context(|| format!("move {} of the recorded hand", index + 1))?;
+            positions.push(game.clone());
+        }
+        // A hand that ran out of stock ended on a draw that was never made.
+        if record.outcome.kind == OutcomeKind::StockDepleted && game.phase != TurnPhase::RoundOver {
+            game.draw(game.current_player, DrawSource::Stock)?;
+            positions.push(game);
+        }
+        let deadwood = positions
+            .iter()
+            .map(|position| {
+                (
+                    analyze_hand(&position.human.hand).deadwood_value,
+                    analyze_hand(&position.bot.hand).deadwood_value,
+                )
+            })
+            .collect();
+        Ok(Self {
+            record,
+            title: title.into(),
+            positions,
+            deadwood,
+            step: 0,
+            face_up: true,
+        })
+    }
+
+    /// The position at the current step.
+    pub fn game(&self) -> &Game {
+        &self.positions[self.step]
+    }
+
+    pub fn record(&self) -> &HandRecord {
+        &self.record
+    }
+
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    pub fn step(&self) -> usize {
+        self.step
+    }
+
+    pub fn last_step(&self) -> usize {
+        self.positions.len() - 1
+    }
+
+    pub fn forward(&mut self) {
+        self.step = (self.step + 1).min(self.last_step());
+    }
+
+    pub fn back(&mut self) {
+        self.step = self.step.saturating_sub(1);
+    }
+
+    pub fn first(&mut self) {
+        self.step = 0;
+    }
+
+    pub fn last(&mut self) {
+        self.step = self.last_step();
+    }
+
+    /// Whether the bot's hand is shown while the hand is in progress.
+    pub fn face_up(&self) -> bool {
+        self.face_up
+    }
+
+    pub fn toggle_face_up(&mut self) {
+        self.face_up = !self.face_up;
+    }
+
+    /// Deadwood for `player` at every step up to the current one.
+    pub fn deadwood_history(&self, player: PlayerId) -> Vec<u32> {
+        self.deadwood[..=self.step]
+            .iter()
+            .map(|&(human, bot)| match player {
+                PlayerId::Human => human,
+                PlayerId::Bot => bot,
+            })
+            .collect()
+    }
+
+    /// The card you drew on the move just shown, to highlight it.
+    pub fn drawn_card(&self) -> Option<Card> {
+        match self.last_move()? {
+            MoveRecord::Draw {
+                player: PlayerId::Human,
+                card,
+                ..
+            } => Some(card),
+            _ => None,
+        }
+    }
+
+    pub fn describe_step(&self) -> String {
+        let name = |player: PlayerId| match player {
+            PlayerId::Human => "You",
+            PlayerId::Bot => "Bot",
+        };
+        let action = match self.last_move() {
+            None if self.step == 0 => "Deal.".to_string(),
+            None => "Stock ran out; the hand is a draw.".to_string(),
+            Some(MoveRecord::Draw {
+                player,
+                source,
+                card,
+            }) => {
+                let pile = match source {
+                    DrawSource::Stock => "stock",
+                    DrawSource::Discard => "discard pile",
+                };
+                format!("{} drew {card} from the {pile}.", name(player))
+            }
+            Some(MoveRecord::Discard {
+                player,
+                card,
+                knock,
+            }) => {
+                let knock = if knock { " and knocked" } else { "" };
+                format!("{} discarded {card}{knock}.", name(player))
+            }
+        };
+        format!("Move {}/{}: {action}", self.step, self.last_step())
+    }
+
+    fn last_move(&self) -> Option<MoveRecord> {
+        self.step
+            .checked_sub(1)
+            .and_then(|index| self.record.moves.get(index))
+            .copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        bot::{take_turn, BotDifficulty, HeuristicStrategy},
+        game::Rules,
+    };
+
+    #[test]
+    fn self_play_hands_replay_to_their_final_position() {
+        let mut depleted = 0;
+        // Easy bots skip the endgame search, which keeps 300 hands quick.
+        for seed in 0..300 {
+            let mut game = Game::with_seed(seed).unwrap();
+            let mut bot = HeuristicStrategy::new(BotDifficulty::Easy);
+            let mut human = HeuristicStrategy::new(BotDifficulty::Easy);
+            while game.phase != TurnPhase::RoundOver {
+                let seat = game.current_player;
+                let strategy = match seat {
+                    PlayerId::Bot => &mut bot,
+                    PlayerId::Human => &mut human,
+                };
+                take_turn(&mut game, seat, strategy).unwrap();
+            }
+            let result = game.pending_round.clone().unwrap();
+            let record =
+                HandRecord::from_round(&game, &result, None, "heuristic", 0, Rules::Standard);
+            if record.outcome.kind == OutcomeKind::StockDepleted {
+                depleted += 1;
+            }
+
+            let mut replay = Replay::new(record, format!("seed {seed}"))
+                .unwrap_or_else(|err| panic!("seed {seed}: {err:#}"));
+            replay.last();
+            let end = replay.game();
+            assert_eq!(end.phase, TurnPhase::RoundOver, "seed {seed}");
+            assert_eq!(end.discard, game.discard, "seed {seed}");
+            for player in [PlayerId::Human, PlayerId::Bot] {
+                let mut replayed = end.player(player).hand.clone();
+                let mut played = game.player(player).hand.clone();
+                replayed.sort();
+                played.sort();
+                assert_eq!(replayed, played, "seed {seed}");
+            }
+            let outcome = end.pending_round.as_ref().unwrap();
+            assert_eq!(outcome.winner, result.winner, "seed {seed}");
+            assert_eq!(outcome.points_awarded, result.points_awarded, "seed {seed}");
+        }
+        assert!(depleted > 0, "no hand ran out of stock");
+    }
+}
@@ -0,0 +1,232 @@
+//! The in-game settings screen: the options it can change and how they are
+//! saved back to `config.toml`.
+
+use std::{path::Path, time::Duration};
+
+use anyhow::Result;
+use ratatui::style::Color;
+
+use crate::{
+    cards::Suit,
+    config::{self, Config},
+};
+
+/// How a setting's value is changed on the settings screen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SettingKind {
+    Toggle,
+    /// Steps through [`BOT_DELAYS_MS`].
+    Delay,
+    /// Steps through [`SUIT_COLORS`] for one suit.
+    Color(Suit),
+}
+
+/// One editable option, stored under `key` in the config file.
+#[derive(Debug, Clone, Copy)]
+pub struct Setting {
+    pub key: &'static str,
+    pub label: &'static str,
+    pub kind: SettingKind,
+}
+
+/// Everything the settings screen edits, in display order.
+pub const SETTINGS: [Setting; 8] = [
+    Setting {
+        key: "persist_stats",
+        label: "Save session between runs",
+        kind: SettingKind::Toggle,
+    },
+    Setting {
+        key: "archive_hands",
+        label: "Archive finished hands",
+        kind: SettingKind::Toggle,
+    },
+    Setting {
+        key: "auto_brackets",
+        label: "Bracket melds in your hand",
+        kind: SettingKind::Toggle,
+    },
+    Setting {
+        key: "bot_delay_ms",
+        label: "Bot turn delay",
+        kind: SettingKind::Delay,
+    },
+    Setting {
+        key: "suit_colors.hearts",
+        label: "Hearts colour",
+        kind: SettingKind::Color(Suit::Hearts),
+    },
+    Setting {
+        key: "suit_colors.diamonds",
+        label: "Diamonds colour",
+        kind: SettingKind::Color(Suit::Diamonds),
+    },
+    Setting {
+        key: "suit_colors.clubs",
+        label: "Clubs colour",
+        kind: SettingKind::Color(Suit::Clubs),
+    },
+    Setting {
+        key: "suit_colors.spades",
+        label: "Spades colour",
+        kind: SettingKind::Color(Suit::Spades),
+    },
+];
+
+/// Bot delays offered on the settings screen, in milliseconds.
+pub const BOT_DELAYS_MS: [u64; 6] = [0, 250, 400, 600, 1000, 1500];
+
+/// Colours offered on the settings screen. Any other colour set in the file
+/// (hex or `rgb()`) is kept until it is changed here.
+pub const SUIT_COLORS: [Color; 14] = [
+    Color::Red,
+    Color::LightRed,
+    Color::Yellow,
+    Color::LightYellow,
+    Color::Green,
+    Color::LightGreen,
+    Color::Cyan,
+    Color::LightCyan,
+    Color::Blue,
+    Color::LightBlue,
+    Color::Magenta,
+    Color::LightMagenta,
+    Color::White,
+    Color::Gray,
+];
+
+/// State of the open settings screen. Changes apply to the live config as
+/// they are made; `original` is what cancelling restores.
+#[derive(Debug, Clone)]
+pub struct SettingsScreen {
+    selected: usize,
+    original: Config,
+    changed: Vec<&'static str>,
+}
+
+impl SettingsScreen {
+    pub fn new(config: &Config) -> Self {
+        Self {
+            selected: 0,
+            original: config.clone(),
+            changed: Vec::new(),
+        }
+    }
+
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    pub fn setting(&self) -> Setting {
+        SETTINGS[self.selected]
+    }
+
+    /// Keys changed since the screen opened.
+    pub fn changed(&self) -> &[&'static str] {
+        &self.changed
+    }
+
+    pub fn original(&self) -> &Config {
+        &self.original
+    }
+
+    pub fn move_selection(&mut self, forward: bool) {
+        let count = SETTINGS.len();
+        self.selected = if forward {
+            (self.selected + 1) % count
+        } else {
+            (self.selected + count - 1) % count
+        };
+    }
+
+    /// Changes the selected setting to its next (or previous) value.
+    pub fn adjust(&mut self, config: &mut Config, forward: bool) {
+        let setting = self.setting();
+        match setting.kind {
+            SettingKind::Toggle => {
+                let value = !toggle_value(config, setting.key);
+                match setting.key {
+                    "persist_stats" => config.set_persist_stats(value),
+                    "archive_hands" => config.set_archive_hands(value),
+                    _ => config.set_auto_brackets(value),
+                }
+            }
+            SettingKind::Delay => {
+                let current = config.bot_delay().as_millis() as u64;
+                let next = if forward {
+                    BOT_DELAYS_MS
+                        .into_iter()
+                        .find(|&ms| ms > current)
+                        .unwrap_or(BOT_DELAYS_MS[0])
+                } else {
+                    BOT_DELAYS_MS
+                        .into_iter()
+                        .rev()
+                        .find(|&ms| ms < current)
+                        .unwrap_or(BOT_DELAYS_MS[BOT_DELAYS_MS.len() - 1])
+                };
+                config.set_bot_delay(Duration::from_millis(next));
+            }
+            SettingKind::Color(suit) => {
+                let count = SUIT_COLORS.len();
+                let next = match SUIT_COLORS
+                    .iter()
+                    .position(|&color| color == config.suit_color(suit))
+                {
+                    Some(index) if forward => (index + 1) % count,
+                    Some(index) => (index + count - 1) % count,
+                    None => 0,
+                };
+                config.set_suit_color(suit, SUIT_COLORS[next]);
+            }
+        }
+        if !self.changed.contains(&setting.key) {
+            self.changed.push(setting.key);
+        }
+    }
+
+    /// Writes the changed settings to the config file at `path`.
+    pub fn save(&self, config: &Config, path: &Path) -> Result<()> {
+        let changes: Vec<(&str, toml_edit::Value)> = SETTINGS
+            .iter()
+            .filter(|setting| self.changed.contains(&setting.key))
+            .map(|setting| (setting.key, file_value(config, *setting)))
+            .collect();
+        config::write_settings(path, &changes)
+    }
+}
+
+/// How a setting's current value is shown on the settings screen.
+pub fn display_value(config: &Config, setting: Setting) -> String {
+    match setting.kind {
+        SettingKind::Toggle => {
+            if toggle_value(config, setting.key) {
+                "on".to_string()
+            } else {
+                "off".to_string()
+            }
+        }
+        SettingKind::Delay => match config.bot_delay().as_millis() {
+            0 => "instant".to_string(),
+            ms => format!("{ms} ms"),
+        },
+        SettingKind::Color(suit) => config.suit_color(suit).to_string(),
+    }
+}
+
+fn toggle_value(config: &Config, key: &str) -> bool {
+    match key {
+        "persist_stats" => config.persist_stats(),
+        "archive_hands" => config.archive_hands(),
+        _ => config.auto_brackets(),
+    }
+}
+
+/// The value written to the config file for a setting.
+fn file_value(config: &Config, setting: Setting) -> toml_edit::Value {
+    match setting.kind {
+        SettingKind::Toggle => toggle_value(config, setting.key).into(),
+        SettingKind::Delay => (config.bot_delay().as_millis() as i64).into(),
+        SettingKind::Color(suit) => config.suit_color(suit).to_string().into(),
+    }
+}
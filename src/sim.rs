@@ -0,0 +1,509 @@
+//! Headless bot-vs-bot play for comparing strategies.
+//!
+//! Each pairing plays hands in duplicate: every seeded deal is played twice with
+//! the contenders swapping seats, so neither side benefits from a lucky deal.
+//! The tuner reuses the same matches to hill-climb `BotParams` profiles.
+
+use std::fmt::Write as _;
+
+use anyhow::{anyhow, Result};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use serde::Serialize;
+
+use crate::{
+    bot::{take_turn, BotDifficulty, HeuristicStrategy, Strategy, StrategyRegistry},
+    game::{ActionOutcome, Game, PlayerId, RoundEndReason, TurnPhase},
+    params::{BotParams, KnockRule},
+};
+
+/// Hands that run this many turns without ending are abandoned as stalled.
+const MAX_TURNS: u32 = 500;
+const Z_95: f64 = 1.96;
+
+/// A strategy at a given difficulty, written `name:difficulty` on the command line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Contender {
+    pub strategy: String,
+    pub difficulty: BotDifficulty,
+}
+
+impl Contender {
+    pub fn new(strategy: &str, difficulty: BotDifficulty) -> Self {
+        Self {
+            strategy: strategy.to_string(),
+            difficulty,
+        }
+    }
+
+    pub fn parse(spec: &str) -> Result<Self> {
+        let (strategy, difficulty) = match spec.split_once(':') {
+            Some((strategy, difficulty)) => {
+                let difficulty = BotDifficulty::from_name(difficulty)
+                    .ok_or_else(|| anyhow!("unknown difficulty '{difficulty}' in '{spec}'"))?;
+                (strategy, difficulty)
+            }
+            None => (spec, BotDifficulty::default()),
+        };
+        if strategy.trim().is_empty() {
+            return Err(anyhow!("missing strategy name in '{spec}'"));
+        }
+        Ok(Self::new(strategy.trim(), difficulty))
+    }
+
+    pub fn label(&self) -> String {
+        format!("{}:{}", self.strategy, self.difficulty.name())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SimOptions {
+    /// Hands per pairing; each seeded deal accounts for two hands.
+    pub hands: u32,
+    pub seed: u64,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SideStats {
+    pub wins: u32,
+    pub points: i64,
+    pub knocks: u32,
+    pub gins: u32,
+    pub big_gins: u32,
+    /// Hands this side won by undercutting the other's knock.
+    pub undercuts: u32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PairingReport {
+    pub first: String,
+    pub second: String,
+    pub hands: u32,
+    pub draws: u32,
+    pub stalled: u32,
+    pub first_stats: SideStats,
+    pub second_stats: SideStats,
+    pub first_win_rate: f64,
+    pub first_win_rate_ci: (f64, f64),
+    /// Average of `first` points minus `second` points per hand.
+    pub points_margin: f64,
+    pub points_margin_ci: (f64, f64),
+    pub gin_rate: f64,
+    pub undercut_rate: f64,
+    pub draw_rate: f64,
+}
+
+/// Plays every pairing of `contenders` against each other.
+pub fn run(
+    registry: &StrategyRegistry,
+    contenders: &[Contender],
+    options: &SimOptions,
+) -> Result<Vec<PairingReport>> {
+    if contenders.len() < 2 {
+        return Err(anyhow!(
+            "at least two strategies are needed for a simulation"
+        ));
+    }
+    let mut reports = Vec::new();
+    for (i, first) in contenders.iter().enumerate() {
+        for second in &contenders[i + 1..] {
+            reports.push(run_pairing(registry, first, second, options)?);
+        }
+    }
+    Ok(reports)
+}
+
+pub fn run_pairing(
+    registry: &StrategyRegistry,
+    first: &Contender,
+    second: &Contender,
+    options: &SimOptions,
+) -> Result<PairingReport> {
+    let mut first_bot = registry.create(&first.strategy, first.difficulty)?;
+    let mut second_bot = registry.create(&second.strategy, second.difficulty)?;
+    play_match(
+        (first.label(), first_bot.as_mut()),
+        (second.label(), second_bot.as_mut()),
+        options,
+    )
+}
+
+/// Plays `options.hands` duplicate hands between two already-built bots.
+pub fn play_match(
+    (first_label, first_bot): (String, &mut dyn Strategy),
+    (second_label, second_bot): (String, &mut dyn Strategy),
+    options: &SimOptions,
+) -> Result<PairingReport> {
+    let mut first_stats = SideStats::default();
+    let mut second_stats = SideStats::default();
+    let mut draws = 0;
+    let mut stalled = 0;
+    let mut margins = Vec::with_capacity(options.hands as usize);
+
+    for hand in 0..options.hands {
+        let seed = deal_seed(options.seed, hand / 2);
+        let first_seat = if hand % 2 == 0 {
+            PlayerId::Human
+        } else {
+            PlayerId::Bot
+        };
+        let outcome = play_hand(seed, first_seat, &mut *first_bot, &mut *second_bot)?;
+
+        let Some(reason) = outcome else {
+            stalled += 1;
+            margins.push(0.0);
+            continue;
+        };
+        let (winner, points, reason) = reason;
+        match winner {
+            Some(seat) => {
+                let (stats, sign) = if seat == first_seat {
+                    (&mut first_stats, 1.0)
+                } else {
+                    (&mut second_stats, -1.0)
+                };
+                stats.wins += 1;
+                stats.points += points as i64;
+                margins.push(sign * points as f64);
+            }
+            None => {
+                draws += 1;
+                margins.push(0.0);
+            }
+        }
+        match reason {
+            RoundEndReason::Knock {
+                knocker,
+                gin,
+                undercut,
+                ..
+            } => {
+                let (knocker_stats, other_stats) = if knocker == first_seat {
+                    (&mut first_stats, &mut second_stats)
+                } else {
+                    (&mut second_stats, &mut first_stats)
+                };
+                knocker_stats.knocks += 1;
+                if gin {
+                    knocker_stats.gins += 1;
+                }
+                if undercut {
+                    other_stats.undercuts += 1;
+                }
+            }
+            RoundEndReason::BigGin { player, .. } => {
+                if player == first_seat {
+                    first_stats.big_gins += 1;
+                } else {
+                    second_stats.big_gins += 1;
+                }
+            }
+            RoundEndReason::StockDepleted => {}
+        }
+    }
+
+    let hands = options.hands;
+    let rate = |count: u32| {
+        if hands == 0 {
+            0.0
+        } else {
+            count as f64 / hands as f64
+        }
+    };
+    let (points_margin, points_margin_ci) = mean_with_ci(&margins);
+
+    Ok(PairingReport {
+        first: first_label,
+        second: second_label,
+        hands,
+        draws,
+        stalled,
+        first_win_rate: rate(first_stats.wins),
+        first_win_rate_ci: wilson_interval(first_stats.wins, hands),
+        points_margin,
+        points_margin_ci,
+        gin_rate: rate(first_stats.gins + second_stats.gins),
+        undercut_rate: rate(first_stats.undercuts + second_stats.undercuts),
+        draw_rate: rate(draws),
+        first_stats,
+        second_stats,
+    })
+}
+
+#[derive(Debug, Clone)]
+pub struct TuneOptions {
+    pub iterations: u32,
+    /// Hands played per candidate; every candidate sees the same deals.
+    pub hands: u32,
+    pub seed: u64,
+}
+
+/// One candidate tried by the tuner.
+#[derive(Debug, Clone)]
+pub struct TuneStep {
+    pub iteration: u32,
+    pub params: BotParams,
+    pub points_margin: f64,
+    pub win_rate: f64,
+    pub accepted: bool,
+}
+
+/// Hill-climbs `start` against `opponent`, keeping a mutation whenever it
+/// raises the average points margin per hand. Returns the best profile and
+/// its margin; `progress` sees every candidate.
+pub fn tune(
+    registry: &StrategyRegistry,
+    start: BotParams,
+    opponent: &Contender,
+    options: &TuneOptions,
+    mut progress: impl FnMut(&TuneStep),
+) -> Result<(BotParams, f64)> {
+    let sim_options = SimOptions {
+        hands: options.hands,
+        seed: options.seed,
+    };
+    let mut opponent_bot = registry.create(&opponent.strategy, opponent.difficulty)?;
+    let mut evaluate = |params: &BotParams| -> Result<PairingReport> {
+        let mut candidate = HeuristicStrategy::with_params(params.clone());
+        play_match(
+            (params.name.clone(), &mut candidate),
+            (opponent.label(), opponent_bot.as_mut()),
+            &sim_options,
+        )
+    };
+
+    let mut best = start.clamped();
+    let report = evaluate(&best)?;
+    let mut best_margin = report.points_margin;
+    progress(&TuneStep {
+        iteration: 0,
+        params: best.clone(),
+        points_margin: best_margin,
+        win_rate: report.first_win_rate,
+        accepted: true,
+    });
+
+    let mut rng = StdRng::seed_from_u64(options.seed ^ 0x7E57_AB1E_5EED_0001);
+    for iteration in 1..=options.iterations {
+        let candidate = mutate(&best, &mut rng);
+        let report = evaluate(&candidate)?;
+        let accepted = report.points_margin > best_margin;
+        progress(&TuneStep {
+            iteration,
+            params: candidate.clone(),
+            points_margin: report.points_margin,
+            win_rate: report.first_win_rate,
+            accepted,
+        });
+        if accepted {
+            best = candidate;
+            best_margin = report.points_margin;
+        }
+    }
+    Ok((best, best_margin))
+}
+
+/// Nudges one or two parameters of `params` within their useful ranges.
+fn mutate(params: &BotParams, rng: &mut StdRng) -> BotParams {
+    let mut next = params.clone();
+    let changes = rng.gen_range(1..=2);
+    for _ in 0..changes {
+        match rng.gen_range(0..10) {
+            0 => next.knock_threshold = step_u32(next.knock_threshold, 2, 0, 10, rng),
+            1 => {
+                next.random_discard_chance =
+                    (next.random_discard_chance + rng.gen_range(-0.05..0.05)).clamp(0.0, 0.3)
+            }
+            2 => next.take_discard_margin = rng.gen_range(-3..=3),
+            3 => {
+                next.discard_value_weight =
+                    (next.discard_value_weight + rng.gen_range(-0.05..0.05)).clamp(0.0, 0.5)
+            }
+            4 => {
+                next.late_round_discards =
+                    step_u32(next.late_round_discards as u32, 3, 0, 20, rng) as usize
+            }
+            5 => {
+                next.late_round_stock =
+                    step_u32(next.late_round_stock as u32, 3, 2, 31, rng) as usize
+            }
+            6 => next.strong_hand_deadwood = step_u32(next.strong_hand_deadwood, 2, 0, 10, rng),
+            7 => {
+                next.min_knock_points =
+                    (next.min_knock_points + rng.gen_range(-3.0..3.0)).clamp(-15.0, 15.0)
+            }
+            8 => next.replayed_draws = step_u32(next.replayed_draws as u32, 2, 0, 10, rng) as usize,
+            _ => {
+                next.knock_rule = match next.knock_rule {
+                    KnockRule::Classic => KnockRule::RiskAware,
+                    KnockRule::RiskAware => KnockRule::Classic,
+                }
+            }
+        }
+    }
+    next.clamped()
+}
+
+fn step_u32(value: u32, max_step: i32, low: u32, high: u32, rng: &mut StdRng) -> u32 {
+    let delta = rng.gen_range(-max_step..=max_step);
+    (value as i32 + delta).clamp(low as i32, high as i32) as u32
+}
+
+type HandResult = (Option<PlayerId>, i32, RoundEndReason);
+
+/// Plays one seeded hand to completion. Returns `None` if it stalls.
+fn play_hand<'a>(
+    seed: u64,
+    first_seat: PlayerId,
+    first: &'a mut dyn Strategy,
+    second: &'a mut dyn Strategy,
+) -> Result<Option<HandResult>> {
+    let mut game = Game::with_seed(seed)?;
+    let (human, bot) = if first_seat == PlayerId::Human {
+        (first, second)
+    } else {
+        (second, first)
+    };
+    human.on_round_start(&game, PlayerId::Human);
+    bot.on_round_start(&game, PlayerId::Bot);
+
+    let mut turns = 0;
+    while game.phase != TurnPhase::RoundOver {
+        if turns >= MAX_TURNS {
+            return Ok(None);
+        }
+        turns += 1;
+        let seat = game.current_player;
+        let strategy: &mut dyn Strategy = match seat {
+            PlayerId::Human => &mut *human,
+            PlayerId::Bot => &mut *bot,
+        };
+        if take_turn(&mut game, seat, strategy)? == ActionOutcome::RoundEnded {
+            break;
+        }
+    }
+
+    let result = game
+        .pending_round
+        .clone()
+        .ok_or_else(|| anyhow!("hand finished without a result"))?;
+    human.on_round_end(&game, PlayerId::Human, &result);
+    bot.on_round_end(&game, PlayerId::Bot, &result);
+    Ok(Some((result.winner, result.points_awarded, result.reason)))
+}
+
+/// Spreads deal indices across the seed space (SplitMix64 finaliser).
+fn deal_seed(base: u64, index: u32) -> u64 {
+    let mut z = base.wrapping_add((index as u64 + 1).wrapping_mul(0x9E37_79B9_7F4A_7C15));
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// 95% Wilson score interval for `successes` out of `trials`.
+pub fn wilson_interval(successes: u32, trials: u32) -> (f64, f64) {
+    if trials == 0 {
+        return (0.0, 0.0);
+    }
+    let n = trials as f64;
+    let p = successes as f64 / n;
+    let z2 = Z_95 * Z_95;
+    let denominator = 1.0 + z2 / n;
+    let centre = (p + z2 / (2.0 * n)) / denominator;
+    let spread = Z_95 * (p * (1.0 - p) / n + z2 / (4.0 * n * n)).sqrt() / denominator;
+    ((centre - spread).max(0.0), (centre + spread).min(1.0))
+}
+
+/// Sample mean with a normal-approximation 95% confidence interval.
+pub fn mean_with_ci(samples: &[f64]) -> (f64, (f64, f64)) {
+    if samples.is_empty() {
+        return (0.0, (0.0, 0.0));
+    }
+    let n = samples.len() as f64;
+    let mean = samples.iter().sum::<f64>() / n;
+    if samples.len() < 2 {
+        return (mean, (mean, mean));
+    }
+    let variance = samples.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (n - 1.0);
+    let half_width = Z_95 * (variance / n).sqrt();
+    (mean, (mean - half_width, mean + half_width))
+}
+
+pub fn format_table(reports: &[PairingReport]) -> String {
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        "{:<44} {:>6} {:>22} {:>22} {:>6} {:>10} {:>6}",
+        "Pairing (first vs second)",
+        "Hands",
+        "First win % (95% CI)",
+        "Pts/hand (95% CI)",
+        "Gin %",
+        "Undercut %",
+        "Draw %"
+    );
+    for report in reports {
+        let pairing = format!("{} vs {}", report.first, report.second);
+        let win = format!(
+            "{:.1} ({:.1}-{:.1})",
+            report.first_win_rate * 100.0,
+            report.first_win_rate_ci.0 * 100.0,
+            report.first_win_rate_ci.1 * 100.0
+        );
+        let points = format!(
+            "{:+.2} ({:+.2}..{:+.2})",
+            report.points_margin, report.points_margin_ci.0, report.points_margin_ci.1
+        );
+        let _ = writeln!(
+            out,
+            "{:<44} {:>6} {:>22} {:>22} {:>6.1} {:>10.1} {:>6.1}",
+            pairing,
+            report.hands,
+            win,
+            points,
+            report.gin_rate * 100.0,
+            report.undercut_rate * 100.0,
+            report.draw_rate * 100.0
+        );
+        if report.stalled > 0 {
+            let _ = writeln!(out, "  {} hands stalled and were scored 0.", report.stalled);
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn seeded_simulations_repeat_and_account_for_every_hand() {
+        let registry = StrategyRegistry::builtin();
+        let contenders = [
+            Contender::parse("heuristic:easy").unwrap(),
+            Contender::parse("heuristic-classic:easy").unwrap(),
+        ];
+        let options = SimOptions { hands: 40, seed: 9 };
+        let reports = run(&registry, &contenders, &options).unwrap();
+        let again = run(&registry, &contenders, &options).unwrap();
+        assert_eq!(
+            serde_json::to_string(&reports).unwrap(),
+            serde_json::to_string(&again).unwrap()
+        );
+
+        let [report] = &reports[..] else {
+            panic!("expected one pairing, got {}", reports.len());
+        };
+        assert_eq!(report.first, "heuristic:easy");
+        assert_eq!(report.second, "heuristic-classic:easy");
+        let (first, second) = (&report.first_stats, &report.second_stats);
+        assert_eq!(
+            first.wins + second.wins + report.draws + report.stalled,
+            options.hands
+        );
+        assert!(first.knocks + second.knocks + first.big_gins + second.big_gins > 0);
+        let margin = (first.points - second.points) as f64 / options.hands as f64;
+        assert!((report.points_margin - margin).abs() < 1e-9);
+        let (low, high) = report.first_win_rate_ci;
+        assert!(low <= report.first_win_rate && report.first_win_rate <= high);
+    }
+}
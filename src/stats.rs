@@ -0,0 +1,333 @@
+//! Aggregate statistics over archived hands, from the human player's side.
+
+use std::collections::BTreeMap;
+
+use crate::{
+    archive::{HandRecord, MoveRecord, OutcomeKind},
+    bot::BotDifficulty,
+    game::PlayerId,
+};
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct HandStats {
+    pub hands: u32,
+    pub wins: u32,
+    pub losses: u32,
+    pub draws: u32,
+    /// Hands you won by going Gin (including Big Gin).
+    pub gins: u32,
+    /// Times you undercut the bot's knock.
+    pub undercuts_given: u32,
+    /// Times the bot undercut your knock.
+    pub undercuts_received: u32,
+    /// Times you knocked (Gin included).
+    pub knocks: u32,
+    knock_deadwood: u32,
+    turns: u32,
+    points_for: i64,
+    points_against: i64,
+    pub longest_win_streak: u32,
+    current_streak: u32,
+}
+
+/// Totals across every archived hand plus one column per difficulty.
+#[derive(Debug, Clone, Default)]
+pub struct StatsReport {
+    pub overall: HandStats,
+    pub by_difficulty: BTreeMap<BotDifficulty, HandStats>,
+}
+
+impl StatsReport {
+    /// Builds the report from records in the order they were played.
+    pub fn from_records<'a>(records: impl IntoIterator<Item = &'a HandRecord>) -> Self {
+        let mut report = Self::default();
+        for record in records {
+            report.overall.add(record);
+            if let Some(difficulty) = record.difficulty {
+                report
+                    .by_difficulty
+                    .entry(difficulty)
+                    .or_default()
+                    .add(record);
+            }
+        }
+        report
+    }
+
+    /// The report as text, one row per statistic and a column for the totals
+    /// and each difficulty. The first line is the column header.
+    pub fn table(&self) -> Vec<String> {
+        let mut columns: Vec<(&str, &HandStats)> = vec![("Overall", &self.overall)];
+        columns.extend(
+            self.by_difficulty
+                .iter()
+                .map(|(difficulty, stats)| (difficulty.label(), stats)),
+        );
+
+        let mut header = format!("{:<26}", "");
+        for (label, _) in &columns {
+            header.push_str(&format!("{label:>13}"));
+        }
+        let mut lines = vec![header];
+
+        let rows: [StatRow; 10] = [
+            ("Hands played", |s| s.hands.to_string()),
+            ("Won / lost / drawn", |s| {
+                format!("{}/{}/{}", s.wins, s.losses, s.draws)
+            }),
+            ("Win %", |s| format_rate(s.win_rate())),
+            ("Gin rate", |s| format_rate(s.gin_rate())),
+            ("Undercuts given", |s| s.undercuts_given.to_string()),
+            ("Undercuts received", |s| s.undercuts_received.to_string()),
+            ("Avg deadwood at knock", |s| {
+                format_average(s.average_knock_deadwood())
+            }),
+            ("Avg hand length (turns)", |s| {
+                format_average(s.average_turns())
+            }),
+            ("Points per hand", |s| {
+                s.points_per_hand()
+                    .map_or("-".to_string(), |value| format!("{value:+.1}"))
+            }),
+            ("Longest win streak", |s| s.longest_win_streak.to_string()),
+        ];
+        for (label, value) in rows {
+            let mut line = format!("{label:<26}");
+            for (_, stats) in &columns {
+                line.push_str(&format!("{:>13}", value(stats)));
+            }
+            lines.push(line);
+        }
+        lines
+    }
+}
+
+/// A row label and how to compute its value for one column.
+type StatRow = (&'static str, fn(&HandStats) -> String);
+
+fn format_rate(rate: Option<f64>) -> String {
+    rate.map_or("-".to_string(), |rate| format!("{:.1}%", rate * 100.0))
+}
+
+fn format_average(value: Option<f64>) -> String {
+    value.map_or("-".to_string(), |value| format!("{value:.1}"))
+}
+
+impl HandStats {
+    pub fn add(&mut self, record: &HandRecord) {
+        let outcome = &record.outcome;
+        self.hands += 1;
+        self.turns += record
+            .moves
+            .iter()
+            .filter(|mv| matches!(mv, MoveRecord::Discard { .. }))
+            .count() as u32;
+
+        match outcome.winner {
+            Some(PlayerId::Human) => {
+                self.wins += 1;
+                self.points_for += i64::from(outcome.points);
+                self.current_streak += 1;
+                self.longest_win_streak = self.longest_win_streak.max(self.current_streak);
+            }
+            Some(PlayerId::Bot) => {
+                self.losses += 1;
+                self.points_against += i64::from(outcome.points);
+                self.current_streak = 0;
+            }
+            None => {
+                self.draws += 1;
+                self.current_streak = 0;
+            }
+        }
+
+        if outcome.gin && outcome.knocker == Some(PlayerId::Human) {
+            self.gins += 1;
+        }
+        if outcome.kind == OutcomeKind::Knock {
+            match outcome.knocker {
+                Some(PlayerId::Human) => {
+                    self.knocks += 1;
+                    self.knock_deadwood += outcome.knocker_deadwood;
+                    if outcome.undercut {
+                        self.undercuts_received += 1;
+                    }
+                }
+                Some(PlayerId::Bot) if outcome.undercut => self.undercuts_given += 1,
+                _ => {}
+            }
+        }
+    }
+
+    pub fn win_rate(&self) -> Option<f64> {
+        self.per_hand(f64::from(self.wins))
+    }
+
+    pub fn gin_rate(&self) -> Option<f64> {
+        self.per_hand(f64::from(self.gins))
+    }
+
+    /// Average deadwood you knocked with.
+    pub fn average_knock_deadwood(&self) -> Option<f64> {
+        (self.knocks > 0).then(|| f64::from(self.knock_deadwood) / f64::from(self.knocks))
+    }
+
+    /// Average turns per hand, counting both players.
+    pub fn average_turns(&self) -> Option<f64> {
+        self.per_hand(f64::from(self.turns))
+    }
+
+    /// Net points per hand: what you scored minus what the bot scored.
+    pub fn points_per_hand(&self) -> Option<f64> {
+        self.per_hand((self.points_for - self.points_against) as f64)
+    }
+
+    fn per_hand(&self, total: f64) -> Option<f64> {
+        (self.hands > 0).then(|| total / f64::from(self.hands))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::archive::{DealRecord, HandAnalysis, OutcomeRecord, ARCHIVE_VERSION};
+
+    fn record(
+        difficulty: Option<BotDifficulty>,
+        turns: usize,
+        outcome: OutcomeRecord,
+    ) -> HandRecord {
+        let shown = HandAnalysis {
+            cards: Vec::new(),
+            melds: Vec::new(),
+            deadwood: Vec::new(),
+            deadwood_value: 0,
+        };
+        let discard = MoveRecord::Discard {
+            player: PlayerId::Human,
+            card: "2C".parse().unwrap(),
+            knock: false,
+        };
+        HandRecord {
+            version: ARCHIVE_VERSION,
+            timestamp: 0,
+            seed: 0,
+            difficulty,
+            bot: "heuristic".to_string(),
+            hints_used: 0,
+            rules: "standard".to_string(),
+            deal: DealRecord {
+                dealer: PlayerId::Bot,
+                human: Vec::new(),
+                bot: Vec::new(),
+                upcard: None,
+            },
+            moves: vec![discard; turns],
+            outcome,
+            human: shown.clone(),
+            bot_hand: shown,
+        }
+    }
+
+    /// A knock by `knocker` on `deadwood`, won by `winner` for `points`.
+    fn knock(knocker: PlayerId, deadwood: u32, winner: PlayerId, points: i32) -> OutcomeRecord {
+        OutcomeRecord {
+            kind: OutcomeKind::Knock,
+            winner: Some(winner),
+            points,
+            knocker: Some(knocker),
+            knocker_deadwood: deadwood,
+            opponent_deadwood: 0,
+            gin: deadwood == 0,
+            undercut: winner != knocker,
+            laid_off: Vec::new(),
+        }
+    }
+
+    fn drawn() -> OutcomeRecord {
+        OutcomeRecord {
+            kind: OutcomeKind::StockDepleted,
+            winner: None,
+            points: 0,
+            knocker: None,
+            knocker_deadwood: 0,
+            opponent_deadwood: 0,
+            gin: false,
+            undercut: false,
+            laid_off: Vec::new(),
+        }
+    }
+
+    fn report() -> StatsReport {
+        use BotDifficulty::{Challenging, Easy};
+        use PlayerId::{Bot, Human};
+        let records = [
+            record(Some(Challenging), 6, knock(Human, 7, Human, 15)),
+            record(Some(Challenging), 8, knock(Human, 0, Human, 31)),
+            record(Some(Easy), 10, knock(Bot, 6, Human, 27)),
+            record(Some(Challenging), 4, knock(Human, 9, Bot, 28)),
+            record(Some(Easy), 2, knock(Human, 5, Human, 10)),
+            record(None, 20, drawn()),
+        ];
+        StatsReport::from_records(&records)
+    }
+
+    #[test]
+    fn totals_cover_every_kind_of_ending() {
+        let overall = report().overall;
+        assert_eq!(
+            (overall.hands, overall.wins, overall.losses, overall.draws),
+            (6, 4, 1, 1)
+        );
+        assert_eq!(overall.gins, 1);
+        assert_eq!(overall.undercuts_given, 1);
+        assert_eq!(overall.undercuts_received, 1);
+        assert_eq!(overall.knocks, 4);
+        assert_eq!(overall.win_rate(), Some(4.0 / 6.0));
+        assert_eq!(overall.gin_rate(), Some(1.0 / 6.0));
+        assert_eq!(overall.average_knock_deadwood(), Some(21.0 / 4.0));
+        assert_eq!(overall.average_turns(), Some(50.0 / 6.0));
+        assert_eq!(overall.points_per_hand(), Some((83.0 - 28.0) / 6.0));
+        // Three wins, then a loss breaks the streak.
+        assert_eq!(overall.longest_win_streak, 3);
+    }
+
+    #[test]
+    fn each_difficulty_gets_its_own_column() {
+        let report = report();
+        let columns: Vec<_> = report.by_difficulty.keys().copied().collect();
+        assert_eq!(columns, [BotDifficulty::Easy, BotDifficulty::Challenging]);
+
+        let easy = &report.by_difficulty[&BotDifficulty::Easy];
+        assert_eq!((easy.hands, easy.wins, easy.longest_win_streak), (2, 2, 2));
+        assert_eq!((easy.undercuts_given, easy.knocks), (1, 1));
+        assert_eq!(easy.average_knock_deadwood(), Some(5.0));
+
+        let challenging = &report.by_difficulty[&BotDifficulty::Challenging];
+        assert_eq!(
+            (challenging.hands, challenging.wins, challenging.losses),
+            (3, 2, 1)
+        );
+        assert_eq!(challenging.undercuts_received, 1);
+        assert_eq!(challenging.longest_win_streak, 2);
+        assert_eq!(challenging.points_per_hand(), Some((46.0 - 28.0) / 3.0));
+    }
+
+    #[test]
+    fn the_table_has_a_column_per_difficulty() {
+        let lines = report().table();
+        assert_eq!(lines.len(), 11);
+        let header = lines[0].split_whitespace().collect::<Vec<_>>();
+        assert_eq!(header, ["Overall", "Easy", "Challenging"]);
+        assert!(lines[3].starts_with("Win %"));
+        assert!(
+            lines[3].ends_with("66.7%       100.0%        66.7%"),
+            "{}",
+            lines[3]
+        );
+
+        let empty = StatsReport::default().table();
+        assert!(empty[3].ends_with(" -"), "{}", empty[3]);
+        assert_eq!(empty[0].split_whitespace().collect::<Vec<_>>(), ["Overall"]);
+    }
+}
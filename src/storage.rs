@@ -1,43 +1,209 @@
 use std::{
+    collections::BTreeMap,
     env, fs,
+    io::Write,
     path::{Path, PathBuf},
 };
 
-use anyhow::{Context, Result};
+use anyhow::{anyhow, bail, Context, Result};
 use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
 
-use crate::game::Scoreboard;
+use crate::{bot::BotDifficulty, game::Scoreboard};
+
+/// Profile used when none is chosen, and the one a pre-profile install migrates into.
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// Current `session.json` schema version.
+pub const SESSION_VERSION: u32 = 2;
+
+/// Previous good copies of the session kept beside it.
+const SESSION_BACKUPS: usize = 3;
+
+/// Per-player files that live in each profile directory.
+const PROFILE_FILES: [&str; 2] = ["session.json", "hands.jsonl"];
+
+const PROFILE_CONFIG_HEADER: &str = "\
+# Preferences for this profile only. Any key from the main config.toml set here
+# overrides it when playing as this profile.
+";
 
 #[derive(Debug, Clone)]
 pub struct Paths {
     config_file: PathBuf,
+    bots_dir: PathBuf,
+    puzzles_dir: PathBuf,
+    daily_file: PathBuf,
+    profile: String,
+    profile_config_file: PathBuf,
     session_file: PathBuf,
+    archive_file: PathBuf,
+    exports_dir: PathBuf,
+    puzzle_progress_file: PathBuf,
 }
 
 impl Paths {
-    pub fn new() -> Result<Self> {
-        let root = resolve_app_root()?;
-        let config_file = root.join("config.toml");
-        let session_file = root.join("session.json");
-        Ok(Self {
-            config_file,
-            session_file,
-        })
+    /// Paths for `profile` under `data_dir` (the OS config directory when
+    /// unset), creating its directory if it is new. Files left in the root by
+    /// versions without profiles are first moved into the default profile.
+    pub fn new(profile: &str, data_dir: Option<&Path>) -> Result<Self> {
+        let profile = validate_profile_name(profile)?;
+        let root = resolve_app_root(data_dir)?;
+        migrate_legacy_layout(&root)?;
+        let profile_dir = root.join("profiles").join(&profile);
+        fs::create_dir_all(&profile_dir).with_context(|| {
+            format!(
+                "failed to create profile directory at {}",
+                profile_dir.display()
+            )
+        })?;
+        let profile_config_file = profile_dir.join("config.toml");
+        if !profile_config_file.exists() {
+            fs::write(&profile_config_file, PROFILE_CONFIG_HEADER).with_context(|| {
+                format!(
+                    "failed to write profile config to {}",
+                    profile_config_file.display()
+                )
+            })?;
+        }
+        // Remembering the last profile is a convenience; failing to is not an error.
+        let _ = fs::write(root.join("last_profile"), &profile);
+        Ok(Self::at(&root, profile))
+    }
+
+    /// Paths for `profile` without creating or moving anything, for commands
+    /// that only read. The files need not exist.
+    pub fn existing(profile: &str, data_dir: Option<&Path>) -> Result<Self> {
+        let profile = validate_profile_name(profile)?;
+        Ok(Self::at(&app_root(data_dir)?, profile))
+    }
+
+    /// The profile used last, if it still exists, read without touching the disk.
+    pub fn last_profile(data_dir: Option<&Path>) -> Result<Option<String>> {
+        let root = app_root(data_dir)?;
+        Ok(fs::read_to_string(root.join("last_profile"))
+            .ok()
+            .map(|name| name.trim().to_string())
+            .filter(|name| validate_profile_name(name).is_ok())
+            .filter(|name| root.join("profiles").join(name).is_dir()))
     }
 
+    fn at(root: &Path, profile: String) -> Self {
+        let profile_dir = root.join("profiles").join(&profile);
+        Self {
+            config_file: root.join("config.toml"),
+            bots_dir: root.join("bots"),
+            puzzles_dir: root.join("puzzles"),
+            daily_file: root.join("daily.json"),
+            profile_config_file: profile_dir.join("config.toml"),
+            session_file: profile_dir.join("session.json"),
+            archive_file: profile_dir.join("hands.jsonl"),
+            exports_dir: profile_dir.join("exports"),
+            puzzle_progress_file: profile_dir.join("puzzles.json"),
+            profile,
+        }
+    }
+
+    /// Names of every existing profile, sorted, plus the profile used last.
+    /// Only reads, so nothing is created until a profile is chosen; files
+    /// from before profiles existed count as the default profile, which
+    /// [`Paths::new`] moves them into.
+    pub fn profiles(data_dir: Option<&Path>) -> Result<(Vec<String>, Option<String>)> {
+        let root = app_root(data_dir)?;
+        let profiles_dir = root.join("profiles");
+        let mut names: Vec<String> = match fs::read_dir(&profiles_dir) {
+            Ok(entries) => entries
+                .filter_map(|entry| entry.ok())
+                .filter(|entry| entry.path().is_dir())
+                .filter_map(|entry| entry.file_name().into_string().ok())
+                .filter(|name| validate_profile_name(name).is_ok())
+                .collect(),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(err) => {
+                return Err(err).with_context(|| {
+                    format!("failed to list profiles in {}", profiles_dir.display())
+                })
+            }
+        };
+        let legacy = PROFILE_FILES.iter().any(|file| root.join(file).exists());
+        if legacy && !names.iter().any(|name| name == DEFAULT_PROFILE) {
+            names.push(DEFAULT_PROFILE.to_string());
+        }
+        names.sort();
+        let last = fs::read_to_string(root.join("last_profile"))
+            .ok()
+            .map(|name| name.trim().to_string())
+            .filter(|name| names.contains(name));
+        Ok((names, last))
+    }
+
+    /// Reads the shared settings from `path` instead of the data directory.
+    pub fn with_config_file(mut self, path: PathBuf) -> Self {
+        self.config_file = path;
+        self
+    }
+
+    /// Shared settings for every profile.
     pub fn config_file(&self) -> &Path {
         &self.config_file
     }
 
+    pub fn profile(&self) -> &str {
+        &self.profile
+    }
+
+    /// Per-profile settings layered over the shared config file.
+    pub fn profile_config_file(&self) -> &Path {
+        &self.profile_config_file
+    }
+
     pub fn session_file(&self) -> &Path {
         &self.session_file
     }
+
+    /// Append-only log of every completed hand (see `archive`).
+    pub fn archive_file(&self) -> &Path {
+        &self.archive_file
+    }
+
+    /// Daily deal leaderboard shared by every profile.
+    pub fn daily_file(&self) -> &Path {
+        &self.daily_file
+    }
+
+    /// Where hands exported in the text notation are written.
+    pub fn exports_dir(&self) -> &Path {
+        &self.exports_dir
+    }
+
+    /// Directory of bot personality profiles (`*.toml`).
+    pub fn bots_dir(&self) -> &Path {
+        &self.bots_dir
+    }
+
+    /// Directory of puzzle files (`*.toml` or `*.json`) shared by every profile.
+    pub fn puzzles_dir(&self) -> &Path {
+        &self.puzzles_dir
+    }
+
+    /// This profile's puzzle attempts and solves.
+    pub fn puzzle_progress_file(&self) -> &Path {
+        &self.puzzle_progress_file
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct SessionData {
+    /// Schema version; files from before versioning count as 1.
+    #[serde(default)]
+    pub version: u32,
+    /// Totals for the difficulty that was active when the session was saved.
     pub scoreboard: Scoreboard,
     #[serde(default)]
+    pub difficulty: Option<BotDifficulty>,
+    #[serde(default)]
+    pub scoreboards: BTreeMap<BotDifficulty, Scoreboard>,
+    #[serde(default)]
     pub round_history: Vec<RoundSummary>,
 }
 
@@ -45,51 +211,512 @@ pub struct SessionData {
 pub struct RoundSummary {
     pub round_number: u32,
     pub description: String,
+    #[serde(default)]
+    pub difficulty: Option<BotDifficulty>,
+    #[serde(default)]
+    pub hints_used: u32,
 }
 
 impl SessionData {
-    pub fn new(scoreboard: Scoreboard, round_history: Vec<RoundSummary>) -> Self {
+    pub fn new(
+        difficulty: BotDifficulty,
+        scoreboards: BTreeMap<BotDifficulty, Scoreboard>,
+        round_history: Vec<RoundSummary>,
+    ) -> Self {
         Self {
-            scoreboard,
+            version: SESSION_VERSION,
+            scoreboard: scoreboards.get(&difficulty).cloned().unwrap_or_default(),
+            difficulty: Some(difficulty),
+            scoreboards,
             round_history,
         }
     }
+
+    /// Per-difficulty totals. Sessions saved before difficulties were tracked
+    /// have their single `scoreboard` moved into this map by the session
+    /// migration as they are read, so nothing is filled in here.
+    pub fn scoreboards_by_difficulty(&self) -> BTreeMap<BotDifficulty, Scoreboard> {
+        self.scoreboards.clone()
+    }
 }
 
-pub fn load_session(path: &Path) -> Result<Option<SessionData>> {
-    if !path.exists() {
-        return Ok(None);
+/// A session read back from disk, with notes about anything that had to be
+/// recovered on the way.
+#[derive(Debug)]
+pub struct LoadedSession {
+    pub data: SessionData,
+    pub warnings: Vec<String>,
+}
+
+/// Loads the session, falling back to the newest readable backup when the
+/// session file itself is missing or damaged. Returns `None` when there is
+/// nothing saved yet, and an error only when every copy is unreadable.
+pub fn load_session(path: &Path) -> Result<Option<LoadedSession>> {
+    let mut failures = Vec::new();
+    let candidates = std::iter::once(path.to_path_buf()).chain(backup_paths(path));
+    for candidate in candidates {
+        if !candidate.exists() {
+            continue;
+        }
+        match read_session(&candidate) {
+            Ok(data) => {
+                let warnings = if candidate == path {
+                    Vec::new()
+                } else {
+                    let reason = if failures.is_empty() {
+                        format!("{} was missing", path.display())
+                    } else {
+                        failures.join("; ")
+                    };
+                    vec![format!(
+                        "Recovered session data from backup {} ({reason}).",
+                        candidate.display()
+                    )]
+                };
+                return Ok(Some(LoadedSession { data, warnings }));
+            }
+            Err(err) => failures.push(format!("{err:#}")),
+        }
+    }
+    if failures.is_empty() {
+        Ok(None)
+    } else {
+        bail!("{}", failures.join("; "))
     }
-    let contents = fs::read_to_string(path)
-        .with_context(|| format!("failed to read session data at {}", path.display()))?;
-    let session = serde_json::from_str::<SessionData>(&contents)
-        .with_context(|| format!("failed to parse session data at {}", path.display()))?;
-    Ok(Some(session))
 }
 
-pub fn save_session(path: &Path, data: &SessionData) -> Result<()> {
+/// Saves the session atomically. With `rotate`, the previous good copy is
+/// first moved into the backups; pass it only on a run's first save, so the
+/// backups keep the sessions of earlier runs rather than near-copies of this one.
+pub fn save_session(path: &Path, data: &SessionData, rotate: bool) -> Result<()> {
     if let Some(parent) = path.parent() {
         fs::create_dir_all(parent).with_context(|| {
             format!("failed to ensure session directory at {}", parent.display())
         })?;
     }
+    let mut data = data.clone();
+    data.version = SESSION_VERSION;
     let contents =
-        serde_json::to_string_pretty(data).context("failed to serialise session data")?;
-    fs::write(path, contents)
+        serde_json::to_string_pretty(&data).context("failed to serialise session data")?;
+    // Only rotate a readable session in, so a damaged file never pushes out a good backup.
+    if rotate && read_session(path).is_ok() {
+        rotate_backups(path)?;
+    }
+    write_atomic(path, contents.as_bytes())
         .with_context(|| format!("failed to write session data to {}", path.display()))
 }
 
-fn resolve_app_root() -> Result<PathBuf> {
+/// Writes `contents` to a temporary file beside `path`, flushes it to disk
+/// and renames it over `path`, so readers see either the old or the new file.
+/// The directory is flushed too, so the rename itself survives a crash.
+pub fn write_atomic(path: &Path, contents: &[u8]) -> Result<()> {
+    let mut tmp_name = path.file_name().unwrap_or_default().to_os_string();
+    tmp_name.push(".tmp");
+    let tmp_path = path.with_file_name(tmp_name);
+    let mut file = fs::File::create(&tmp_path)
+        .with_context(|| format!("failed to create {}", tmp_path.display()))?;
+    file.write_all(contents)
+        .and_then(|_| file.sync_all())
+        .with_context(|| format!("failed to write {}", tmp_path.display()))?;
+    fs::rename(&tmp_path, path).with_context(|| format!("failed to replace {}", path.display()))?;
+    sync_parent_dir(path)
+}
+
+/// Where [`set_aside`] moves `path`: `<name>.unreadable`, or `<name>.unreadable.2`
+/// and so on while earlier copies are still there.
+pub fn aside_path(path: &Path) -> PathBuf {
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    (1..)
+        .map(|copy| match copy {
+            1 => path.with_file_name(format!("{name}.unreadable")),
+            copy => path.with_file_name(format!("{name}.unreadable.{copy}")),
+        })
+        .find(|candidate| !candidate.exists())
+        .expect("some copy number is free")
+}
+
+/// Moves a data file that could not be read out of the way, so saving a fresh
+/// one does not destroy whatever it held.
+pub fn set_aside(path: &Path) -> Result<PathBuf> {
+    let aside = aside_path(path);
+    fs::rename(path, &aside)
+        .with_context(|| format!("failed to move {} to {}", path.display(), aside.display()))?;
+    Ok(aside)
+}
+
+#[cfg(unix)]
+fn sync_parent_dir(path: &Path) -> Result<()> {
+    let dir = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    fs::File::open(dir)
+        .and_then(|dir| dir.sync_all())
+        .with_context(|| format!("failed to flush directory {}", dir.display()))
+}
+
+/// Directories cannot be opened as files here; the rename is as durable as
+/// the platform makes it.
+#[cfg(not(unix))]
+fn sync_parent_dir(_path: &Path) -> Result<()> {
+    Ok(())
+}
+
+fn read_session(path: &Path) -> Result<SessionData> {
+    let contents = fs::read_to_string(path)
+        .with_context(|| format!("failed to read session data at {}", path.display()))?;
+    let value: Value = serde_json::from_str(&contents)
+        .with_context(|| format!("failed to parse session data at {}", path.display()))?;
+    let value = migrate_session(value)
+        .with_context(|| format!("failed to upgrade session data at {}", path.display()))?;
+    serde_json::from_value(value)
+        .with_context(|| format!("failed to parse session data at {}", path.display()))
+}
+
+/// `session.json.bak1` (newest) to `session.json.bakN` (oldest).
+fn backup_paths(path: &Path) -> impl Iterator<Item = PathBuf> + '_ {
+    (1..=SESSION_BACKUPS).map(move |index| {
+        let mut name = path.file_name().unwrap_or_default().to_os_string();
+        name.push(format!(".bak{index}"));
+        path.with_file_name(name)
+    })
+}
+
+fn rotate_backups(path: &Path) -> Result<()> {
+    let backups: Vec<PathBuf> = backup_paths(path).collect();
+    for pair in backups.windows(2).rev() {
+        if pair[0].exists() {
+            fs::rename(&pair[0], &pair[1])
+                .with_context(|| format!("failed to rotate backup {}", pair[0].display()))?;
+        }
+    }
+    fs::copy(path, &backups[0]).with_context(|| format!("failed to back up {}", path.display()))?;
+    Ok(())
+}
+
+/// Upgrades a session of any earlier schema version to [`SESSION_VERSION`],
+/// one version at a time.
+fn migrate_session(mut value: Value) -> Result<Value> {
+    let mut version = value
+        .get("version")
+        .and_then(Value::as_u64)
+        .map_or(1, |version| version as u32);
+    if version > SESSION_VERSION {
+        bail!(
+            "written by a newer version of Deadwood (session version {version}, this build reads {SESSION_VERSION})"
+        );
+    }
+    while version < SESSION_VERSION {
+        value = match version {
+            1 => migrate_v1_to_v2(value)?,
+            other => bail!("no migration from session version {other}"),
+        };
+        version += 1;
+    }
+    Ok(value)
+}
+
+/// Version 1 files were unversioned. Early ones kept a single scoreboard
+/// (always played on Challenging) and no per-hand counts.
+fn migrate_v1_to_v2(mut value: Value) -> Result<Value> {
+    let object = value
+        .as_object_mut()
+        .ok_or_else(|| anyhow!("session data is not a JSON object"))?;
+    let mut scoreboard = object
+        .remove("scoreboard")
+        .unwrap_or_else(|| Value::Object(Map::new()));
+    fill_scoreboard_counts(&mut scoreboard);
+    let mut scoreboards = match object.remove("scoreboards") {
+        Some(Value::Object(map)) => map,
+        _ => Map::new(),
+    };
+    for board in scoreboards.values_mut() {
+        fill_scoreboard_counts(board);
+    }
+    let played = scoreboard
+        .get("rounds_played")
+        .and_then(Value::as_u64)
+        .unwrap_or(0);
+    if scoreboards.is_empty() && played > 0 {
+        let difficulty = object
+            .get("difficulty")
+            .and_then(Value::as_str)
+            .unwrap_or("challenging")
+            .to_string();
+        scoreboards.insert(difficulty, scoreboard.clone());
+    }
+    object.insert("scoreboard".to_string(), scoreboard);
+    object.insert("scoreboards".to_string(), Value::Object(scoreboards));
+    object.insert("version".to_string(), Value::from(2));
+    Ok(value)
+}
+
+fn fill_scoreboard_counts(scoreboard: &mut Value) {
+    if let Some(board) = scoreboard.as_object_mut() {
+        for key in [
+            "human",
+            "bot",
+            "rounds_played",
+            "human_hands_won",
+            "bot_hands_won",
+            "draws",
+        ] {
+            board.entry(key).or_insert(Value::from(0));
+        }
+    }
+}
+
+/// Normalises a profile name, rejecting anything unsafe as a directory name.
+pub fn validate_profile_name(name: &str) -> Result<String> {
+    let name = name.trim().to_ascii_lowercase();
+    if name.is_empty() || name.len() > 32 {
+        bail!("profile names must be 1-32 characters long");
+    }
+    if !name
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        bail!("profile name '{name}' may only use letters, digits, '-' and '_'");
+    }
+    Ok(name)
+}
+
+/// Moves a session and archive saved before profiles existed into the
+/// default profile, and makes sure that profile exists.
+fn migrate_legacy_layout(root: &Path) -> Result<()> {
+    let default_dir = root.join("profiles").join(DEFAULT_PROFILE);
+    fs::create_dir_all(&default_dir).with_context(|| {
+        format!(
+            "failed to create profile directory at {}",
+            default_dir.display()
+        )
+    })?;
+    for file in PROFILE_FILES {
+        let legacy = root.join(file);
+        let target = default_dir.join(file);
+        if legacy.exists() && !target.exists() {
+            fs::rename(&legacy, &target).with_context(|| {
+                format!(
+                    "failed to move {} into the default profile",
+                    legacy.display()
+                )
+            })?;
+        }
+    }
+    Ok(())
+}
+
+/// The data directory, created if it is missing.
+fn resolve_app_root(data_dir: Option<&Path>) -> Result<PathBuf> {
+    let dir = app_root(data_dir)?;
+    fs::create_dir_all(&dir)
+        .with_context(|| format!("failed to create data directory at {}", dir.display()))?;
+    Ok(dir)
+}
+
+/// `--data-dir`, else `deadwood` in the OS config directory, else `.deadwood`
+/// in the current directory.
+fn app_root(data_dir: Option<&Path>) -> Result<PathBuf> {
+    if let Some(dir) = data_dir {
+        return Ok(dir.to_path_buf());
+    }
     if let Some(mut dir) = dirs::config_dir() {
         dir.push("deadwood");
-        fs::create_dir_all(&dir)
-            .with_context(|| format!("failed to create config directory at {}", dir.display()))?;
         return Ok(dir);
     }
-
     let mut dir = env::current_dir().context("failed to resolve current directory")?;
     dir.push(".deadwood");
-    fs::create_dir_all(&dir)
-        .with_context(|| format!("failed to create fallback directory at {}", dir.display()))?;
     Ok(dir)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session(rounds_played: u32) -> SessionData {
+        let board = Scoreboard {
+            rounds_played,
+            ..Scoreboard::default()
+        };
+        let scoreboards = BTreeMap::from([(BotDifficulty::Easy, board)]);
+        SessionData::new(BotDifficulty::Easy, scoreboards, Vec::new())
+    }
+
+    fn rounds_played(path: &Path) -> u32 {
+        read_session(path).unwrap().scoreboard.rounds_played
+    }
+
+    #[test]
+    fn unversioned_sessions_migrate_to_per_difficulty_scoreboards() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("session.json");
+        fs::write(
+            &path,
+            r#"{"scoreboard": {"human": 41, "bot": 12, "rounds_played": 3}}"#,
+        )
+        .unwrap();
+
+        let data = read_session(&path).unwrap();
+        assert_eq!(data.version, SESSION_VERSION);
+        assert_eq!(data.scoreboard.human, 41);
+        assert_eq!(data.scoreboard.human_hands_won, 0);
+        let board = &data.scoreboards[&BotDifficulty::Challenging];
+        assert_eq!((board.human, board.bot, board.rounds_played), (41, 12, 3));
+        assert_eq!(data.scoreboards.len(), 1);
+    }
+
+    #[test]
+    fn unversioned_sessions_without_rounds_get_no_scoreboards() {
+        let value = serde_json::json!({"scoreboard": {"human": 0, "bot": 0}});
+        let value = migrate_session(value).unwrap();
+        let data: SessionData = serde_json::from_value(value).unwrap();
+        assert!(data.scoreboards.is_empty());
+        assert_eq!(data.scoreboard.rounds_played, 0);
+    }
+
+    #[test]
+    fn newer_sessions_are_rejected() {
+        let value = serde_json::json!({"version": SESSION_VERSION + 1, "scoreboard": {}});
+        let err = migrate_session(value).unwrap_err();
+        assert!(err.to_string().contains("newer version"), "{err}");
+    }
+
+    #[test]
+    fn corrupt_session_is_recovered_from_newest_readable_backup() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("session.json");
+        for rounds in 1..=3 {
+            save_session(&path, &session(rounds), true).unwrap();
+        }
+        fs::write(&path, "{ not json").unwrap();
+        let backups: Vec<PathBuf> = backup_paths(&path).collect();
+        fs::write(&backups[0], "").unwrap();
+
+        let loaded = load_session(&path).unwrap().unwrap();
+        assert_eq!(loaded.data.scoreboard.rounds_played, 1);
+        assert_eq!(loaded.warnings.len(), 1);
+        assert!(
+            loaded.warnings[0].contains("session.json.bak2"),
+            "{:?}",
+            loaded.warnings
+        );
+    }
+
+    #[test]
+    fn missing_session_is_none_and_unreadable_session_is_an_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("session.json");
+        assert!(load_session(&path).unwrap().is_none());
+        fs::write(&path, "[]").unwrap();
+        assert!(load_session(&path).is_err());
+    }
+
+    #[test]
+    fn backups_rotate_only_when_asked() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("session.json");
+        let backups: Vec<PathBuf> = backup_paths(&path).collect();
+
+        save_session(&path, &session(1), true).unwrap();
+        assert!(!backups[0].exists());
+        // Later saves in the same run leave the backups alone.
+        save_session(&path, &session(2), true).unwrap();
+        save_session(&path, &session(3), false).unwrap();
+        save_session(&path, &session(4), false).unwrap();
+        assert_eq!(rounds_played(&path), 4);
+        assert_eq!(rounds_played(&backups[0]), 1);
+        assert!(!backups[1].exists());
+
+        for rounds in 5..=8 {
+            save_session(&path, &session(rounds), true).unwrap();
+        }
+        let kept: Vec<u32> = backups.iter().map(|path| rounds_played(path)).collect();
+        assert_eq!(kept, [7, 6, 5]);
+        assert!(!dir.path().join("session.json.tmp").exists());
+    }
+
+    #[test]
+    fn damaged_sessions_are_not_rotated_into_the_backups() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("session.json");
+        let backups: Vec<PathBuf> = backup_paths(&path).collect();
+        save_session(&path, &session(1), true).unwrap();
+        save_session(&path, &session(2), true).unwrap();
+        fs::write(&path, "{ not json").unwrap();
+
+        save_session(&path, &session(3), true).unwrap();
+        assert_eq!(rounds_played(&path), 3);
+        assert_eq!(rounds_played(&backups[0]), 1);
+        assert!(!backups[1].exists());
+    }
+
+    #[test]
+    fn legacy_files_move_into_the_default_profile_once_one_is_opened() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        let session = r#"{"scoreboard": {"human": 41, "bot": 12, "rounds_played": 3}}"#;
+        fs::write(root.join("session.json"), session).unwrap();
+        fs::write(root.join("hands.jsonl"), "{\"hand\": 1}\n").unwrap();
+        fs::write(root.join("config.toml"), "bot_delay_ms = 0\n").unwrap();
+
+        // Listing profiles for the picker changes nothing on disk.
+        let (profiles, last) = Paths::profiles(Some(root)).unwrap();
+        assert_eq!(profiles, [DEFAULT_PROFILE]);
+        assert_eq!(last, None);
+        assert!(!root.join("profiles").exists());
+        assert!(root.join("session.json").exists());
+
+        let paths = Paths::new(DEFAULT_PROFILE, Some(root)).unwrap();
+        assert_eq!(
+            paths.session_file(),
+            root.join("profiles/default/session.json")
+        );
+        assert_eq!(fs::read_to_string(paths.session_file()).unwrap(), session);
+        assert_eq!(
+            fs::read_to_string(paths.archive_file()).unwrap(),
+            "{\"hand\": 1}\n"
+        );
+        assert!(!root.join("session.json").exists());
+        assert!(!root.join("hands.jsonl").exists());
+        // The shared config stays where it was.
+        assert_eq!(paths.config_file(), root.join("config.toml"));
+        assert!(root.join("config.toml").exists());
+        let loaded = load_session(paths.session_file()).unwrap().unwrap();
+        assert_eq!(loaded.data.scoreboard.human, 41);
+
+        let (profiles, last) = Paths::profiles(Some(root)).unwrap();
+        assert_eq!(profiles, [DEFAULT_PROFILE]);
+        assert_eq!(last.as_deref(), Some(DEFAULT_PROFILE));
+    }
+
+    #[test]
+    fn legacy_files_never_overwrite_a_default_profile() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        Paths::new("ana", Some(root)).unwrap();
+        let kept = root.join("profiles/default/session.json");
+        fs::write(&kept, "newer").unwrap();
+        fs::write(root.join("session.json"), "older").unwrap();
+
+        let (profiles, last) = Paths::profiles(Some(root)).unwrap();
+        assert_eq!(profiles, ["ana", DEFAULT_PROFILE]);
+        assert_eq!(last.as_deref(), Some("ana"));
+
+        Paths::new("ana", Some(root)).unwrap();
+        assert_eq!(fs::read_to_string(&kept).unwrap(), "newer");
+        assert_eq!(
+            fs::read_to_string(root.join("session.json")).unwrap(),
+            "older"
+        );
+    }
+
+    #[test]
+    fn listing_profiles_in_a_new_data_directory_creates_nothing() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("fresh");
+        let (profiles, last) = Paths::profiles(Some(&root)).unwrap();
+        assert!(profiles.is_empty());
+        assert_eq!(last, None);
+        assert!(!root.exists());
+    }
+}
@@ -1,5 +1,6 @@
 use std::collections::HashMap;
 
+use chrono::NaiveDate;
 use ratatui::{
     layout::{Alignment, Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
@@ -10,9 +11,16 @@ use ratatui::{
 
 use crate::{
     app::App,
+    bot::{BotDifficulty, TurnTrace},
     cards::Card,
-    game::{PlayerId, RoundEndReason, TurnPhase},
+    daily::{DailyResult, Leaderboard},
+    game::{DrawSource, PlayerId, RoundEndReason, TurnPhase},
+    keys::{Action, KeyBindings, Phase},
     meld::{analyze_hand, MeldKind},
+    profiles::ProfilePicker,
+    puzzle::Question,
+    settings::{self, SettingKind, SettingsScreen, SETTINGS},
+    stats::StatsReport,
 };
 
 const RULES_TEXT: &str = r"GIN RUMMY RULES
@@ -49,8 +57,7 @@ ENDING A ROUND
 
 WINNING THE MATCH
   - First player to reach 100 points wins
-
-Press Esc or ? to return to the game.";
+";
 
 pub fn draw(frame: &mut Frame<'_>, app: &App) {
     if app.show_help() {
@@ -72,9 +79,29 @@ pub fn draw(frame: &mut Frame<'_>, app: &App) {
     draw_opponent_hand(frame, app, layout[1]);
     draw_piles(frame, app, layout[2]);
     draw_player_section(frame, app, layout[3]);
+
+    if let Some(selected) = app.difficulty_picker() {
+        draw_difficulty_picker(frame, app, selected);
+    }
+
+    if let Some((trace, turn, total)) = app.trace_view() {
+        draw_trace_overlay(frame, app.config().keys(), trace, turn, total);
+    }
+
+    if let Some(report) = app.stats_view() {
+        draw_stats_overlay(frame, app.config().keys(), report);
+    }
+
+    if let Some((date, leaderboard)) = app.daily_view() {
+        draw_daily_overlay(frame, app.config().keys(), app.profile(), date, leaderboard);
+    }
+
+    if let Some(screen) = app.settings() {
+        draw_settings_overlay(frame, app, screen);
+    }
 }
 
-fn draw_help_overlay(frame: &mut Frame<'_>, _app: &App, area: Rect) {
+fn popup_area(area: Rect) -> Rect {
     let vertical = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
@@ -93,15 +120,428 @@ fn draw_help_overlay(frame: &mut Frame<'_>, _app: &App, area: Rect) {
         ])
         .split(vertical[1]);
 
-    let popup_area = middle[1];
+    middle[1]
+}
+
+fn draw_trace_overlay(
+    frame: &mut Frame<'_>,
+    keys: &KeyBindings,
+    trace: &TurnTrace,
+    turn: usize,
+    total: usize,
+) {
+    let popup_area = popup_area(frame.size());
+    frame.render_widget(Clear, popup_area);
+
+    let heading = Style::default()
+        .fg(Color::Yellow)
+        .add_modifier(Modifier::BOLD);
+    let mut lines = vec![
+        Line::from(format!("Bot turn {turn} of {total}")),
+        Line::from(""),
+    ];
+
+    if let Some(draw) = &trace.draw {
+        lines.push(Line::from(Span::styled("DRAW", heading)));
+        let source = match draw.choice {
+            DrawSource::Stock => "stock",
+            DrawSource::Discard => "discard",
+        };
+        match (draw.top_discard, draw.deadwood_with_discard) {
+            (Some(card), Some(with_discard)) => lines.push(Line::from(format!(
+                "  Top discard {card}: deadwood {} now, {with_discard} with it -> drew from {source}",
+                draw.current_deadwood
+            ))),
+            _ => lines.push(Line::from(format!(
+                "  No discard available (deadwood {}) -> drew from {source}",
+                draw.current_deadwood
+            ))),
+        }
+        lines.push(Line::from(""));
+    }
+
+    if let Some(discard) = &trace.discard {
+        lines.push(Line::from(Span::styled("DISCARD", heading)));
+        lines.push(Line::from("  Card  Deadwood  Score"));
+        for candidate in &discard.candidates {
+            let text = format!(
+                "  {:<5} {:>8}  {:>6.1}",
+                candidate.card.to_string(),
+                candidate.deadwood,
+                candidate.score
+            );
+            if candidate.card == discard.chosen {
+                lines.push(Line::from(Span::styled(
+                    format!("{text}  <- discarded"),
+                    Style::default()
+                        .fg(Color::Green)
+                        .add_modifier(Modifier::BOLD),
+                )));
+            } else {
+                lines.push(Line::from(text));
+            }
+        }
+        if discard.randomised {
+            lines.push(Line::from("  Discard chosen at random (Easy difficulty)."));
+        }
+        lines.push(Line::from(""));
+    }
+
+    if let Some(knock) = &trace.knock {
+        lines.push(Line::from(Span::styled("KNOCK", heading)));
+        for check in &knock.checks {
+            let (mark, color) = if check.passed {
+                ("[x]", Color::Green)
+            } else {
+                ("[ ]", Color::Red)
+            };
+            lines.push(Line::from(vec![
+                Span::styled(format!("  {mark} "), Style::default().fg(color)),
+                Span::raw(format!("{}: {}", check.label, check.detail)),
+            ]));
+        }
+        let decision = if knock.knock {
+            "knocked"
+        } else {
+            "kept playing"
+        };
+        lines.push(Line::from(format!(
+            "  Deadwood after discard {} -> {decision}",
+            knock.deadwood_after_discard
+        )));
+    } else if trace.discard.is_some() {
+        lines.push(Line::from(Span::styled("KNOCK", heading)));
+        lines.push(Line::from("  Not considered this turn."));
+    }
+
+    if !trace.notes.is_empty() {
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled("NOTES", heading)));
+        for note in &trace.notes {
+            lines.push(Line::from(format!("  {note}")));
+        }
+    }
+
+    let block = Block::default()
+        .title(format!(
+            "Bot reasoning ({}/{} turns, {}/Esc to close)",
+            keys.primary(Action::MoveLeft),
+            keys.primary(Action::MoveRight),
+            keys.primary(Action::BotReasoning)
+        ))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan));
+    let paragraph = Paragraph::new(lines)
+        .block(block)
+        .alignment(Alignment::Left)
+        .wrap(Wrap { trim: false });
+    frame.render_widget(paragraph, popup_area);
+}
+
+fn draw_stats_overlay(frame: &mut Frame<'_>, keys: &KeyBindings, report: &StatsReport) {
+    let popup_area = popup_area(frame.size());
+    frame.render_widget(Clear, popup_area);
+
+    let heading = Style::default()
+        .fg(Color::Yellow)
+        .add_modifier(Modifier::BOLD);
+    let mut lines = Vec::new();
+    if report.overall.hands == 0 {
+        lines.push(Line::from(
+            "No hands recorded yet. Finish a hand to start collecting stats.",
+        ));
+    } else {
+        let mut table = report.table().into_iter();
+        if let Some(header) = table.next() {
+            lines.push(Line::from(Span::styled(header, heading)));
+        }
+        lines.extend(table.map(Line::from));
+    }
+
+    let block = Block::default()
+        .title(format!(
+            "Statistics ({}/Esc to close)",
+            keys.primary(Action::Stats)
+        ))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan));
+    let paragraph = Paragraph::new(lines)
+        .block(block)
+        .alignment(Alignment::Left)
+        .wrap(Wrap { trim: false });
+    frame.render_widget(paragraph, popup_area);
+}
+
+fn draw_daily_overlay(
+    frame: &mut Frame<'_>,
+    keys: &KeyBindings,
+    profile: &str,
+    date: NaiveDate,
+    board: &Leaderboard,
+) {
+    let popup_area = popup_area(frame.size());
+    frame.render_widget(Clear, popup_area);
+
+    let heading = Style::default()
+        .fg(Color::Yellow)
+        .add_modifier(Modifier::BOLD);
+    let describe = |result: &DailyResult| {
+        if !result.finished {
+            return format!("left unfinished  ({})", result.difficulty.label());
+        }
+        let outcome = match result.winner {
+            Some(PlayerId::Human) => "won",
+            Some(PlayerId::Bot) => "lost",
+            None => "draw",
+        };
+        format!(
+            "{outcome:<5} {:>+4}  ({})",
+            result.points,
+            result.difficulty.label()
+        )
+    };
+
+    let mut lines = vec![Line::from(Span::styled(
+        format!("DAILY DEAL FOR {date}"),
+        heading,
+    ))];
+    match board.result(date, profile) {
+        Some(result) => lines.push(Line::from(format!(
+            "  You played today's deal: {}. A new deal arrives tomorrow.",
+            describe(result)
+        ))),
+        None => lines.push(Line::from(Span::styled(
+            format!(
+                "  Everyone gets the same cards today. Press {} to play your one attempt.",
+                keys.primary(Action::NextRound)
+            ),
+            Style::default().fg(Color::Green),
+        ))),
+    }
+    lines.push(Line::from(""));
+
+    let streaks = board.streaks(profile, date);
+    lines.push(Line::from(Span::styled("STREAKS", heading)));
+    lines.push(Line::from(format!(
+        "  Days in a row: {} (best {}) | Daily wins in a row: {}",
+        streaks.played, streaks.best_played, streaks.won
+    )));
+    lines.push(Line::from(""));
+
+    lines.push(Line::from(Span::styled("TODAY'S LEADERBOARD", heading)));
+    let standings = board.standings(date);
+    if standings.is_empty() {
+        lines.push(Line::from("  Nobody has played today's deal yet."));
+    }
+    for (rank, (name, result)) in standings.iter().enumerate() {
+        lines.push(Line::from(format!(
+            "  {}. {name:<16} {}",
+            rank + 1,
+            describe(result)
+        )));
+    }
+    lines.push(Line::from(""));
+
+    lines.push(Line::from(Span::styled("YOUR PAST RESULTS", heading)));
+    let history = board.history(profile);
+    if history.is_empty() {
+        lines.push(Line::from("  No daily deals played yet."));
+    }
+    for (day, result) in history.iter().take(14) {
+        lines.push(Line::from(format!("  {day}  {}", describe(result))));
+    }
+
+    let block = Block::default()
+        .title(format!(
+            "Daily deal ({}=play, {}/Esc to close)",
+            keys.primary(Action::NextRound),
+            keys.primary(Action::DailyDeal)
+        ))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan));
+    let paragraph = Paragraph::new(lines)
+        .block(block)
+        .alignment(Alignment::Left)
+        .wrap(Wrap { trim: false });
+    frame.render_widget(paragraph, popup_area);
+}
+
+fn draw_difficulty_picker(frame: &mut Frame<'_>, app: &App, selected: usize) {
+    let area = frame.size();
+    let height = (BotDifficulty::ALL.len() as u16 + 2).min(area.height);
+    let width = 32.min(area.width);
+    let popup_area = Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    };
+    frame.render_widget(Clear, popup_area);
+
+    let lines: Vec<Line> = BotDifficulty::ALL
+        .iter()
+        .enumerate()
+        .map(|(idx, difficulty)| {
+            let current = if *difficulty == app.bot_difficulty() {
+                " (current)"
+            } else {
+                ""
+            };
+            let label = format!("{}{current}", difficulty.label());
+            if idx == selected {
+                Line::from(Span::styled(
+                    format!("> {label}"),
+                    Style::default()
+                        .fg(Color::Green)
+                        .add_modifier(Modifier::BOLD),
+                ))
+            } else {
+                Line::from(format!("  {label}"))
+            }
+        })
+        .collect();
+
+    let paragraph = Paragraph::new(lines).block(
+        Block::default()
+            .title("Bot difficulty")
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Cyan)),
+    );
+    frame.render_widget(paragraph, popup_area);
+}
+
+fn draw_settings_overlay(frame: &mut Frame<'_>, app: &App, screen: &SettingsScreen) {
+    let area = frame.size();
+    let height = (SETTINGS.len() as u16 + 6).min(area.height);
+    let width = 60.min(area.width);
+    let popup_area = Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    };
     frame.render_widget(Clear, popup_area);
 
+    let config = app.config();
+    let mut lines: Vec<Line> = SETTINGS
+        .iter()
+        .enumerate()
+        .map(|(idx, setting)| {
+            let marker = if idx == screen.selected() { "> " } else { "  " };
+            let label = format!("{marker}{:<30}", setting.label);
+            let value = settings::display_value(config, *setting);
+            let value_style = match setting.kind {
+                SettingKind::Color(suit) => Style::default().fg(config.suit_color(suit)),
+                _ => Style::default(),
+            };
+            let label_style = if idx == screen.selected() {
+                Style::default()
+                    .fg(Color::Green)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default()
+            };
+            Line::from(vec![
+                Span::styled(label, label_style),
+                Span::styled(format!("< {value} >"), value_style),
+            ])
+        })
+        .collect();
+    let setting = screen.setting();
+    let source = match app.config_source(setting.key) {
+        Some(source) if screen.changed().contains(&setting.key) => {
+            format!("Changed (was from {source})")
+        }
+        Some(source) => format!("From {source}"),
+        None => "Changed".to_string(),
+    };
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        source,
+        Style::default().fg(Color::DarkGray),
+    )));
+
+    let paragraph = Paragraph::new(lines)
+        .block(
+            Block::default()
+                .title("Settings")
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Cyan)),
+        )
+        .wrap(Wrap { trim: false });
+    frame.render_widget(paragraph, popup_area);
+}
+
+/// Startup screen for choosing or creating a profile, shown before the game loads.
+pub fn draw_profile_picker(frame: &mut Frame<'_>, picker: &ProfilePicker) {
+    let area = frame.size();
+    let height = (picker.profiles().len() as u16 + 6).min(area.height);
+    let width = 48.min(area.width);
+    let popup_area = Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    };
+    frame.render_widget(Clear, popup_area);
+
+    let mut lines: Vec<Line> = picker
+        .profiles()
+        .iter()
+        .enumerate()
+        .map(|(idx, name)| {
+            if idx == picker.selected() && picker.new_name().is_none() {
+                Line::from(Span::styled(
+                    format!("> {name}"),
+                    Style::default()
+                        .fg(Color::Green)
+                        .add_modifier(Modifier::BOLD),
+                ))
+            } else {
+                Line::from(format!("  {name}"))
+            }
+        })
+        .collect();
+    lines.push(Line::from(""));
+    match picker.new_name() {
+        Some(name) => lines.push(Line::from(Span::styled(
+            format!("New profile: {name}_"),
+            Style::default().fg(Color::Yellow),
+        ))),
+        None => lines.push(Line::from("↑/↓ choose, Enter=play, N=new, Q=quit")),
+    }
+    if let Some(err) = picker.error() {
+        lines.push(Line::from(Span::styled(
+            err.to_string(),
+            Style::default().fg(Color::Red),
+        )));
+    } else if picker.new_name().is_some() {
+        lines.push(Line::from("Enter=create, Esc=cancel"));
+    }
+
+    let paragraph = Paragraph::new(lines).block(
+        Block::default()
+            .title("Choose a profile")
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Cyan)),
+    );
+    frame.render_widget(paragraph, popup_area);
+}
+
+fn draw_help_overlay(frame: &mut Frame<'_>, app: &App, area: Rect) {
+    let popup_area = popup_area(area);
+    frame.render_widget(Clear, popup_area);
+
+    let keys = app.config().keys();
     let block = Block::default()
-        .title("Gin Rummy Reference (?/Esc to close)")
+        .title(format!(
+            "Gin Rummy Reference ({}/Esc to close)",
+            keys.primary(Action::Rules)
+        ))
         .borders(Borders::ALL)
         .border_style(Style::default().fg(Color::Cyan));
 
-    let paragraph = Paragraph::new(RULES_TEXT)
+    let paragraph = Paragraph::new(rules_text(keys))
         .block(block)
         .alignment(Alignment::Left)
         .wrap(Wrap { trim: false });
@@ -119,14 +559,25 @@ fn draw_header(frame: &mut Frame<'_>, app: &App, area: Rect) {
         )),
         Span::raw(" "),
         Span::styled(
-            "Rules: press ?",
+            format!(
+                "Rules: press {}",
+                app.config().keys().primary(Action::Rules)
+            ),
             Style::default()
                 .fg(Color::Gray)
                 .add_modifier(Modifier::ITALIC),
         ),
     ])];
+    let bot_level = match app.bot_level() {
+        Some(level) => format!(
+            "{} (level {:.0}%)",
+            app.bot_difficulty().label(),
+            level * 100.0
+        ),
+        None => app.bot_difficulty().label().to_string(),
+    };
     lines.push(Line::from(format!(
-        "Hands: You {} | Bot {} | Draws {}",
+        "Hands: You {} | Bot {} | Draws {} | Bot level: {bot_level}",
         scoreboard.human_hands_won, scoreboard.bot_hands_won, scoreboard.draws
     )));
     lines.push(Line::from(format!("Phase: {phase_text}")));
@@ -144,7 +595,7 @@ fn draw_header(frame: &mut Frame<'_>, app: &App, area: Rect) {
         )));
     }
 
-    if let Some(round) = app.game.pending_round.as_ref() {
+    if let Some(round) = app.board().pending_round.as_ref() {
         if let RoundEndReason::Knock {
             knocker, laid_off, ..
         } = &round.reason
@@ -164,15 +615,19 @@ fn draw_header(frame: &mut Frame<'_>, app: &App, area: Rect) {
     lines.push(Line::from(instructions));
 
     let paragraph = Paragraph::new(lines)
-        .block(Block::default().title("Status").borders(Borders::ALL))
+        .block(
+            Block::default()
+                .title(format!("Status ({})", app.profile()))
+                .borders(Borders::ALL),
+        )
         .alignment(Alignment::Left);
     frame.render_widget(paragraph, area);
 }
 
 fn draw_opponent_hand(frame: &mut Frame<'_>, app: &App, area: Rect) {
     let mut layoff_cards: Vec<Card> = Vec::new();
-    let reveal_cards = if app.game.phase == TurnPhase::RoundOver {
-        app.game.pending_round.as_ref().map(|round| {
+    let reveal_cards = if app.board().phase == TurnPhase::RoundOver {
+        app.board().pending_round.as_ref().map(|round| {
             if let RoundEndReason::Knock {
                 knocker, laid_off, ..
             } = &round.reason
@@ -183,6 +638,8 @@ fn draw_opponent_hand(frame: &mut Frame<'_>, app: &App, area: Rect) {
             }
             round.bot_hand.as_slice()
         })
+    } else if app.replay().is_some_and(|replay| replay.face_up()) {
+        Some(app.board().bot.hand.as_slice())
     } else {
         None
     };
@@ -214,7 +671,7 @@ fn draw_opponent_hand(frame: &mut Frame<'_>, app: &App, area: Rect) {
         }
         spans
     } else {
-        app.game
+        app.board()
             .bot
             .hand
             .iter()
@@ -222,8 +679,27 @@ fn draw_opponent_hand(frame: &mut Frame<'_>, app: &App, area: Rect) {
             .collect()
     };
     let line = Line::from(spans);
+    let title = match app.replay() {
+        None if app.puzzle_run().is_some() => format!(
+            "Opponent (known pickups: {})",
+            format_card_list(&app.board().known_cards(PlayerId::Bot))
+        ),
+        Some(replay) => format!(
+            "Opponent ({}, {})",
+            replay.record().bot,
+            replay
+                .record()
+                .difficulty
+                .map_or("unknown difficulty", |difficulty| difficulty.label())
+        ),
+        None => format!(
+            "Opponent ({}, {})",
+            app.bot_name(),
+            app.bot_difficulty().label()
+        ),
+    };
     let paragraph = Paragraph::new(line)
-        .block(Block::default().title("Opponent").borders(Borders::ALL))
+        .block(Block::default().title(title).borders(Borders::ALL))
         .alignment(Alignment::Center);
     frame.render_widget(paragraph, area);
 }
@@ -234,7 +710,7 @@ fn draw_piles(frame: &mut Frame<'_>, app: &App, area: Rect) {
         .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
         .split(area);
 
-    let stock_size = app.game.stock.len();
+    let stock_size = app.board().stock.len();
     let stock_para = Paragraph::new(vec![
         Line::from("Stock pile"),
         Line::from(Span::styled(
@@ -249,10 +725,10 @@ fn draw_piles(frame: &mut Frame<'_>, app: &App, area: Rect) {
     let discard_para = Paragraph::new(vec![
         Line::from("Discard pile"),
         Line::from(Span::styled(
-            format!("Cards: {}", app.game.discard.len()),
+            format!("Cards: {}", app.board().discard.len()),
             Style::default().fg(Color::Yellow),
         )),
-        if let Some(card) = app.game.discard.last() {
+        if let Some(card) = app.board().discard.last() {
             Line::from(vec![
                 Span::raw("Top: "),
                 Span::styled(card.rank.short_name().to_string(), Style::default()),
@@ -275,7 +751,16 @@ fn draw_piles(frame: &mut Frame<'_>, app: &App, area: Rect) {
 fn draw_player_section(frame: &mut Frame<'_>, app: &App, area: Rect) {
     let layout = Layout::default()
         .direction(Direction::Vertical)
-        .constraints([Constraint::Min(3), Constraint::Length(3)])
+        .constraints([
+            Constraint::Min(3),
+            Constraint::Length(if app.puzzle_run().is_some() {
+                6
+            } else if app.replay().is_some() {
+                4
+            } else {
+                3
+            }),
+        ])
         .split(area);
 
     draw_player_hand(frame, app, layout[0]);
@@ -284,18 +769,18 @@ fn draw_player_section(frame: &mut Frame<'_>, app: &App, area: Rect) {
 
 fn draw_player_hand(frame: &mut Frame<'_>, app: &App, area: Rect) {
     let recent_draw = app.recent_draw();
-    let hand_slice = if app.game.phase == TurnPhase::RoundOver {
-        app.game
+    let hand_slice = if app.board().phase == TurnPhase::RoundOver {
+        app.board()
             .pending_round
             .as_ref()
             .map(|round| round.human_hand.as_slice())
-            .unwrap_or_else(|| app.game.human.hand.as_slice())
+            .unwrap_or_else(|| app.board().human.hand.as_slice())
     } else {
-        app.game.human.hand.as_slice()
+        app.board().human.hand.as_slice()
     };
 
-    let layoff_cards: Vec<Card> = if app.game.phase == TurnPhase::RoundOver {
-        app.game
+    let layoff_cards: Vec<Card> = if app.board().phase == TurnPhase::RoundOver {
+        app.board()
             .pending_round
             .as_ref()
             .and_then(|round| match &round.reason {
@@ -336,9 +821,10 @@ fn draw_player_hand(frame: &mut Frame<'_>, app: &App, area: Rect) {
             spans.push(Span::raw(" "));
         }
 
-        let is_selected = idx == app.selection;
+        let is_selected = app.replay().is_none() && idx == app.selection;
         let was_laid_off = layoff_cards.contains(card);
         let is_recent = Some(*card) == recent_draw;
+        let is_hinted = app.hinted_discard() == Some(idx);
         if is_selected {
             spans.push(Span::styled("[", selection_style));
         }
@@ -362,6 +848,9 @@ fn draw_player_hand(frame: &mut Frame<'_>, app: &App, area: Rect) {
         if is_recent {
             rank_style = rank_style.bg(Color::DarkGray);
         }
+        if is_hinted {
+            rank_style = rank_style.add_modifier(Modifier::REVERSED);
+        }
         if was_laid_off {
             rank_style = rank_style.add_modifier(Modifier::UNDERLINED);
         }
@@ -377,6 +866,9 @@ fn draw_player_hand(frame: &mut Frame<'_>, app: &App, area: Rect) {
         if is_recent {
             suit_style = suit_style.bg(Color::DarkGray);
         }
+        if is_hinted {
+            suit_style = suit_style.add_modifier(Modifier::REVERSED);
+        }
         if was_laid_off {
             suit_style = suit_style.add_modifier(Modifier::UNDERLINED);
         }
@@ -409,27 +901,73 @@ fn draw_player_hand(frame: &mut Frame<'_>, app: &App, area: Rect) {
 }
 fn draw_player_details(frame: &mut Frame<'_>, app: &App, area: Rect) {
     let mut lines = Vec::new();
-    if let TurnPhase::AwaitDiscard = app.game.phase {
+    if let Some((run, progress)) = app.puzzle_run() {
+        let puzzle = run.puzzle();
+        let mut heading = format!(
+            "Puzzle {} of {}: {}",
+            run.number(),
+            run.puzzles().len(),
+            puzzle.name
+        );
+        if let Some(description) = &puzzle.description {
+            heading.push_str(&format!(" - {description}"));
+        }
+        lines.push(Line::from(Span::styled(
+            heading,
+            Style::default().fg(Color::Yellow),
+        )));
+        let score = progress.score(&puzzle.name);
+        lines.push(Line::from(format!(
+            "Solved {} of {} attempt(s) at this puzzle{}",
+            score.solved,
+            score.attempts,
+            if score.first_try && score.attempts > 0 {
+                ", first try"
+            } else {
+                ""
+            }
+        )));
+    }
+    if app.board().phase == TurnPhase::AwaitDiscard && app.replay().is_none() {
         let knock_status = if app.knock_intent() { "ON" } else { "OFF" };
         lines.push(Line::from(format!("Knock intent: {knock_status}")));
     }
 
-    let hand_slice = if app.game.phase == TurnPhase::RoundOver {
-        app.game
+    let hand_slice = if app.board().phase == TurnPhase::RoundOver {
+        app.board()
             .pending_round
             .as_ref()
             .map(|round| round.human_hand.as_slice())
-            .unwrap_or_else(|| app.game.human.hand.as_slice())
+            .unwrap_or_else(|| app.board().human.hand.as_slice())
     } else {
-        app.game.human.hand.as_slice()
+        app.board().human.hand.as_slice()
     };
 
-    let analysis = analyze_hand(hand_slice);
-    lines.push(Line::from(format!(
-        "Deadwood: {} ({} cards)",
-        analysis.deadwood_value,
-        analysis.deadwood.len()
-    )));
+    if let Some(replay) = app.replay() {
+        let history = |player| {
+            replay
+                .deadwood_history(player)
+                .iter()
+                .map(|value| value.to_string())
+                .collect::<Vec<_>>()
+                .join(" ")
+        };
+        lines.push(Line::from(format!(
+            "Deadwood, you: {}",
+            history(PlayerId::Human)
+        )));
+        lines.push(Line::from(format!(
+            "Deadwood, bot: {}",
+            history(PlayerId::Bot)
+        )));
+    } else {
+        let analysis = analyze_hand(hand_slice);
+        lines.push(Line::from(format!(
+            "Deadwood: {} ({} cards)",
+            analysis.deadwood_value,
+            analysis.deadwood.len()
+        )));
+    }
 
     let paragraph = Paragraph::new(lines)
         .block(Block::default().title("Details").borders(Borders::ALL))
@@ -438,20 +976,62 @@ fn draw_player_details(frame: &mut Frame<'_>, app: &App, area: Rect) {
 }
 
 fn phase_description(app: &App) -> String {
+    let keys = app.config().keys();
     if app.exit_prompt_active() {
         return "Exit requested: choose Y to save, N to quit without saving, Esc to cancel."
             .to_string();
     }
     if app.show_help() {
-        return "Rules reference open. Press Esc or ? to close.".to_string();
+        return format!(
+            "Rules reference open. Press Esc or {} to close.",
+            keys.primary(Action::Rules)
+        );
+    }
+    if app.difficulty_picker().is_some() {
+        return "Choosing bot difficulty for the next round.".to_string();
+    }
+    if app.settings().is_some() {
+        return "Editing settings. Changes apply now; save to keep them.".to_string();
     }
-    match app.game.phase {
-        TurnPhase::RoundOver => "Round complete. Press Enter to continue.".to_string(),
-        TurnPhase::AwaitDraw => match app.game.current_player {
-            PlayerId::Human => "Your turn: draw from stock [S] or discard [D].".to_string(),
+    if app.trace_view().is_some() {
+        return "Reviewing the bot's reasoning.".to_string();
+    }
+    if app.stats_view().is_some() {
+        return "Reviewing your statistics.".to_string();
+    }
+    if app.daily_view().is_some() {
+        return "Viewing the daily deal.".to_string();
+    }
+    if let Some((run, _)) = app.puzzle_run() {
+        let task = match (run.verdict(), run.puzzle().question()) {
+            (Some(verdict), _) if verdict.correct => "solved",
+            (Some(_), _) => "answered",
+            (None, Question::Draw) => "find the best draw",
+            (None, Question::Discard) => "find the best discard and decide whether to knock",
+        };
+        return format!(
+            "Puzzle {} of {}: {task}.",
+            run.number(),
+            run.puzzles().len()
+        );
+    }
+    if let Some(replay) = app.replay() {
+        return format!("Replaying {}. {}", replay.title(), replay.describe_step());
+    }
+    match app.board().phase {
+        TurnPhase::RoundOver => format!(
+            "Round complete. Press {} to continue.",
+            keys.primary(Action::NextRound)
+        ),
+        TurnPhase::AwaitDraw => match app.board().current_player {
+            PlayerId::Human => format!(
+                "Your turn: draw from stock [{}] or discard [{}].",
+                keys.primary(Action::DrawStock),
+                keys.primary(Action::DrawDiscard)
+            ),
             PlayerId::Bot => "Bot drawing...".to_string(),
         },
-        TurnPhase::AwaitDiscard => match app.game.current_player {
+        TurnPhase::AwaitDiscard => match app.board().current_player {
             PlayerId::Human => "Your turn: choose a card to discard.".to_string(),
             PlayerId::Bot => "Bot deciding on a discard...".to_string(),
         },
@@ -459,19 +1039,173 @@ fn phase_description(app: &App) -> String {
 }
 
 fn instructions_for_phase(app: &App) -> String {
+    let keys = app.config().keys();
     if app.exit_prompt_active() {
         return "Controls: Y=save & quit, N=quit without saving, Esc=cancel.".to_string();
     }
     if app.show_help() {
-        return "Controls: Esc/?=close rules.".to_string();
+        return format!("Controls: Esc/{}=close rules.", keys.primary(Action::Rules));
+    }
+    if app.difficulty_picker().is_some() {
+        return format!(
+            "Controls: {}/{} choose, {}=confirm, {}/Esc=cancel.",
+            keys.primary(Action::MoveUp),
+            keys.primary(Action::MoveDown),
+            keys.primary(Action::Discard),
+            keys.primary(Action::BotDifficulty)
+        );
+    }
+    if app.settings().is_some() {
+        return format!(
+            "Controls: {}/{} choose, {}/{} change, {}=save to config.toml, {}/Esc=cancel.",
+            keys.primary(Action::MoveUp),
+            keys.primary(Action::MoveDown),
+            keys.primary(Action::MoveLeft),
+            keys.primary(Action::MoveRight),
+            keys.primary(Action::Discard),
+            keys.primary(Action::Settings)
+        );
+    }
+    if app.trace_view().is_some() {
+        return format!(
+            "Controls: {}/{} previous/next bot turn, {}/Esc=close.",
+            keys.primary(Action::MoveLeft),
+            keys.primary(Action::MoveRight),
+            keys.primary(Action::BotReasoning)
+        );
+    }
+    if app.stats_view().is_some() {
+        return format!(
+            "Controls: {}/Esc=close statistics.",
+            keys.primary(Action::Stats)
+        );
+    }
+    if app.daily_view().is_some() {
+        return format!(
+            "Controls: {}=play today's deal, {}/Esc=close.",
+            keys.primary(Action::NextRound),
+            keys.primary(Action::DailyDeal)
+        );
     }
-    match app.game.phase {
-        TurnPhase::RoundOver => "Controls: Enter/N=next round, ?=rules, Q=quit.".to_string(),
-        TurnPhase::AwaitDraw => "Controls: S=stock, D=discard, ?=rules, Q=quit.".to_string(),
-        TurnPhase::AwaitDiscard => {
-            "Controls: ←/→ move, Enter=discard, K=toggle knock, ?=rules, Q=quit.".to_string()
+    if let Some((run, _)) = app.puzzle_run() {
+        let leave = format!("{}/Esc=leave puzzles", keys.primary(Action::Puzzles));
+        return match (run.verdict(), run.puzzle().question()) {
+            (Some(_), _) => format!(
+                "Controls: {}=next puzzle, {}=retry, {leave}, {}.",
+                keys.label(Action::NextRound),
+                keys.label(Action::Replay),
+                keys.controls(&[Action::Rules, Action::Quit])
+            ),
+            (None, Question::Draw) => format!(
+                "Controls: {}, {leave}, {}.",
+                keys.controls(&[Action::DrawStock, Action::DrawDiscard]),
+                keys.controls(&[Action::Rules, Action::Quit])
+            ),
+            (None, Question::Discard) => format!(
+                "Controls: {}, {leave}, {}.",
+                keys.controls(&[
+                    Action::MoveLeft,
+                    Action::MoveRight,
+                    Action::Discard,
+                    Action::ToggleKnock
+                ]),
+                keys.controls(&[Action::Rules, Action::Quit])
+            ),
+        };
+    }
+    if app.replay().is_some() {
+        return format!(
+            "Controls: {}/{} step, {}/{} first/last, {}/{} previous/next hand, {}, {}/Esc=close, {}.",
+            keys.primary(Action::ReplayBack),
+            keys.primary(Action::ReplayForward),
+            keys.primary(Action::ReplayFirst),
+            keys.primary(Action::ReplayLast),
+            keys.primary(Action::ReplayPreviousHand),
+            keys.primary(Action::ReplayNextHand),
+            keys.controls(&[Action::ReplayShowBot]),
+            keys.primary(Action::Replay),
+            keys.controls(&[Action::Rules, Action::Quit])
+        );
+    }
+    if app.board().phase != TurnPhase::RoundOver && app.board().current_player == PlayerId::Bot {
+        return format!(
+            "Controls: any key=skip the bot's turn, {}.",
+            keys.controls(&[Action::Settings, Action::Rules, Action::Quit])
+        );
+    }
+    let actions: &[Action] = match app.board().phase {
+        TurnPhase::RoundOver => &[
+            Action::NextRound,
+            Action::BotReasoning,
+            Action::Replay,
+            Action::Stats,
+            Action::DailyDeal,
+            Action::Puzzles,
+            Action::Export,
+            Action::BotDifficulty,
+            Action::Settings,
+            Action::Rules,
+            Action::Quit,
+        ],
+        TurnPhase::AwaitDraw => &[
+            Action::DrawStock,
+            Action::DrawDiscard,
+            Action::Hint,
+            Action::Settings,
+            Action::Rules,
+            Action::Quit,
+        ],
+        TurnPhase::AwaitDiscard => &[
+            Action::MoveLeft,
+            Action::MoveRight,
+            Action::Discard,
+            Action::ToggleKnock,
+            Action::Hint,
+            Action::Settings,
+            Action::Rules,
+            Action::Quit,
+        ],
+    };
+    format!("Controls: {}.", keys.controls(actions))
+}
+
+/// The rules reference followed by the current key bindings.
+fn rules_text(keys: &KeyBindings) -> String {
+    let mut text = RULES_TEXT.to_string();
+    text.push_str("\nCONTROLS\n");
+    let sections = [
+        ("Drawing", Some(Phase::Draw)),
+        ("Discarding", Some(Phase::Discard)),
+        ("After a round", Some(Phase::RoundOver)),
+        ("In a replay", Some(Phase::Replay)),
+        ("Any time", None),
+    ];
+    let any_time = |action: Action| {
+        Phase::HAND
+            .iter()
+            .all(|phase| action.phases().contains(phase))
+    };
+    for (heading, phase) in sections {
+        text.push_str(&format!("  {heading}\n"));
+        for action in Action::ALL {
+            let listed = match phase {
+                Some(phase) => action.phases().contains(&phase) && !any_time(action),
+                None => any_time(action),
+            };
+            if listed {
+                text.push_str(&format!(
+                    "    {:<12} {}\n",
+                    keys.label(action),
+                    action.description()
+                ));
+            }
         }
     }
+    text.push_str(&format!(
+        "\nPress Esc or {} to return to the game.",
+        keys.primary(Action::Rules)
+    ));
+    text
 }
 
 fn format_card_list(cards: &[Card]) -> String {